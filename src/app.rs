@@ -1,8 +1,20 @@
+use crate::history::History;
+use crate::query::Query;
+use crate::quick_add;
+use crate::reminder::{ReminderScheduler, ReminderSettings};
+use crate::schema;
+use crate::smart_list::{SmartList, SmartLists};
+use crate::storage::{self, Backup};
+use crate::template::{TaskTemplate, TaskTemplates};
 use crate::theme::{Theme, ThemePresets};
-use crate::todo::{Emoji, Priority, SubTask, Todo, TodoList};
+use crate::todo::{Emoji, Priority, Snooze, SubTask, Todo, TodoList};
+use crate::workspace::Workspaces;
+use chrono::{DateTime, Local, NaiveDate};
 use egui::FontId;
 use serde::{Serialize, Deserialize};
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
 
 /// 应用程序的主视图部分
 #[derive(Debug, PartialEq, Clone)]
@@ -16,7 +28,6 @@ pub enum View {
     /// 设置视图
     Settings,
     /// 统计视图
-    #[allow(dead_code)]
     Stats,
     /// 标签管理视图
     Tags,
@@ -25,6 +36,45 @@ pub enum View {
     About,
     /// Markdown预览视图
     MarkdownViewer,
+    /// 时间报表视图
+    TimeReport,
+    /// 回收站视图
+    Trash,
+    /// 看板视图
+    Board,
+    /// 日历视图
+    Calendar,
+}
+
+/// 日历显示方式
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CalendarMode {
+    /// 按月显示
+    Month,
+    /// 按周显示
+    Week,
+}
+
+/// 看板分组方式
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum BoardGrouping {
+    /// 按工作流状态分组
+    Status,
+    /// 按优先级分组
+    Priority,
+    /// 按标签分组
+    Tag,
+}
+
+impl BoardGrouping {
+    /// 获取分组方式的显示名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BoardGrouping::Status => "状态",
+            BoardGrouping::Priority => "优先级",
+            BoardGrouping::Tag => "标签",
+        }
+    }
 }
 
 /// 应用程序视觉风格
@@ -54,6 +104,44 @@ pub struct RodoApp {
     pub view: View,
     /// 任务列表
     pub todo_list: TodoList,
+    /// 任务文件无法读取时的信息，处理之前不保存任务
+    pub load_failure: Option<LoadFailure>,
+    /// 工作区集合
+    pub workspaces: Workspaces,
+    /// 工作区名称输入
+    pub workspace_name_input: String,
+    /// 工作流状态名称输入
+    pub status_name_input: String,
+    /// 快速添加输入
+    pub quick_add_input: String,
+    /// 任务模板
+    pub templates: TaskTemplates,
+    /// 设置页面中尚未保存的模板修改
+    pub template_draft: Option<TaskTemplates>,
+    /// 设置页面中模板的新子任务输入
+    pub template_subtask_input: String,
+    /// 设置页面中选择添加下级子任务的模板子任务
+    pub template_subtask_parent: Option<String>,
+    /// 当前工作区的智能列表
+    pub smart_lists: SmartLists,
+    /// 智能列表名称输入
+    pub smart_list_name_input: String,
+    /// 搜索框输入
+    pub search_input: String,
+    /// 搜索语法错误
+    pub search_error: Option<String>,
+    /// 列表是否处于多选模式
+    pub selection_mode: bool,
+    /// 多选模式下选中的任务ID
+    pub selected_todos: HashSet<String>,
+    /// 上次点选的任务，用于 Shift 连续选择
+    pub selection_anchor: Option<String>,
+    /// 批量操作的标签输入
+    pub bulk_tag_input: String,
+    /// 批量设置截止时间的输入
+    pub bulk_due_input: String,
+    /// 推迟菜单中自选的推迟时间
+    pub snooze_pick: DateTime<Local>,
     /// 应用主题
     pub theme: Theme,
     /// 主题预设集合
@@ -68,6 +156,26 @@ pub struct RodoApp {
     pub temp_input: String,
     /// 临时标签输入
     pub temp_tag_input: String,
+    /// 新子任务要添加到的父级子任务ID（None表示添加到顶层）
+    pub subtask_parent_id: Option<String>,
+    /// 描述编辑器是否显示Markdown预览
+    pub description_preview: bool,
+    /// 正在拖动排序的任务ID
+    pub dragging_todo_id: Option<String>,
+    /// 编辑页面中正在拖动的子任务ID
+    pub dragging_subtask_id: Option<String>,
+    /// 看板分组方式
+    pub board_grouping: BoardGrouping,
+    /// 看板中正在拖动的卡片所在的列
+    pub board_drag_source: Option<String>,
+    /// 日历显示方式
+    pub calendar_mode: CalendarMode,
+    /// 日历当前显示的月或周中的某一天
+    pub calendar_date: NaiveDate,
+    /// 统计页面的时间范围（天）
+    pub stats_range_days: i64,
+    /// 统计页面是否按周显示完成数量
+    pub stats_weekly: bool,
     /// 是否已修改（用于保存）
     pub modified: bool,
     /// 显示确认对话框
@@ -84,6 +192,77 @@ pub struct RodoApp {
     pub current_markdown_directory: Option<String>,
     /// 当前目录中的Markdown文件列表
     pub markdown_files: Vec<String>,
+    /// 提醒设置
+    pub reminder_settings: ReminderSettings,
+    /// 设置页面中正在编辑的提醒命令
+    pub reminder_command_input: String,
+    /// 后台提醒调度器
+    pub reminder_scheduler: ReminderScheduler,
+    /// 应用内提示
+    pub toasts: Vec<Toast>,
+    /// 撤销/重做历史
+    pub history: History,
+}
+
+/// 任务文件无法读取时的信息，用于提示从备份恢复
+pub struct LoadFailure {
+    /// 无法读取的任务文件名
+    pub file_name: String,
+    /// 错误信息
+    pub error: String,
+    /// 可用的备份，最新的在前
+    pub backups: Vec<Backup>,
+}
+
+impl LoadFailure {
+    fn new(file_name: &str, error: String) -> Self {
+        let backups = TodoList::data_file_path(file_name)
+            .map(|path| storage::list_backups(&path))
+            .unwrap_or_default();
+        Self {
+            file_name: file_name.to_string(),
+            error,
+            backups,
+        }
+    }
+}
+
+/// 应用内提示的操作按钮
+#[derive(Debug, Clone, PartialEq)]
+pub enum ToastAction {
+    /// 打开关联的任务
+    OpenTodo(String),
+    /// 撤销刚才的操作
+    Undo,
+}
+
+/// 应用内提示
+#[derive(Debug, Clone)]
+pub struct Toast {
+    /// 提示内容
+    pub message: String,
+    /// 操作按钮
+    pub action: Option<ToastAction>,
+    /// 显示时间
+    pub created_at: std::time::Instant,
+}
+
+impl Toast {
+    /// 提示显示的时长
+    pub const LIFETIME: std::time::Duration = std::time::Duration::from_secs(10);
+    
+    pub fn new(message: String, action: Option<ToastAction>) -> Self {
+        Self {
+            message,
+            action,
+            created_at: std::time::Instant::now(),
+        }
+    }
+    
+    /// 检查提示是否已过期
+    pub fn is_expired(&self) -> bool {
+        self.created_at.elapsed() >= Self::LIFETIME
+    }
 }
 
 /// 确认对话框动作类型
@@ -98,6 +277,15 @@ pub enum ConfirmationAction {
     DeleteTag(String),
     ResetApp,
     DeleteThemePreset(String),
+    CompleteBlocked(String, String),
+    DeleteWorkspace(String),
+    PurgeTrashedTodo(String),
+    EmptyTrash,
+    DeleteTodos(Vec<String>),
+    /// 将任务转为另一个任务的子任务：(任务ID, 目标任务ID)
+    DemoteTodo(String, String),
+    /// 仅显示提示信息，只有确定按钮，确定时不执行任何操作
+    Notice,
 }
 
 /// Markdown目录信息
@@ -118,18 +306,43 @@ struct AppStyleConfig {
 impl Default for RodoApp {
     fn default() -> Self {
         // 加载应用状态
-        let todo_list = TodoList::load();
+        let (workspaces, workspaces_notice) = Workspaces::load();
+        let (todo_list, load_failure) = Self::load_todo_list(&workspaces.active().file_name());
+        let (smart_lists, smart_lists_notice) = SmartLists::load(&workspaces.active().smart_lists_file_name());
+        let (templates, templates_notice) = TaskTemplates::load();
         let theme = Theme::default();
         let theme_presets = ThemePresets::default();
         let app_style = Self::load_app_style().unwrap_or_default();
+        let reminder_settings = ReminderSettings::load();
+        let reminder_scheduler = ReminderScheduler::start(reminder_settings.notifiers(), None);
+        let history = History::new(&todo_list);
         
         // 加载上次打开的Markdown目录信息
         let (markdown_directory, markdown_files, current_file, current_content) = 
             Self::load_markdown_directory_info().unwrap_or_else(|_| (None, Vec::new(), None, None));
         
-        Self {
+        let mut app = Self {
             view: View::List,
             todo_list,
+            load_failure,
+            workspaces,
+            workspace_name_input: String::new(),
+            status_name_input: String::new(),
+            quick_add_input: String::new(),
+            templates,
+            template_draft: None,
+            template_subtask_input: String::new(),
+            template_subtask_parent: None,
+            smart_lists,
+            smart_list_name_input: String::new(),
+            search_input: String::new(),
+            search_error: None,
+            selection_mode: false,
+            selected_todos: HashSet::new(),
+            selection_anchor: None,
+            bulk_tag_input: String::new(),
+            bulk_due_input: String::new(),
+            snooze_pick: Snooze::Tomorrow.until(Local::now()).unwrap_or_else(Local::now),
             theme,
             theme_presets,
             app_style,
@@ -137,6 +350,16 @@ impl Default for RodoApp {
             new_todo: Todo::new(String::new()),
             temp_input: String::new(),
             temp_tag_input: String::new(),
+            subtask_parent_id: None,
+            description_preview: false,
+            dragging_todo_id: None,
+            dragging_subtask_id: None,
+            board_grouping: BoardGrouping::Status,
+            board_drag_source: None,
+            calendar_mode: CalendarMode::Month,
+            calendar_date: Local::now().date_naive(),
+            stats_range_days: 30,
+            stats_weekly: false,
             modified: false,
             show_confirmation: false,
             confirmation_message: String::new(),
@@ -145,7 +368,17 @@ impl Default for RodoApp {
             markdown_content: current_content.unwrap_or_default(),
             current_markdown_directory: markdown_directory,
             markdown_files,
-        }
+            reminder_command_input: reminder_settings.command.clone(),
+            reminder_settings,
+            reminder_scheduler,
+            toasts: Vec::new(),
+            history,
+        };
+        
+        // 安排提醒，错过的提醒会立即触发
+        app.refresh_reminder_schedule();
+        app.report_load_notices(&[workspaces_notice, smart_lists_notice, templates_notice]);
+        app
     }
 }
 
@@ -178,10 +411,16 @@ impl RodoApp {
         // 应用样式
         ctx.set_style(style);
         
-        // 加载应用状态，包括任务和主题
-        let todo_list = TodoList::load();
+        // 加载应用状态，包括工作区、任务和主题
+        let (workspaces, workspaces_notice) = Workspaces::load();
+        let (todo_list, load_failure) = Self::load_todo_list(&workspaces.active().file_name());
+        let (smart_lists, smart_lists_notice) = SmartLists::load(&workspaces.active().smart_lists_file_name());
+        let (templates, templates_notice) = TaskTemplates::load();
         let theme = Theme::load();
         let theme_presets = ThemePresets::load();
+        let reminder_settings = ReminderSettings::load();
+        let reminder_scheduler = ReminderScheduler::start(reminder_settings.notifiers(), Some(ctx.clone()));
+        let history = History::new(&todo_list);
         
         // 加载上次打开的Markdown目录信息
         let (markdown_directory, markdown_files, current_file, current_content) = 
@@ -190,6 +429,25 @@ impl RodoApp {
         let mut app = Self {
             view: View::List,
             todo_list,
+            load_failure,
+            workspaces,
+            workspace_name_input: String::new(),
+            status_name_input: String::new(),
+            quick_add_input: String::new(),
+            templates,
+            template_draft: None,
+            template_subtask_input: String::new(),
+            template_subtask_parent: None,
+            smart_lists,
+            smart_list_name_input: String::new(),
+            search_input: String::new(),
+            search_error: None,
+            selection_mode: false,
+            selected_todos: HashSet::new(),
+            selection_anchor: None,
+            bulk_tag_input: String::new(),
+            bulk_due_input: String::new(),
+            snooze_pick: Snooze::Tomorrow.until(Local::now()).unwrap_or_else(Local::now),
             theme,
             theme_presets,
             app_style: Self::load_app_style().unwrap_or_default(),
@@ -197,6 +455,16 @@ impl RodoApp {
             new_todo: Todo::new(String::new()),
             temp_input: String::new(),
             temp_tag_input: String::new(),
+            subtask_parent_id: None,
+            description_preview: false,
+            dragging_todo_id: None,
+            dragging_subtask_id: None,
+            board_grouping: BoardGrouping::Status,
+            board_drag_source: None,
+            calendar_mode: CalendarMode::Month,
+            calendar_date: Local::now().date_naive(),
+            stats_range_days: 30,
+            stats_weekly: false,
             modified: false,
             show_confirmation: false,
             confirmation_message: String::new(),
@@ -205,6 +473,11 @@ impl RodoApp {
             markdown_content: current_content.unwrap_or_default(),
             current_markdown_directory: markdown_directory,
             markdown_files,
+            reminder_command_input: reminder_settings.command.clone(),
+            reminder_settings,
+            reminder_scheduler,
+            toasts: Vec::new(),
+            history,
         };
         
         // 应用主题
@@ -213,8 +486,13 @@ impl RodoApp {
         // 添加一些示例任务，如果没有任务的话
         if app.todo_list.todos.is_empty() {
             app.add_sample_todos();
+            app.history.sync(&app.todo_list);
         }
         
+        // 安排提醒，错过的提醒会立即触发
+        app.refresh_reminder_schedule();
+        app.report_load_notices(&[workspaces_notice, smart_lists_notice, templates_notice]);
+        
         app
     }
     
@@ -265,10 +543,31 @@ impl RodoApp {
         self.todo_list.add_todo(todo4);
     }
     
+    /// 加载任务文件，无法读取时返回空列表和失败信息
+    fn load_todo_list(file_name: &str) -> (TodoList, Option<LoadFailure>) {
+        match TodoList::load(file_name) {
+            Ok(list) => (list, None),
+            Err(err) => {
+                eprintln!("加载任务失败: {}", err);
+                (TodoList::default(), Some(LoadFailure::new(file_name, err)))
+            }
+        }
+    }
+    
+    /// 显示加载数据文件时遇到的问题
+    fn report_load_notices(&mut self, notices: &[Option<String>]) {
+        let notices: Vec<&str> = notices.iter().flatten().map(String::as_str).collect();
+        if !notices.is_empty() {
+            self.show_notice(&notices.join("\n\n"));
+        }
+    }
+    
     /// 保存应用程序状态
     pub fn save(&mut self) {
-        if self.modified {
-            if let Err(err) = self.todo_list.save() {
+        // 任务文件无法读取时不保存，以免覆盖还能恢复的数据
+        if self.modified && self.load_failure.is_none() {
+            self.purge_expired_trash();
+            if let Err(err) = self.todo_list.save(&self.workspaces.active().file_name()) {
                 eprintln!("保存失败: {}", err);
             }
             self.modified = false;
@@ -282,6 +581,273 @@ impl RodoApp {
         }
     }
     
+    /// 永久删除回收站中超过保留天数的任务，返回删除的数量
+    pub fn purge_expired_trash(&mut self) -> usize {
+        // 先记录尚未记录的修改，避免同步时丢失它们的撤销记录
+        self.record_history();
+        let purged = self.todo_list.purge_expired_trash(Local::now());
+        if purged > 0 {
+            // 自动清除不需要撤销
+            self.history.sync(&self.todo_list);
+            self.modified = true;
+        }
+        purged
+    }
+    
+    /// 用备份恢复无法读取的任务文件，原文件改名保留
+    pub fn restore_from_backup(&mut self, backup: &Path) -> Result<(), String> {
+        let list = TodoList::read_file(backup)?;
+        let path = TodoList::data_file_path(&self.workspaces.active().file_name())?;
+        if path.exists() {
+            storage::preserve_unreadable(&path, Local::now())?;
+        }
+        storage::restore_backup(backup, &path)?;
+        
+        self.todo_list = list;
+        self.load_failure = None;
+        self.modified = false;
+        self.history.reset(&self.todo_list);
+        self.refresh_reminder_schedule();
+        self.apply_search();
+        self.toasts.push(Toast::new("已从备份恢复任务".to_string(), None));
+        Ok(())
+    }
+    
+    /// 放弃无法读取的任务文件，使用空列表，原文件改名保留
+    pub fn discard_unreadable_todos(&mut self) -> Result<(), String> {
+        let path = TodoList::data_file_path(&self.workspaces.active().file_name())?;
+        if path.exists() {
+            let kept = storage::preserve_unreadable(&path, Local::now())?;
+            self.toasts.push(Toast::new(format!("原文件已保留为 {}", kept.display()), None));
+        }
+        self.load_failure = None;
+        Ok(())
+    }
+    
+    /// 切换到指定工作区
+    pub fn switch_workspace(&mut self, id: &str) {
+        if self.workspaces.active == id || self.workspaces.get(id).is_none() {
+            return;
+        }
+        
+        // 切换前保存当前工作区的任务
+        if self.load_failure.is_none() {
+            if let Err(err) = self.todo_list.save(&self.workspaces.active().file_name()) {
+                eprintln!("保存失败: {}", err);
+            }
+        }
+        
+        self.workspaces.active = id.to_string();
+        if let Err(err) = self.workspaces.save() {
+            eprintln!("保存工作区失败: {}", err);
+        }
+        
+        self.load_active_workspace();
+    }
+    
+    /// 加载当前工作区的任务并返回列表视图
+    fn load_active_workspace(&mut self) {
+        (self.todo_list, self.load_failure) = Self::load_todo_list(&self.workspaces.active().file_name());
+        let smart_lists_notice;
+        (self.smart_lists, smart_lists_notice) = SmartLists::load(&self.workspaces.active().smart_lists_file_name());
+        self.report_load_notices(&[smart_lists_notice]);
+        self.modified = false;
+        self.editing_todo_id = None;
+        self.view = View::List;
+        self.clear_selection();
+        self.history.reset(&self.todo_list);
+        self.refresh_reminder_schedule();
+        self.apply_search();
+    }
+    
+    /// 保存当前工作区的智能列表
+    fn save_smart_lists(&self) -> Result<(), String> {
+        self.smart_lists.save(&self.workspaces.active().smart_lists_file_name())
+    }
+    
+    /// 将当前的筛选条件保存为智能列表
+    pub fn add_smart_list(&mut self, name: &str) -> Result<(), String> {
+        let list = SmartList::from_current(name, &self.todo_list, &self.search_input);
+        self.smart_lists.add(list)?;
+        self.save_smart_lists()
+    }
+    
+    /// 用当前的筛选条件更新智能列表
+    pub fn update_smart_list(&mut self, id: &str) -> Result<(), String> {
+        let current = SmartList::from_current("", &self.todo_list, &self.search_input);
+        let list = self.smart_lists.lists.iter_mut()
+            .find(|l| l.id == id)
+            .ok_or_else(|| "智能列表不存在".to_string())?;
+        *list = SmartList {
+            id: list.id.clone(),
+            name: list.name.clone(),
+            ..current
+        };
+        self.save_smart_lists()
+    }
+    
+    /// 重命名智能列表
+    pub fn rename_smart_list(&mut self, id: &str, name: &str) -> Result<(), String> {
+        self.smart_lists.rename(id, name)?;
+        self.save_smart_lists()
+    }
+    
+    /// 删除智能列表
+    pub fn delete_smart_list(&mut self, id: &str) -> Result<(), String> {
+        self.smart_lists.remove(id);
+        self.save_smart_lists()
+    }
+    
+    /// 应用智能列表的筛选条件
+    pub fn apply_smart_list(&mut self, id: &str) {
+        if let Some(list) = self.smart_lists.lists.iter().find(|l| l.id == id) {
+            self.search_input = list.apply(&mut self.todo_list);
+            self.apply_search();
+            self.modified = true;
+        }
+    }
+    
+    /// 解析搜索框内容并应用到任务列表，语法错误时不过滤并记录错误
+    pub fn apply_search(&mut self) {
+        match Query::parse(&self.search_input) {
+            Ok(query) => {
+                self.todo_list.search = if query.is_empty() { None } else { Some(query) };
+                self.search_error = None;
+            }
+            Err(err) => {
+                self.todo_list.search = None;
+                self.search_error = Some(err);
+            }
+        }
+    }
+    
+    /// 将当前工作区中尚未触发的提醒提交给调度器，其他工作区的提醒在切换过去后才提交
+    pub fn refresh_reminder_schedule(&self) {
+        self.reminder_scheduler.set_schedule(self.todo_list.scheduled_reminders());
+    }
+    
+    /// 处理调度器已触发的提醒：记录触发状态并显示应用内提示
+    pub fn process_fired_reminders(&mut self) {
+        // 任务可能已在提醒触发后被删除
+        let fired: Vec<_> = self.reminder_scheduler
+            .poll_fired()
+            .into_iter()
+            .filter(|fired| self.todo_list.todos.contains_key(&fired.todo_id))
+            .collect();
+        
+        if !fired.is_empty() {
+            // 先记录尚未记录的修改，避免同步时丢失它们的撤销记录
+            self.record_history();
+            
+            for fired in fired {
+                self.todo_list.mark_reminder_fired(&fired.todo_id, &fired.reminder_id, fired.at);
+                self.modified = true;
+                
+                let message = if fired.missed {
+                    format!("⏰ 错过的提醒（{}）：{}", fired.at.format("%m-%d %H:%M"), fired.title)
+                } else {
+                    format!("⏰ 提醒：{}", fired.title)
+                };
+                self.toasts.push(Toast::new(message, Some(ToastAction::OpenTodo(fired.todo_id))));
+            }
+            
+            // 提醒的触发状态不需要撤销
+            self.history.sync(&self.todo_list);
+        }
+        
+        self.toasts.retain(|t| !t.is_expired());
+    }
+    
+    /// 记录本帧对任务的修改，破坏性操作后显示撤销提示
+    pub fn record_history(&mut self) {
+        let message = match self.history.commit(&self.todo_list) {
+            Some(command) if command.destructive => format!("已{}", command.label),
+            _ => return,
+        };
+        
+        // 只保留最新的撤销提示
+        self.toasts.retain(|t| t.action != Some(ToastAction::Undo));
+        self.toasts.push(Toast::new(message, Some(ToastAction::Undo)));
+    }
+    
+    /// 撤销最近的修改
+    pub fn undo(&mut self) {
+        // 先记录尚未记录的修改，确保撤销的是最近一次操作
+        self.record_history();
+        if let Some(label) = self.history.undo(&mut self.todo_list) {
+            self.after_history_change();
+            self.toasts.retain(|t| t.action != Some(ToastAction::Undo));
+            self.toasts.push(Toast::new(format!("已撤销：{}", label), None));
+        }
+    }
+    
+    /// 重做最近撤销的修改
+    pub fn redo(&mut self) {
+        self.record_history();
+        if let Some(label) = self.history.redo(&mut self.todo_list) {
+            self.after_history_change();
+            self.toasts.push(Toast::new(format!("已重做：{}", label), None));
+        }
+    }
+    
+    /// 撤销或重做后保存并离开已不存在的任务的编辑页面
+    fn after_history_change(&mut self) {
+        self.modified = true;
+        if let Some(id) = &self.editing_todo_id {
+            if !self.todo_list.todos.contains_key(id) {
+                self.editing_todo_id = None;
+                self.view = View::List;
+            }
+        }
+    }
+    
+    /// 更新并保存提醒设置
+    pub fn set_reminder_settings(&mut self, settings: ReminderSettings) -> Result<(), String> {
+        self.reminder_scheduler.set_notifiers(settings.notifiers());
+        self.reminder_settings = settings;
+        self.reminder_settings.save()
+    }
+    
+    /// 创建新工作区并切换过去
+    pub fn create_workspace(&mut self, name: &str) -> Result<(), String> {
+        let id = self.workspaces.create(name)?;
+        self.switch_workspace(&id);
+        Ok(())
+    }
+    
+    /// 重命名当前工作区
+    pub fn rename_workspace(&mut self, name: &str) -> Result<(), String> {
+        let id = self.workspaces.active.clone();
+        self.workspaces.rename(&id, name)
+    }
+    
+    /// 复制当前工作区并切换到副本
+    pub fn duplicate_workspace(&mut self, name: &str) -> Result<(), String> {
+        // 先保存，确保副本包含最新的任务
+        if self.load_failure.is_some() {
+            return Err("当前工作区的任务文件无法读取，请先从备份恢复".to_string());
+        }
+        self.todo_list.save(&self.workspaces.active().file_name())?;
+        
+        let source_id = self.workspaces.active.clone();
+        let id = self.workspaces.duplicate(&source_id, name)?;
+        self.switch_workspace(&id);
+        Ok(())
+    }
+    
+    /// 删除工作区
+    pub fn delete_workspace(&mut self, id: &str) -> Result<(), String> {
+        let was_active = self.workspaces.active == id;
+        self.workspaces.delete(id)?;
+        
+        // 删除的是当前工作区时加载新的当前工作区
+        if was_active {
+            self.load_active_workspace();
+        }
+        
+        Ok(())
+    }
+    
     /// 显示确认对话框
     pub fn show_confirm(&mut self, message: &str, action: ConfirmationAction) {
         self.confirmation_message = message.to_string();
@@ -289,6 +855,11 @@ impl RodoApp {
         self.show_confirmation = true;
     }
     
+    /// 显示只有确定按钮的提示对话框
+    pub fn show_notice(&mut self, message: &str) {
+        self.show_confirm(message, ConfirmationAction::Notice);
+    }
+    
     /// 创建新的待办事项
     #[allow(dead_code)]
     pub fn create_new_todo(&mut self) {
@@ -300,8 +871,52 @@ impl RodoApp {
         }
     }
     
+    /// 设置任务完成状态，提前完成仍被阻塞的任务时先请求确认
+    pub fn toggle_todo_completed(&mut self, id: &str, completed: bool) {
+        let status_id = match self.todo_list.todos.get(id) {
+            Some(todo) => self.todo_list.completion_status_id(todo, completed),
+            None => return,
+        };
+        
+        self.change_todo_status(id, &status_id);
+    }
+    
+    /// 设置任务的工作流状态，进入终止状态时与标记完成一样检查前置任务
+    pub fn change_todo_status(&mut self, id: &str, status_id: &str) {
+        let terminal = match self.todo_list.status(status_id) {
+            Some(status) => status.terminal,
+            None => return,
+        };
+        
+        if terminal {
+            let blockers: Vec<String> = match self.todo_list.todos.get(id) {
+                Some(todo) => self.todo_list.open_blockers(todo).iter().map(|t| t.title.clone()).collect(),
+                None => return,
+            };
+            
+            if !blockers.is_empty() {
+                self.show_confirm(
+                    &format!("此任务仍被以下任务阻塞：{}。确定要提前完成吗？", blockers.join("、")),
+                    ConfirmationAction::CompleteBlocked(id.to_string(), status_id.to_string()),
+                );
+                return;
+            }
+        }
+        
+        let was_completed = self.todo_list.todos.get(id).map(|t| t.completed).unwrap_or(false);
+        let label = match (was_completed, terminal) {
+            (false, true) => "完成任务",
+            (true, false) => "取消完成任务",
+            _ => "修改状态",
+        };
+        self.history.label_next(label, false);
+        self.todo_list.set_todo_status(id, status_id);
+        self.modified = true;
+    }
+    
     /// 删除待办事项
     pub fn delete_todo(&mut self, id: &str) {
+        self.history.label_next("删除任务", true);
         self.todo_list.remove_todo(id);
         self.modified = true;
         
@@ -314,13 +929,293 @@ impl RodoApp {
         }
     }
     
+    /// 清空多选的任务
+    pub fn clear_selection(&mut self) {
+        self.selected_todos.clear();
+        self.selection_anchor = None;
+    }
+    
+    /// 获取仍然存在的选中任务ID
+    pub fn selected_ids(&self) -> Vec<String> {
+        self.selected_todos
+            .iter()
+            .filter(|id| self.todo_list.todos.contains_key(*id))
+            .cloned()
+            .collect()
+    }
+    
+    /// 批量设置选中任务的完成状态，完成时跳过仍被阻塞的任务，返回跳过的数量
+    pub fn bulk_set_completed(&mut self, completed: bool) -> usize {
+        let mut skipped = 0;
+        self.history.label_next(if completed { "批量完成任务" } else { "批量取消完成" }, false);
+        for id in self.selected_ids() {
+            let status_id = match self.todo_list.todos.get(&id) {
+                Some(todo) if todo.completed == completed => continue,
+                Some(todo) if completed && !self.todo_list.open_blockers(todo).is_empty() => {
+                    skipped += 1;
+                    continue;
+                }
+                Some(todo) => self.todo_list.completion_status_id(todo, completed),
+                None => continue,
+            };
+            self.todo_list.set_todo_status(&id, &status_id);
+        }
+        self.modified = true;
+        skipped
+    }
+    
+    /// 批量设置选中任务的优先级
+    pub fn bulk_set_priority(&mut self, priority: Priority) {
+        self.history.label_next("批量修改优先级", false);
+        for id in self.selected_ids() {
+            if let Some(todo) = self.todo_list.todos.get_mut(&id) {
+                todo.priority = priority.clone();
+            }
+        }
+        self.modified = true;
+    }
+    
+    /// 为选中任务添加标签
+    pub fn bulk_add_tag(&mut self, tag: &str) {
+        let tag = tag.trim();
+        if tag.is_empty() {
+            return;
+        }
+        
+        self.history.label_next("批量添加标签", false);
+        for id in self.selected_ids() {
+            if let Some(todo) = self.todo_list.todos.get_mut(&id) {
+                if !todo.tags.iter().any(|t| t == tag) {
+                    todo.tags.push(tag.to_string());
+                }
+            }
+        }
+        self.modified = true;
+    }
+    
+    /// 从选中任务中移除标签
+    pub fn bulk_remove_tag(&mut self, tag: &str) {
+        self.history.label_next("批量移除标签", false);
+        for id in self.selected_ids() {
+            if let Some(todo) = self.todo_list.todos.get_mut(&id) {
+                todo.tags.retain(|t| t != tag);
+            }
+        }
+        self.modified = true;
+    }
+    
+    /// 批量设置选中任务的截止时间，为空时清除截止时间
+    pub fn bulk_set_due_date(&mut self, due_date: Option<DateTime<Local>>) {
+        self.history.label_next("批量修改截止时间", false);
+        for id in self.selected_ids() {
+            if let Some(todo) = self.todo_list.todos.get_mut(&id) {
+                todo.due_date = due_date;
+            }
+        }
+        self.modified = true;
+    }
+    
+    /// 将选中任务移动到另一个工作区，返回移动的数量
+    ///
+    /// 目标工作区的任务不在撤销历史中，因此移动操作不能撤销。之前的命令可能涉及移走的任务，
+    /// 撤销它们会把任务放回当前工作区，使其同时出现在两个工作区中，所以移动后清空撤销历史。
+    pub fn bulk_move_to_workspace(&mut self, workspace_id: &str) -> Result<usize, String> {
+        if self.workspaces.active == workspace_id {
+            return Err("任务已在此工作区中".to_string());
+        }
+        let target = self.workspaces.get(workspace_id).ok_or_else(|| "工作区不存在".to_string())?;
+        let file_name = target.file_name();
+        let target_name = target.name.clone();
+        
+        // 先写入目标工作区，成功后再从当前工作区移除
+        let mut target_list = TodoList::load(&file_name)?;
+        let mut source_list = self.todo_list.clone();
+        let todos = source_list.take_todos(&self.selected_ids());
+        let count = todos.len();
+        target_list.receive_todos(todos);
+        target_list.save(&file_name)?;
+        
+        self.todo_list = source_list;
+        self.history.reset(&self.todo_list);
+        self.toasts.retain(|t| t.action != Some(ToastAction::Undo));
+        self.clear_selection();
+        // 立即保存，避免任务同时留在两个工作区中
+        self.modified = true;
+        self.save();
+        self.toasts.push(Toast::new(format!("已将 {} 个任务移动到工作区 \"{}\"，此操作不能撤销", count, target_name), None));
+        Ok(count)
+    }
+    
+    /// 将选中任务移入回收站
+    pub fn bulk_delete(&mut self, ids: &[String]) {
+        self.history.label_next("批量删除任务", true);
+        for id in ids {
+            self.todo_list.remove_todo(id);
+        }
+        
+        if self.editing_todo_id.as_ref().is_some_and(|id| ids.contains(id)) {
+            self.editing_todo_id = None;
+            self.view = View::List;
+        }
+        self.clear_selection();
+        self.modified = true;
+    }
+    
+    /// 将子任务提升为独立任务
+    pub fn promote_subtask(&mut self, todo_id: &str, subtask_id: &str) {
+        if let Some(id) = self.todo_list.promote_subtask(todo_id, subtask_id) {
+            // 成功后才设置名称，失败时不留给之后无关的修改
+            self.history.label_next("转为任务", false);
+            let title = self.todo_list.todos.get(&id).map(|t| t.title.clone()).unwrap_or_default();
+            self.toasts.push(Toast::new(format!("已将子任务\"{}\"转为任务", title), None));
+            self.modified = true;
+        }
+    }
+    
+    /// 请求将任务降级为子任务，任务的描述、截止时间等信息会丢失时先请求确认
+    pub fn request_demote_todo(&mut self, todo_id: &str, target_id: &str) -> Result<(), String> {
+        let todo = self.todo_list.todos.get(todo_id).ok_or_else(|| "任务不存在".to_string())?;
+        let target = self.todo_list.todos.get(target_id).ok_or_else(|| "目标任务不存在".to_string())?;
+        
+        let losses = todo.demotion_losses();
+        if losses.is_empty() {
+            return self.demote_todo(todo_id, target_id);
+        }
+        
+        let message = format!(
+            "将 \"{}\" 转为 \"{}\" 的子任务后，以下信息将被丢弃：{}。确定要继续吗？",
+            todo.title,
+            target.title,
+            losses.join("、")
+        );
+        self.show_confirm(&message, ConfirmationAction::DemoteTodo(todo_id.to_string(), target_id.to_string()));
+        Ok(())
+    }
+    
+    /// 将任务降级为另一个任务的子任务，正在编辑此任务时改为编辑目标任务
+    pub fn demote_todo(&mut self, todo_id: &str, target_id: &str) -> Result<(), String> {
+        self.todo_list.demote_todo(todo_id, target_id)?;
+        self.history.label_next("转为子任务", true);
+        self.selected_todos.remove(todo_id);
+        if self.editing_todo_id.as_deref() == Some(todo_id) {
+            self.editing_todo_id = Some(target_id.to_string());
+        }
+        self.modified = true;
+        Ok(())
+    }
+    
+    /// 推迟任务到指定时间，为空时取消推迟
+    pub fn snooze_todos(&mut self, ids: &[String], until: Option<DateTime<Local>>) {
+        self.history.label_next(if until.is_some() { "推迟任务" } else { "取消推迟" }, false);
+        for id in ids {
+            if let Some(todo) = self.todo_list.todos.get_mut(id) {
+                todo.defer_until = until;
+            }
+        }
+        self.modified = true;
+    }
+    
+    /// 将任务的截止时间改到指定日期
+    pub fn reschedule_todo(&mut self, id: &str, date: NaiveDate) {
+        if let Some(todo) = self.todo_list.todos.get_mut(id) {
+            self.history.label_next("修改截止时间", false);
+            todo.reschedule_to(date);
+            self.modified = true;
+        }
+    }
+    
+    /// 解析快速添加输入并创建任务
+    pub fn quick_add_todo(&mut self) -> Result<(), String> {
+        let parsed = quick_add::parse(&self.quick_add_input, Local::now());
+        if parsed.title.trim().is_empty() {
+            return Err("任务标题不能为空".to_string());
+        }
+        
+        let mut todo = parsed.into_todo();
+        todo.id = format!("todo-{}", Uuid::new_v4());
+        let todo_id = todo.id.clone();
+        let status_id = self.todo_list.default_status_id(false);
+        
+        self.history.label_next("添加任务", false);
+        self.todo_list.todos.insert(todo_id.clone(), todo);
+        self.todo_list.set_todo_status(&todo_id, &status_id);
+        self.quick_add_input.clear();
+        self.modified = true;
+        Ok(())
+    }
+    
+    /// 将任务保存为模板
+    pub fn save_todo_as_template(&mut self, id: &str) -> Result<(), String> {
+        let todo = self.todo_list.todos.get(id).ok_or_else(|| "任务不存在".to_string())?;
+        let name = self.templates.unique_name(&todo.title);
+        let template = TaskTemplate::from_todo(&name, todo, Local::now());
+        // 设置页面中有未保存的修改时同样加入，避免之后保存修改时丢失新模板
+        if let Some(draft) = &mut self.template_draft {
+            draft.templates.push(template.clone());
+        }
+        self.templates.templates.push(template);
+        self.templates.save()?;
+        self.toasts.push(Toast::new(format!("已保存为模板：{}", name), None));
+        Ok(())
+    }
+    
+    /// 更新任务模板并保存
+    pub fn set_templates(&mut self, templates: TaskTemplates) -> Result<(), String> {
+        self.templates = templates;
+        self.template_draft = None;
+        self.templates.save()
+    }
+    
+    /// 打开添加任务页面，并用模板填写新任务
+    pub fn add_todo_from_template(&mut self, template_id: &str) {
+        if let Some(template) = self.templates.get(template_id) {
+            self.new_todo = template.instantiate(Local::now());
+            self.editing_todo_id = None;
+            self.subtask_parent_id = None;
+            self.view = View::AddTodo;
+        }
+    }
+    
+    /// 打开添加任务页面，并预先填入截止日期
+    pub fn add_todo_on(&mut self, date: NaiveDate) {
+        let mut todo = Todo::new(String::new());
+        todo.reschedule_to(date);
+        self.new_todo = todo;
+        self.editing_todo_id = None;
+        self.subtask_parent_id = None;
+        self.view = View::AddTodo;
+    }
+    
+    /// 从回收站恢复任务
+    pub fn restore_todo(&mut self, id: &str) {
+        if self.todo_list.restore_from_trash(id) {
+            self.history.label_next("恢复任务", false);
+            self.modified = true;
+        }
+    }
+    
+    /// 从回收站永久删除任务
+    pub fn purge_trashed_todo(&mut self, id: &str) {
+        self.history.label_next("永久删除任务", true);
+        self.todo_list.purge_from_trash(id);
+        self.modified = true;
+    }
+    
+    /// 清空回收站
+    pub fn empty_trash(&mut self) {
+        self.history.label_next("清空回收站", true);
+        self.todo_list.empty_trash();
+        self.modified = true;
+    }
+    
     /// 删除所有已完成的任务
     pub fn delete_all_completed(&mut self) {
         let completed_ids: Vec<String> = self.todo_list.todos.values()
             .filter(|todo| todo.completed)
             .map(|todo| todo.id.clone())
             .collect();
-            
+        
+        self.history.label_next("删除已完成任务", true);
         for id in completed_ids {
             self.todo_list.remove_todo(&id);
         }
@@ -336,7 +1231,9 @@ impl RodoApp {
     /// 从文件导入待办事项
     pub fn import_todos(&mut self, file_path: &std::path::Path) -> Result<(), String> {
         let imported_list = TodoList::import_from_file(file_path)?;
+        self.history.label_replacement("导入任务");
         self.todo_list = imported_list;
+        self.apply_search();
         self.modified = true;
         Ok(())
     }
@@ -354,6 +1251,9 @@ impl RodoApp {
         }
         
         if imported_count > 0 {
+            // 导入的任务可能使用当前工作区不存在的状态
+            self.todo_list.normalize_statuses();
+            self.history.label_next("合并导入", false);
             self.modified = true;
         }
         
@@ -362,19 +1262,23 @@ impl RodoApp {
     
     /// 删除指定标签（从所有任务中）
     pub fn delete_tag(&mut self, tag_name: &str) {
+        self.history.label_next("删除标签", true);
         for todo in self.todo_list.todos.values_mut() {
             todo.tags.retain(|t| t != tag_name);
         }
         
-        // 同时从活跃标签中移除
-        self.todo_list.active_tags.retain(|t| t != tag_name);
+        // 同时从活跃标签和排除的标签中移除
+        self.todo_list.clear_tag_filter(tag_name);
         
         self.modified = true;
     }
     
     /// 重置应用程序到初始状态
     pub fn reset_app(&mut self, ctx: &egui::Context) {
+        self.history.label_replacement("恢复初始状态");
         self.todo_list = TodoList::default();
+        self.search_input.clear();
+        self.search_error = None;
         self.theme = Theme::default();
         self.theme_presets = ThemePresets::default();
         self.editing_todo_id = None;
@@ -441,7 +1345,7 @@ impl RodoApp {
         
         // 序列化并保存
         let path = Self::get_markdown_info_file_path()?;
-        let serialized = serde_json::to_string(&info).map_err(|e| format!("序列化Markdown目录信息失败: {}", e))?;
+        let serialized = schema::MARKDOWN_INFO.encode(&info).map_err(|e| format!("序列化Markdown目录信息失败: {}", e))?;
         std::fs::write(path, serialized).map_err(|e| format!("写入Markdown目录信息文件失败: {}", e))?;
         Ok(())
     }
@@ -456,7 +1360,7 @@ impl RodoApp {
         let data = std::fs::read_to_string(path)
             .map_err(|e| format!("读取Markdown目录信息文件失败: {}", e))?;
             
-        let info: MarkdownDirectoryInfo = serde_json::from_str(&data)
+        let info: MarkdownDirectoryInfo = schema::MARKDOWN_INFO.decode(&data)
             .map_err(|e| format!("解析Markdown目录信息JSON失败: {}", e))?;
             
         Ok((info.directory, info.files, info.current_file, info.current_content))
@@ -485,7 +1389,7 @@ impl RodoApp {
         let data = std::fs::read_to_string(path)
             .map_err(|e| format!("读取应用风格配置文件失败: {}", e))?;
             
-        let config: AppStyleConfig = serde_json::from_str(&data)
+        let config: AppStyleConfig = schema::APP_STYLE.decode(&data)
             .map_err(|e| format!("解析应用风格配置JSON失败: {}", e))?;
             
         Ok(config.app_style)
@@ -498,7 +1402,7 @@ impl RodoApp {
         };
         
         let path = Self::get_app_style_file_path()?;
-        let serialized = serde_json::to_string(&config)
+        let serialized = schema::APP_STYLE.encode(&config)
             .map_err(|e| format!("序列化应用风格配置失败: {}", e))?;
             
         std::fs::write(path, serialized)
@@ -525,4 +1429,38 @@ impl RodoApp {
         
         Ok(data_dir.join("app_style.json"))
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn loads_unversioned_markdown_info() {
+        let text = r#"{
+            "directory": "/home/user/notes",
+            "files": ["/home/user/notes/a.md", "/home/user/notes/b.md"],
+            "current_file": "/home/user/notes/a.md",
+            "current_content": "标题"
+        }"#;
+        let info: MarkdownDirectoryInfo = schema::MARKDOWN_INFO.decode(text).unwrap();
+        assert_eq!(info.directory.as_deref(), Some("/home/user/notes"));
+        assert_eq!(info.files.len(), 2);
+        assert_eq!(info.current_content.as_deref(), Some("标题"));
+        
+        let decoded: MarkdownDirectoryInfo = schema::MARKDOWN_INFO
+            .decode(&schema::MARKDOWN_INFO.encode(&info).unwrap())
+            .unwrap();
+        assert_eq!(decoded.current_file, info.current_file);
+    }
+    
+    #[test]
+    fn loads_unversioned_app_style() {
+        let config: AppStyleConfig = schema::APP_STYLE.decode(r#"{"app_style": "Retro"}"#).unwrap();
+        assert_eq!(config.app_style, AppStyle::Retro);
+        
+        let decoded: AppStyleConfig = schema::APP_STYLE
+            .decode(&schema::APP_STYLE.encode(&config).unwrap())
+            .unwrap();
+        assert_eq!(decoded.app_style, AppStyle::Retro);
+    }
+}
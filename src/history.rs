@@ -0,0 +1,540 @@
+use crate::todo::{Status, Todo, TodoList, TrashedTodo};
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant};
+
+/// 撤销栈的最大长度
+const MAX_HISTORY: usize = 100;
+
+/// 连续编辑同一任务时合并为一条命令的时间窗口
+const MERGE_WINDOW: Duration = Duration::from_millis(1500);
+
+/// 单个任务的变更，`before` 为空表示新增，`after` 为空表示删除
+#[derive(Clone, Debug)]
+struct TodoChange {
+    id: String,
+    before: Option<Todo>,
+    after: Option<Todo>,
+}
+
+/// 可撤销的命令，记录一次操作涉及的所有任务变更
+#[derive(Clone, Debug)]
+pub struct Command {
+    /// 命令名称，用于提示
+    pub label: String,
+    /// 是否为破坏性操作（删除、导入、重置等）
+    pub destructive: bool,
+    changes: Vec<TodoChange>,
+    /// 回收站变化前后的内容，回收站未变化时为空
+    trash: Option<(Vec<TrashedTodo>, Vec<TrashedTodo>)>,
+    /// 手动顺序变化前后的内容，未变化时为空
+    manual_order: Option<(Vec<String>, Vec<String>)>,
+    /// 工作流状态变化前后的内容，未变化时为空
+    statuses: Option<(Vec<Status>, Vec<Status>)>,
+    /// 整体替换任务列表时替换前后的完整列表，包括状态、排序和筛选设置
+    lists: Option<Box<(TodoList, TodoList)>>,
+    updated_at: Instant,
+}
+
+impl Command {
+    /// 比较修改前后的任务生成命令，没有变化时返回None
+    fn diff(label: String, destructive: bool, before: &TodoList, after: &TodoList) -> Option<Self> {
+        let ids: BTreeSet<&String> = before.todos.keys().chain(after.todos.keys()).collect();
+        
+        let changes: Vec<TodoChange> = ids.into_iter()
+            .filter_map(|id| {
+                let old = before.todos.get(id);
+                let new = after.todos.get(id);
+                if old == new {
+                    return None;
+                }
+                Some(TodoChange {
+                    id: id.clone(),
+                    before: old.cloned(),
+                    after: new.cloned(),
+                })
+            })
+            .collect();
+        
+        let trash = Self::field_change(&before.trash, &after.trash);
+        let manual_order = Self::field_change(&before.manual_order, &after.manual_order);
+        let statuses = Self::field_change(&before.statuses, &after.statuses);
+        
+        if changes.is_empty() && trash.is_none() && manual_order.is_none() && statuses.is_none() {
+            return None;
+        }
+        
+        Some(Self {
+            label,
+            destructive,
+            changes,
+            trash,
+            manual_order,
+            statuses,
+            lists: None,
+            updated_at: Instant::now(),
+        })
+    }
+    
+    /// 记录整体替换任务列表的命令
+    fn replacement(label: String, before: &TodoList, after: &TodoList) -> Self {
+        Self {
+            label,
+            destructive: true,
+            changes: Vec::new(),
+            trash: None,
+            manual_order: None,
+            statuses: None,
+            lists: Some(Box::new((before.clone(), after.clone()))),
+            updated_at: Instant::now(),
+        }
+    }
+    
+    /// 比较列表中任务之外的内容，变化时返回变化前后的值
+    fn field_change<T: Clone + PartialEq>(before: &T, after: &T) -> Option<(T, T)> {
+        (before != after).then(|| (before.clone(), after.clone()))
+    }
+    
+    /// 检查命令是否只修改了任务本身
+    fn only_todos(&self) -> bool {
+        self.trash.is_none() && self.manual_order.is_none() && self.statuses.is_none() && self.lists.is_none()
+    }
+    
+    /// 检查两个命令是否修改了同一组任务
+    fn same_targets(&self, other: &Command) -> bool {
+        self.only_todos()
+            && other.only_todos()
+            && self.changes.len() == other.changes.len()
+            && self.changes.iter().zip(&other.changes).all(|(a, b)| a.id == b.id)
+    }
+    
+    /// 将后续的连续编辑合并到此命令中
+    fn merge(&mut self, next: Command) {
+        for (change, next_change) in self.changes.iter_mut().zip(next.changes) {
+            change.after = next_change.after;
+        }
+        self.updated_at = next.updated_at;
+    }
+    
+    /// 撤销命令
+    fn undo(&self, list: &mut TodoList) {
+        if let Some(lists) = &self.lists {
+            *list = lists.0.clone();
+            return;
+        }
+        for change in &self.changes {
+            Self::apply(list, &change.id, &change.before);
+        }
+        if let Some((before, _)) = &self.trash {
+            list.trash = before.clone();
+        }
+        if let Some((before, _)) = &self.manual_order {
+            list.manual_order = before.clone();
+        }
+        if let Some((before, _)) = &self.statuses {
+            list.statuses = before.clone();
+        }
+    }
+    
+    /// 重做命令
+    fn redo(&self, list: &mut TodoList) {
+        if let Some(lists) = &self.lists {
+            *list = lists.1.clone();
+            return;
+        }
+        for change in &self.changes {
+            Self::apply(list, &change.id, &change.after);
+        }
+        if let Some((_, after)) = &self.trash {
+            list.trash = after.clone();
+        }
+        if let Some((_, after)) = &self.manual_order {
+            list.manual_order = after.clone();
+        }
+        if let Some((_, after)) = &self.statuses {
+            list.statuses = after.clone();
+        }
+    }
+    
+    fn apply(list: &mut TodoList, id: &str, state: &Option<Todo>) {
+        match state {
+            Some(todo) => {
+                list.todos.insert(id.to_string(), todo.clone());
+            }
+            None => {
+                list.todos.remove(id);
+            }
+        }
+    }
+}
+
+/// 撤销/重做历史
+///
+/// 每帧结束时将任务列表与上次记录的状态比较，把任务、回收站、手动顺序和工作流状态的差异
+/// 记录为一条命令，因此所有修改这些内容的操作都能撤销；排序方式和筛选条件属于视图设置，不记录。具名操作在修改前调用 `label_next`
+/// 设置命令名称，未设置名称的修改记录为"编辑任务"。导入、重置等整体替换任务列表的操作
+/// 调用 `label_replacement`，撤销时恢复完整的任务列表。
+pub struct History {
+    undo_stack: Vec<Command>,
+    redo_stack: Vec<Command>,
+    /// 上次记录时的任务列表
+    baseline: TodoList,
+    /// 下一条命令的名称和是否为破坏性操作
+    next_label: Option<(String, bool)>,
+    /// 下一条命令是否记录完整的任务列表
+    replace_next: bool,
+}
+
+impl History {
+    pub fn new(list: &TodoList) -> Self {
+        Self {
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            baseline: list.clone(),
+            next_label: None,
+            replace_next: false,
+        }
+    }
+    
+    /// 设置下一条命令的名称
+    pub fn label_next(&mut self, label: &str, destructive: bool) {
+        self.next_label = Some((label.to_string(), destructive));
+    }
+    
+    /// 设置下一条命令的名称，并记录替换前后的完整任务列表
+    pub fn label_replacement(&mut self, label: &str) {
+        self.label_next(label, true);
+        self.replace_next = true;
+    }
+    
+    /// 记录自上次记录以来的修改，返回新记录的命令
+    pub fn commit(&mut self, list: &TodoList) -> Option<&Command> {
+        let (label, destructive) = self.next_label.take().unwrap_or_else(|| ("编辑任务".to_string(), false));
+        let command = if std::mem::take(&mut self.replace_next) {
+            Some(Command::replacement(label, &self.baseline, list))
+        } else {
+            Command::diff(label, destructive, &self.baseline, list)
+        };
+        self.baseline = list.clone();
+        let command = command?;
+        
+        self.redo_stack.clear();
+        
+        // 连续编辑同一任务（例如逐字输入标题）合并为一条命令
+        let mergeable = matches!(self.undo_stack.last(), Some(last) if !command.destructive
+            && !last.destructive
+            && last.label == command.label
+            && last.updated_at.elapsed() < MERGE_WINDOW
+            && last.same_targets(&command));
+        
+        if let (true, Some(last)) = (mergeable, self.undo_stack.last_mut()) {
+            last.merge(command);
+        } else {
+            self.undo_stack.push(command);
+            if self.undo_stack.len() > MAX_HISTORY {
+                self.undo_stack.remove(0);
+            }
+        }
+        
+        self.undo_stack.last()
+    }
+    
+    /// 更新记录的状态但不生成命令，用于不需要撤销的后台修改
+    pub fn sync(&mut self, list: &TodoList) {
+        self.baseline = list.clone();
+    }
+    
+    /// 清空历史，用于切换工作区等整体替换任务列表的场景
+    pub fn reset(&mut self, list: &TodoList) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.baseline = list.clone();
+        self.next_label = None;
+        self.replace_next = false;
+    }
+    
+    /// 撤销最近的命令，返回命令名称
+    pub fn undo(&mut self, list: &mut TodoList) -> Option<String> {
+        let command = self.undo_stack.pop()?;
+        command.undo(list);
+        self.baseline = list.clone();
+        let label = command.label.clone();
+        self.redo_stack.push(command);
+        Some(label)
+    }
+    
+    /// 重做最近撤销的命令，返回命令名称
+    pub fn redo(&mut self, list: &mut TodoList) -> Option<String> {
+        let command = self.redo_stack.pop()?;
+        command.redo(list);
+        self.baseline = list.clone();
+        let label = command.label.clone();
+        self.undo_stack.push(command);
+        Some(label)
+    }
+    
+    /// 下一条可撤销命令的名称
+    pub fn undo_label(&self) -> Option<&str> {
+        self.undo_stack.last().map(|c| c.label.as_str())
+    }
+    
+    /// 下一条可重做命令的名称
+    pub fn redo_label(&self) -> Option<&str> {
+        self.redo_stack.last().map(|c| c.label.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// 包含一个任务的列表，返回列表和任务ID
+    fn list_with_todo(title: &str) -> (TodoList, String) {
+        let mut list = TodoList::default();
+        let todo = Todo::new(title.to_string());
+        let id = todo.id.clone();
+        list.add_todo(todo);
+        (list, id)
+    }
+    
+    fn rename(list: &mut TodoList, id: &str, title: &str) {
+        list.todos.get_mut(id).unwrap().title = title.to_string();
+    }
+    
+    fn title(list: &TodoList, id: &str) -> String {
+        list.todos[id].title.clone()
+    }
+    
+    /// 让最近的命令超出合并窗口
+    fn expire_merge_window(history: &mut History) {
+        let last = history.undo_stack.last_mut().unwrap();
+        last.updated_at = Instant::now().checked_sub(MERGE_WINDOW).unwrap();
+    }
+    
+    #[test]
+    fn commit_without_changes_records_nothing() {
+        let (list, _) = list_with_todo("任务");
+        let mut history = History::new(&list);
+        
+        history.label_next("添加任务", false);
+        assert!(history.commit(&list).is_none());
+        assert_eq!(history.undo_label(), None);
+        
+        // 未使用的名称不会留给下一条命令
+        let mut list = list;
+        list.add_todo(Todo::new("新任务".to_string()));
+        assert_eq!(history.commit(&list).unwrap().label, "编辑任务");
+    }
+    
+    #[test]
+    fn undo_and_redo_add() {
+        let mut list = TodoList::default();
+        let mut history = History::new(&list);
+        
+        let todo = Todo::new("任务".to_string());
+        let id = todo.id.clone();
+        list.add_todo(todo);
+        history.label_next("添加任务", false);
+        history.commit(&list);
+        
+        assert_eq!(history.undo(&mut list), Some("添加任务".to_string()));
+        assert!(list.todos.is_empty());
+        assert_eq!(history.redo_label(), Some("添加任务"));
+        
+        assert_eq!(history.redo(&mut list), Some("添加任务".to_string()));
+        assert!(list.todos.contains_key(&id));
+        assert_eq!(history.redo(&mut list), None);
+    }
+    
+    #[test]
+    fn consecutive_edits_merge_within_window() {
+        let (mut list, id) = list_with_todo("a");
+        let mut history = History::new(&list);
+        
+        for title in ["ab", "abc"] {
+            rename(&mut list, &id, title);
+            history.commit(&list);
+        }
+        assert_eq!(history.undo_stack.len(), 1);
+        
+        history.undo(&mut list);
+        assert_eq!(title(&list, &id), "a");
+        assert_eq!(history.undo_label(), None);
+    }
+    
+    #[test]
+    fn edits_after_window_are_separate() {
+        let (mut list, id) = list_with_todo("a");
+        let mut history = History::new(&list);
+        
+        rename(&mut list, &id, "ab");
+        history.commit(&list);
+        expire_merge_window(&mut history);
+        rename(&mut list, &id, "abc");
+        history.commit(&list);
+        assert_eq!(history.undo_stack.len(), 2);
+        
+        history.undo(&mut list);
+        assert_eq!(title(&list, &id), "ab");
+    }
+    
+    #[test]
+    fn different_labels_or_targets_do_not_merge() {
+        let (mut list, id) = list_with_todo("a");
+        let other = Todo::new("b".to_string());
+        let other_id = other.id.clone();
+        list.add_todo(other);
+        let mut history = History::new(&list);
+        
+        rename(&mut list, &id, "a1");
+        history.commit(&list);
+        rename(&mut list, &other_id, "b1");
+        history.commit(&list);
+        history.label_next("修改标签", false);
+        rename(&mut list, &other_id, "b2");
+        history.commit(&list);
+        
+        assert_eq!(history.undo_stack.len(), 3);
+    }
+    
+    #[test]
+    fn destructive_commands_never_merge() {
+        let (mut list, id) = list_with_todo("a");
+        let mut history = History::new(&list);
+        
+        for title in ["b", "c"] {
+            history.label_next("删除标签", true);
+            rename(&mut list, &id, title);
+            assert!(history.commit(&list).unwrap().destructive);
+        }
+        assert_eq!(history.undo_stack.len(), 2);
+        
+        history.undo(&mut list);
+        assert_eq!(title(&list, &id), "b");
+    }
+    
+    #[test]
+    fn new_command_clears_redo_stack() {
+        let (mut list, id) = list_with_todo("a");
+        let mut history = History::new(&list);
+        
+        rename(&mut list, &id, "b");
+        history.commit(&list);
+        history.undo(&mut list);
+        assert!(history.redo_label().is_some());
+        
+        rename(&mut list, &id, "c");
+        history.commit(&list);
+        assert_eq!(history.redo_label(), None);
+        assert_eq!(history.redo(&mut list), None);
+        assert_eq!(title(&list, &id), "c");
+    }
+    
+    #[test]
+    fn undo_stack_is_capped() {
+        let mut list = TodoList::default();
+        let mut history = History::new(&list);
+        
+        for i in 0..MAX_HISTORY + 5 {
+            list.add_todo(Todo::new(format!("任务{}", i)));
+            history.commit(&list);
+        }
+        assert_eq!(history.undo_stack.len(), MAX_HISTORY);
+        
+        while history.undo(&mut list).is_some() {}
+        // 最早的五条命令已被丢弃，无法撤销
+        assert_eq!(list.todos.len(), 5);
+    }
+    
+    #[test]
+    fn replacement_restores_whole_list() {
+        let (mut list, id) = list_with_todo("a");
+        let before = list.clone();
+        let mut history = History::new(&list);
+        
+        history.label_replacement("导入任务");
+        list = TodoList::default();
+        list.filter_completed = true;
+        list.add_todo(Todo::new("导入的任务".to_string()));
+        let after = list.clone();
+        let command = history.commit(&list).unwrap();
+        assert!(command.destructive);
+        assert!(!command.only_todos());
+        
+        history.undo(&mut list);
+        assert_eq!(list.todos, before.todos);
+        assert!(!list.filter_completed);
+        assert!(list.todos.contains_key(&id));
+        
+        history.redo(&mut list);
+        assert_eq!(list.todos, after.todos);
+        assert!(list.filter_completed);
+        
+        // 整体替换只影响被标记的那一条命令
+        let imported_id = list.todos.keys().next().unwrap().clone();
+        rename(&mut list, &imported_id, "b");
+        assert!(history.commit(&list).unwrap().lists.is_none());
+    }
+    
+    #[test]
+    fn manual_order_and_statuses_are_recorded() {
+        let (mut list, id) = list_with_todo("a");
+        let other = Todo::new("b".to_string());
+        let other_id = other.id.clone();
+        list.add_todo(other);
+        list.manual_order = vec![id.clone(), other_id.clone()];
+        let statuses = list.statuses.clone();
+        let mut history = History::new(&list);
+        
+        list.manual_order = vec![other_id.clone(), id.clone()];
+        history.label_next("调整顺序", false);
+        assert!(history.commit(&list).is_some());
+        
+        list.statuses.push(Status::new("审核中".to_string(), false));
+        assert!(history.commit(&list).is_some());
+        assert_eq!(history.undo_stack.len(), 2);
+        
+        history.undo(&mut list);
+        assert_eq!(list.statuses, statuses);
+        history.undo(&mut list);
+        assert_eq!(list.manual_order, vec![id.clone(), other_id.clone()]);
+        
+        history.redo(&mut list);
+        assert_eq!(list.manual_order, vec![other_id, id]);
+    }
+    
+    #[test]
+    fn sync_and_view_settings_record_nothing() {
+        let (mut list, id) = list_with_todo("a");
+        let mut history = History::new(&list);
+        
+        rename(&mut list, &id, "b");
+        history.sync(&list);
+        list.filter_completed = true;
+        list.manual_sort = true;
+        assert!(history.commit(&list).is_none());
+        assert_eq!(history.undo(&mut list), None);
+        assert_eq!(title(&list, &id), "b");
+    }
+    
+    #[test]
+    fn reset_after_moving_todos_away_keeps_them_out() {
+        let (mut list, id) = list_with_todo("a");
+        let mut history = History::new(&list);
+        
+        rename(&mut list, &id, "b");
+        history.commit(&list);
+        history.label_replacement("导入任务");
+        list.filter_completed = true;
+        history.commit(&list);
+        
+        // 移动到其他工作区后清空历史，之前涉及该任务的命令不能再把它放回
+        let moved = list.take_todos(&[id.clone()]);
+        assert_eq!(moved.len(), 1);
+        history.reset(&list);
+        
+        assert_eq!(history.undo(&mut list), None);
+        assert_eq!(history.redo(&mut list), None);
+        assert!(!list.todos.contains_key(&id));
+        assert!(history.commit(&list).is_none());
+    }
+}
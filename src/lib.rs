@@ -2,6 +2,15 @@ mod app;
 mod theme;
 mod todo;
 mod ui;
+mod workspace;
+mod reminder;
+mod history;
+mod query;
+mod smart_list;
+mod quick_add;
+mod template;
+mod storage;
+mod schema;
 pub mod markdown;
 
 // 添加全局状态模块
@@ -8,8 +8,14 @@ use syntect::parsing::SyntaxSet;
 use syntect::easy::HighlightLines;
 use std::fs;
 use std::path::Path;
+use std::sync::OnceLock;
 use open;
 
+/// 语法定义集合，加载较慢，只加载一次
+static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+/// 高亮主题集合，加载较慢，只加载一次
+static THEME_SET: OnceLock<ThemeSet> = OnceLock::new();
+
 /// 加载Markdown文件
 pub fn load_markdown_file(path: &Path) -> Result<String, String> {
     fs::read_to_string(path)
@@ -92,9 +98,9 @@ pub fn render_markdown(ui: &mut Ui, content: &str, is_dark: bool) {
     // 创建解析器
     let parser = Parser::new(content);
     
-    // 初始化语法高亮
-    let syntax_set = SyntaxSet::load_defaults_newlines();
-    let theme_set = ThemeSet::load_defaults();
+    // 初始化语法高亮（任务列表每帧都会渲染描述，因此复用已加载的集合）
+    let syntax_set = SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines);
+    let theme_set = THEME_SET.get_or_init(ThemeSet::load_defaults);
     let theme = if is_dark {
         &theme_set.themes["base16-ocean.dark"]
     } else {
@@ -251,7 +257,7 @@ pub fn render_markdown(ui: &mut Ui, content: &str, is_dark: bool) {
                     frame.show(ui, |ui| {
                         // 分行处理代码高亮
                         for line in current_code_block.lines() {
-                            if let Ok(ranges) = highlighter.highlight_line(line, &syntax_set) {
+                            if let Ok(ranges) = highlighter.highlight_line(line, syntax_set) {
                                 // 使用下划线前缀表示有意不使用的变量
                                 let _line_text = String::new();
                                 let mut fragments = Vec::new();
@@ -0,0 +1,553 @@
+use crate::todo::{priority_to_number, Priority, SubTask, Todo, TodoList};
+use chrono::{DateTime, Duration, Local, NaiveDate};
+use std::cmp::Ordering;
+
+/// 搜索语法说明，用于搜索框的提示
+pub const SYNTAX_HELP: &str = "\
+关键词         匹配标题、描述、子任务和标签，例如 发布 或 \"release notes\"
+tag:work       带有指定标签
+priority>=high 按优先级比较（low / medium / high / critical，也可用 低 / 中 / 高 / 紧急）
+due<7d         截止时间早于7天后（单位 h / d / w，可为负数；也可用 today / tomorrow / 2024-05-01）
+due:none       没有截止时间
+created>-30d   最近30天内创建
+is:open        状态：open / done / overdue / blocked / recurring / deferred
+status:进行中   处于指定的工作流状态
+-条件          排除匹配的任务，例如 -tag:blocked
+多个条件之间为\"并且\"关系";
+
+/// 比较运算符
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Comparison {
+    Eq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Comparison {
+    /// 判断比较结果是否满足运算符
+    fn accepts(self, ordering: Ordering) -> bool {
+        match self {
+            Comparison::Eq => ordering == Ordering::Equal,
+            Comparison::Lt => ordering == Ordering::Less,
+            Comparison::Le => ordering != Ordering::Greater,
+            Comparison::Gt => ordering == Ordering::Greater,
+            Comparison::Ge => ordering != Ordering::Less,
+        }
+    }
+}
+
+/// 查询中的日期值
+#[derive(Clone, Debug, PartialEq)]
+enum DateValue {
+    /// 相对当前时间的偏移，例如 7d
+    Offset(Duration),
+    /// 相对今天的天数，例如 today、tomorrow
+    Day(i64),
+    /// 指定日期
+    Date(NaiveDate),
+}
+
+impl DateValue {
+    /// 比较时间与日期值，偏移量按时间比较，其余按日期比较
+    fn compare(&self, time: DateTime<Local>, comparison: Comparison, now: DateTime<Local>) -> bool {
+        let ordering = match self {
+            DateValue::Offset(offset) => match now.checked_add_signed(*offset) {
+                Some(target) if comparison == Comparison::Eq => time.date_naive().cmp(&target.date_naive()),
+                Some(target) => time.cmp(&target),
+                // 超出可表示的时间范围：任何时间都早于正偏移，晚于负偏移
+                None if *offset > Duration::zero() => Ordering::Less,
+                None => Ordering::Greater,
+            },
+            DateValue::Day(days) => match Duration::try_days(*days).and_then(|d| now.date_naive().checked_add_signed(d)) {
+                Some(day) => time.date_naive().cmp(&day),
+                None if *days > 0 => Ordering::Less,
+                None => Ordering::Greater,
+            },
+            DateValue::Date(date) => time.date_naive().cmp(date),
+        };
+        comparison.accepts(ordering)
+    }
+}
+
+/// 任务状态条件
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum State {
+    Open,
+    Done,
+    Overdue,
+    Blocked,
+    Recurring,
+    Deferred,
+}
+
+/// 单个搜索条件
+#[derive(Clone, Debug, PartialEq)]
+enum Condition {
+    /// 关键词（已转为小写）
+    Text(String),
+    /// 标签（已转为小写）
+    Tag(String),
+    Priority(Comparison, Priority),
+    Due(Comparison, DateValue),
+    /// 没有截止时间
+    NoDue,
+    Created(Comparison, DateValue),
+    Is(State),
+    /// 工作流状态名称或ID（已转为小写）
+    Status(String),
+}
+
+/// 带有取反标记的搜索条件
+#[derive(Clone, Debug, PartialEq)]
+struct Term {
+    negated: bool,
+    condition: Condition,
+}
+
+/// 解析后的搜索查询，所有条件都满足时任务才匹配
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Query {
+    terms: Vec<Term>,
+}
+
+/// 词法分析得到的单词
+struct Token {
+    text: String,
+    /// 整个单词是否为引号括起的短语
+    quoted: bool,
+    negated: bool,
+}
+
+impl Query {
+    /// 解析搜索文本，语法错误时返回说明
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let terms = tokenize(input)?
+            .into_iter()
+            .map(|token| {
+                let condition = if token.quoted {
+                    Condition::Text(token.text.to_lowercase())
+                } else {
+                    parse_condition(&token.text)?
+                };
+                Ok(Term { negated: token.negated, condition })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Self { terms })
+    }
+    
+    /// 查询是否没有任何条件
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+    
+    /// 判断任务是否满足查询
+    pub fn matches(&self, todo: &Todo, list: &TodoList) -> bool {
+        let now = Local::now();
+        self.terms.iter().all(|term| condition_matches(&term.condition, todo, list, now) != term.negated)
+    }
+}
+
+/// 将搜索文本拆分为单词，支持引号短语和 - 取反
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        
+        let negated = c == '-';
+        if negated {
+            chars.next();
+            if chars.peek().is_none_or(|c| c.is_whitespace()) {
+                return Err("\"-\" 后缺少要排除的条件".to_string());
+            }
+        }
+        
+        let quoted = chars.peek() == Some(&'"');
+        let mut text = String::new();
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() {
+                break;
+            }
+            chars.next();
+            if c == '"' {
+                // 引号内的内容原样保留，可以包含空格
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == '"' {
+                        closed = true;
+                        break;
+                    }
+                    text.push(c);
+                }
+                if !closed {
+                    return Err(format!("引号未闭合: \"{}", text));
+                }
+            } else {
+                text.push(c);
+            }
+        }
+        
+        if text.is_empty() && !quoted {
+            continue;
+        }
+        tokens.push(Token { text, quoted, negated });
+    }
+    
+    Ok(tokens)
+}
+
+/// 解析单个条件，例如 tag:work、priority>=high 或普通关键词
+fn parse_condition(text: &str) -> Result<Condition, String> {
+    let Some(op_start) = text.find([':', '<', '>', '=']) else {
+        return Ok(Condition::Text(text.to_lowercase()));
+    };
+    
+    let field = text[..op_start].to_lowercase();
+    let rest = &text[op_start..];
+    let (comparison, value) = if let Some(value) = rest.strip_prefix(">=") {
+        (Comparison::Ge, value)
+    } else if let Some(value) = rest.strip_prefix("<=") {
+        (Comparison::Le, value)
+    } else if let Some(value) = rest.strip_prefix('>') {
+        (Comparison::Gt, value)
+    } else if let Some(value) = rest.strip_prefix('<') {
+        (Comparison::Lt, value)
+    } else {
+        (Comparison::Eq, &rest[1..])
+    };
+    
+    if field.is_empty() {
+        return Err(format!("\"{}\" 缺少字段名，例如 priority{}", text, rest));
+    }
+    if value.is_empty() {
+        return Err(format!("\"{}\" 缺少值", field));
+    }
+    
+    let equality_only = |condition: Condition| {
+        if comparison == Comparison::Eq {
+            Ok(condition)
+        } else {
+            Err(format!("\"{}\" 只支持 \":\"，不支持大小比较", field))
+        }
+    };
+    
+    match field.as_str() {
+        "tag" | "t" => equality_only(Condition::Tag(value.to_lowercase())),
+        "status" => equality_only(Condition::Status(value.to_lowercase())),
+        "is" => {
+            let state = match value.to_lowercase().as_str() {
+                "open" | "todo" => State::Open,
+                "done" | "completed" => State::Done,
+                "overdue" => State::Overdue,
+                "blocked" => State::Blocked,
+                "recurring" => State::Recurring,
+                "deferred" | "snoozed" => State::Deferred,
+                _ => {
+                    return Err(format!(
+                        "无效的状态 \"{}\"，可用: open, done, overdue, blocked, recurring, deferred",
+                        value
+                    ))
+                }
+            };
+            equality_only(Condition::Is(state))
+        }
+        "priority" | "p" => Ok(Condition::Priority(comparison, parse_priority(value)?)),
+        "due" => {
+            if value.eq_ignore_ascii_case("none") {
+                equality_only(Condition::NoDue)
+            } else {
+                Ok(Condition::Due(comparison, parse_date(value)?))
+            }
+        }
+        "created" => Ok(Condition::Created(comparison, parse_date(value)?)),
+        _ => Err(format!(
+            "未知的字段 \"{}\"，可用字段: tag, priority, due, created, is, status（包含冒号的关键词请用引号括起）",
+            field
+        )),
+    }
+}
+
+/// 解析优先级
+fn parse_priority(value: &str) -> Result<Priority, String> {
+    Priority::from_name(value).ok_or_else(|| format!(
+        "无效的优先级 \"{}\"，可用: low, medium, high, critical",
+        value
+    ))
+}
+
+/// 解析日期值：today、tomorrow、yesterday、带单位的偏移量（如 7d、-2w、12h）或 YYYY-MM-DD
+fn parse_date(value: &str) -> Result<DateValue, String> {
+    match value.to_lowercase().as_str() {
+        "today" => return Ok(DateValue::Day(0)),
+        "tomorrow" => return Ok(DateValue::Day(1)),
+        "yesterday" => return Ok(DateValue::Day(-1)),
+        _ => {}
+    }
+    
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(DateValue::Date(date));
+    }
+    
+    let invalid = || format!(
+        "无效的日期 \"{}\"，示例: 7d、-2w、12h、today、2024-05-01",
+        value
+    );
+    
+    let unit_start = value.len() - value.chars().last().map_or(0, char::len_utf8);
+    let amount: i64 = value[..unit_start].parse().map_err(|_| invalid())?;
+    let offset = match &value[unit_start..] {
+        "h" | "H" => Duration::try_hours(amount),
+        "d" | "D" => Duration::try_days(amount),
+        "w" | "W" => Duration::try_weeks(amount),
+        _ => return Err(invalid()),
+    };
+    
+    // 偏移量过大时无法换算成时间，现在加上偏移量也必须在可表示的范围内
+    match offset {
+        Some(offset) if Local::now().checked_add_signed(offset).is_some() => Ok(DateValue::Offset(offset)),
+        _ => Err(format!("日期偏移超出范围: \"{}\"", value)),
+    }
+}
+
+/// 判断任务是否满足单个条件
+fn condition_matches(condition: &Condition, todo: &Todo, list: &TodoList, now: DateTime<Local>) -> bool {
+    match condition {
+        Condition::Text(text) => {
+            todo.title.to_lowercase().contains(text)
+                || todo.description.to_lowercase().contains(text)
+                || todo.tags.iter().any(|tag| tag.to_lowercase().contains(text))
+                || subtasks_contain(&todo.subtasks, text)
+        }
+        Condition::Tag(tag) => todo.tags.iter().any(|t| t.to_lowercase() == *tag),
+        Condition::Priority(comparison, priority) => {
+            comparison.accepts(priority_to_number(&todo.priority).cmp(&priority_to_number(priority)))
+        }
+        Condition::Due(comparison, value) => todo.due_date.is_some_and(|due| value.compare(due, *comparison, now)),
+        Condition::NoDue => todo.due_date.is_none(),
+        Condition::Created(comparison, value) => value.compare(todo.created_at, *comparison, now),
+        Condition::Is(state) => match state {
+            State::Open => !todo.completed,
+            State::Done => todo.completed,
+            State::Overdue => todo.is_overdue(),
+            State::Blocked => list.is_blocked(todo),
+            State::Recurring => todo.recurrence.is_some(),
+            State::Deferred => todo.is_deferred(now),
+        },
+        Condition::Status(name) => list
+            .status_of(todo)
+            .is_some_and(|status| status.name.to_lowercase() == *name || status.id.to_lowercase() == *name),
+    }
+}
+
+/// 递归检查子任务标题是否包含关键词
+fn subtasks_contain(subtasks: &[SubTask], text: &str) -> bool {
+    subtasks
+        .iter()
+        .any(|s| s.title.to_lowercase().contains(text) || subtasks_contain(&s.children, text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    
+    /// 2024-05-15 10:00，星期三
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 5, 15, 10, 0, 0).unwrap()
+    }
+    
+    fn at(month: u32, day: u32, hour: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, month, day, hour, 0, 0).unwrap()
+    }
+    
+    fn condition(text: &str) -> Condition {
+        parse_condition(text).unwrap()
+    }
+    
+    fn error(input: &str) -> String {
+        Query::parse(input).unwrap_err()
+    }
+    
+    /// 判断任务是否满足查询中的所有条件，使用固定的当前时间
+    fn matches_at(input: &str, todo: &Todo, list: &TodoList) -> bool {
+        let query = Query::parse(input).unwrap();
+        query
+            .terms
+            .iter()
+            .all(|term| condition_matches(&term.condition, todo, list, now()) != term.negated)
+    }
+    
+    fn todo(title: &str) -> Todo {
+        let mut todo = Todo::new(title.to_string());
+        todo.created_at = at(5, 1, 9);
+        todo
+    }
+    
+    #[test]
+    fn tokenizes_quotes_and_negation() {
+        let query = Query::parse(r#"release "release notes" -tag:blocked tag:"my tag""#).unwrap();
+        assert_eq!(
+            query.terms,
+            vec![
+                Term { negated: false, condition: Condition::Text("release".to_string()) },
+                Term { negated: false, condition: Condition::Text("release notes".to_string()) },
+                Term { negated: true, condition: Condition::Tag("blocked".to_string()) },
+                Term { negated: false, condition: Condition::Tag("my tag".to_string()) },
+            ]
+        );
+        // 引号括起的短语即使包含冒号也是关键词
+        assert_eq!(
+            Query::parse(r#""tag:work""#).unwrap().terms[0].condition,
+            Condition::Text("tag:work".to_string())
+        );
+        assert!(Query::parse("   ").unwrap().is_empty());
+    }
+    
+    #[test]
+    fn tokenizer_errors() {
+        assert_eq!(error(r#""unclosed phrase"#), "引号未闭合: \"unclosed phrase");
+        assert_eq!(error("a - b"), "\"-\" 后缺少要排除的条件");
+        assert_eq!(error("a -"), "\"-\" 后缺少要排除的条件");
+    }
+    
+    #[test]
+    fn parses_fields() {
+        assert_eq!(condition("Tag:Work"), Condition::Tag("work".to_string()));
+        assert_eq!(condition("t:work"), Condition::Tag("work".to_string()));
+        assert_eq!(condition("status:进行中"), Condition::Status("进行中".to_string()));
+        assert_eq!(condition("priority:high"), Condition::Priority(Comparison::Eq, Priority::High));
+        assert_eq!(condition("p>=紧急"), Condition::Priority(Comparison::Ge, Priority::Critical));
+        assert_eq!(condition("due:none"), Condition::NoDue);
+        assert_eq!(condition("due:NONE"), Condition::NoDue);
+        assert_eq!(condition("created>-30d"), Condition::Created(Comparison::Gt, DateValue::Offset(Duration::days(-30))));
+        assert_eq!(condition("Release"), Condition::Text("release".to_string()));
+    }
+    
+    #[test]
+    fn parses_states() {
+        let cases = [
+            ("open", State::Open),
+            ("todo", State::Open),
+            ("done", State::Done),
+            ("completed", State::Done),
+            ("overdue", State::Overdue),
+            ("blocked", State::Blocked),
+            ("recurring", State::Recurring),
+            ("deferred", State::Deferred),
+            ("snoozed", State::Deferred),
+        ];
+        for (value, state) in cases {
+            assert_eq!(condition(&format!("is:{}", value)), Condition::Is(state), "{}", value);
+        }
+    }
+    
+    #[test]
+    fn parses_operators() {
+        let cases = [
+            ("p:low", Comparison::Eq),
+            ("p=low", Comparison::Eq),
+            ("p<low", Comparison::Lt),
+            ("p<=low", Comparison::Le),
+            ("p>low", Comparison::Gt),
+            ("p>=low", Comparison::Ge),
+        ];
+        for (text, comparison) in cases {
+            assert_eq!(condition(text), Condition::Priority(comparison, Priority::Low), "{}", text);
+        }
+    }
+    
+    #[test]
+    fn parses_relative_and_absolute_dates() {
+        assert_eq!(parse_date("today"), Ok(DateValue::Day(0)));
+        assert_eq!(parse_date("Tomorrow"), Ok(DateValue::Day(1)));
+        assert_eq!(parse_date("yesterday"), Ok(DateValue::Day(-1)));
+        assert_eq!(parse_date("7d"), Ok(DateValue::Offset(Duration::days(7))));
+        assert_eq!(parse_date("-2w"), Ok(DateValue::Offset(Duration::weeks(-2))));
+        assert_eq!(parse_date("12H"), Ok(DateValue::Offset(Duration::hours(12))));
+        assert_eq!(
+            parse_date("2024-05-01"),
+            Ok(DateValue::Date(NaiveDate::from_ymd_opt(2024, 5, 1).unwrap()))
+        );
+    }
+    
+    #[test]
+    fn reports_bad_input() {
+        assert_eq!(error("priority>=urgentish"), "无效的优先级 \"urgentish\"，可用: low, medium, high, critical");
+        assert_eq!(error(">=high"), "\">=high\" 缺少字段名，例如 priority>=high");
+        assert_eq!(error("tag:"), "\"tag\" 缺少值");
+        assert_eq!(error("tag>work"), "\"tag\" 只支持 \":\"，不支持大小比较");
+        assert_eq!(error("is>open"), "\"is\" 只支持 \":\"，不支持大小比较");
+        assert_eq!(error("due<none"), "\"due\" 只支持 \":\"，不支持大小比较");
+        assert_eq!(error("is:later"), "无效的状态 \"later\"，可用: open, done, overdue, blocked, recurring, deferred");
+        assert_eq!(error("due<7x"), "无效的日期 \"7x\"，示例: 7d、-2w、12h、today、2024-05-01");
+        assert_eq!(error("due<2024-13-01"), "无效的日期 \"2024-13-01\"，示例: 7d、-2w、12h、today、2024-05-01");
+        assert!(error("color:red").starts_with("未知的字段 \"color\""));
+    }
+    
+    #[test]
+    fn rejects_offsets_out_of_range() {
+        assert_eq!(error("due<9999999999999d"), "日期偏移超出范围: \"9999999999999d\"");
+        assert_eq!(error("due<99999999w"), "日期偏移超出范围: \"99999999w\"");
+        assert_eq!(error("created>-9223372036854775807h"), "日期偏移超出范围: \"-9223372036854775807h\"");
+    }
+    
+    #[test]
+    fn compares_out_of_range_offsets_without_panicking() {
+        let time = at(5, 20, 9);
+        let far_future = DateValue::Offset(Duration::weeks(99_999_999));
+        assert!(far_future.compare(time, Comparison::Lt, now()));
+        assert!(!far_future.compare(time, Comparison::Eq, now()));
+        let far_past = DateValue::Offset(Duration::weeks(-99_999_999));
+        assert!(far_past.compare(time, Comparison::Gt, now()));
+        assert!(DateValue::Day(i64::MAX).compare(time, Comparison::Lt, now()));
+    }
+    
+    #[test]
+    fn compares_dates() {
+        let due = at(5, 16, 9);
+        assert!(DateValue::Day(1).compare(due, Comparison::Eq, now()));
+        assert!(DateValue::Offset(Duration::days(7)).compare(due, Comparison::Lt, now()));
+        // 偏移量按时间比较：23小时后是明天9点
+        assert!(DateValue::Offset(Duration::hours(23)).compare(due, Comparison::Eq, now()));
+        assert!(!DateValue::Offset(Duration::hours(22)).compare(due, Comparison::Le, now()));
+        assert!(DateValue::Date(NaiveDate::from_ymd_opt(2024, 5, 16).unwrap()).compare(due, Comparison::Ge, now()));
+    }
+    
+    #[test]
+    fn matches_todos() {
+        let mut list = TodoList::default();
+        list.normalize_statuses();
+        
+        let mut release = todo("Release 1.0");
+        release.tags = vec!["Work".to_string()];
+        release.priority = Priority::High;
+        release.due_date = Some(at(5, 16, 9));
+        release.subtasks = vec![SubTask::new("Write notes".to_string())];
+        
+        let mut done = todo("Buy milk");
+        done.completed = true;
+        
+        let mut blocked = todo("Deploy");
+        blocked.blocked_by = vec![release.id.clone()];
+        list.add_todo(release.clone());
+        
+        assert!(matches_at("release", &release, &list));
+        assert!(matches_at("notes", &release, &list));
+        assert!(matches_at("tag:work priority>=high due<7d", &release, &list));
+        assert!(!matches_at("-tag:work", &release, &list));
+        assert!(!matches_at("due:none", &release, &list));
+        assert!(matches_at("due:none is:done", &done, &list));
+        assert!(!matches_at("is:open", &done, &list));
+        assert!(matches_at("is:blocked", &blocked, &list));
+        assert!(!matches_at("is:blocked", &release, &list));
+        assert!(matches_at("created<today created:2024-05-01", &release, &list));
+        assert!(matches_at("status:待办", &release, &list));
+        assert!(matches_at("status:done", &done, &list));
+    }
+}
@@ -0,0 +1,809 @@
+use crate::todo::{Priority, Todo};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, TimeZone, Weekday};
+
+/// 只指定日期时使用的默认时间
+const DEFAULT_HOUR: u32 = 18;
+
+/// 只说"今晚"、"明晚"时使用的默认时间
+const EVENING_HOUR: u32 = 20;
+
+/// 快速添加的解析结果
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuickAdd {
+    pub title: String,
+    pub due_date: Option<DateTime<Local>>,
+    pub tags: Vec<String>,
+    pub priority: Option<Priority>,
+    /// 预计用时（分钟）
+    pub estimate_minutes: Option<i64>,
+}
+
+impl QuickAdd {
+    /// 根据解析结果创建任务，未指定的字段使用默认值
+    pub fn into_todo(self) -> Todo {
+        let mut todo = Todo::new(self.title);
+        todo.due_date = self.due_date;
+        todo.tags = self.tags;
+        if let Some(priority) = self.priority {
+            todo.priority = priority;
+        }
+        todo.estimate_minutes = self.estimate_minutes;
+        todo
+    }
+}
+
+/// 从输入中识别出的时间信息
+#[derive(Default)]
+struct When {
+    date: Option<NaiveDate>,
+    time: Option<(u32, u32)>,
+    /// 相对当前时间的偏移，例如 3小时后
+    offset: Option<Duration>,
+    /// 是否为晚上（今晚、明晚），指定的小时数小于12时按下午计算
+    evening: bool,
+}
+
+impl When {
+    /// 合并另一个短语识别出的时间信息
+    fn merge(&mut self, other: When) {
+        self.date = other.date.or(self.date);
+        self.time = other.time.or(self.time);
+        self.offset = other.offset.or(self.offset);
+        self.evening |= other.evening;
+    }
+}
+
+/// 解析一行快速添加的输入，例如 `Deploy api tomorrow 17:00 #work !high ~30m` 或 `明天下午3点 开会 #工作`
+///
+/// - `#标签` 添加标签
+/// - `!high`、`!高` 设置优先级
+/// - `~30m`、`~1h30m`、`~1.5h` 设置预计用时
+/// - 日期和时间：today、tomorrow、friday、next monday、in 3 days、2024-05-20、5/20、17:00、5pm，
+///   以及 今天、明天、后天、今晚、周五、下周一、3天后、2小时后、5月20日、下午3点半 等
+///
+/// 5/20、5-20、17:00 这样的纯数字日期和时间只在日期词之后识别，5/20 和 17:00 在末尾时也识别。
+/// 其余内容作为标题。只指定时间且该时间今天已过时，截止时间为明天。
+pub fn parse(input: &str, now: DateTime<Local>) -> QuickAdd {
+    let today = now.date_naive();
+    let mut when = When::default();
+    let mut words: Vec<String> = Vec::new();
+    let mut tags: Vec<String> = Vec::new();
+    let mut priority = None;
+    let mut estimate_minutes = None;
+    
+    let tokens: Vec<&str> = input.split_whitespace().collect();
+    // 上一个单词是否为日期或时间，之后的纯数字日期和时间才会识别
+    let mut after_date = false;
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i];
+        
+        if let Some(tag) = token.strip_prefix('#').filter(|t| !t.is_empty()) {
+            if !tags.iter().any(|t| t == tag) {
+                tags.push(tag.to_string());
+            }
+            i += 1;
+            continue;
+        }
+        if let Some(p) = token.strip_prefix('!').and_then(Priority::from_name) {
+            priority = Some(p);
+            i += 1;
+            continue;
+        }
+        if let Some(minutes) = token.strip_prefix('~').and_then(parse_estimate) {
+            estimate_minutes = Some(minutes);
+            i += 1;
+            continue;
+        }
+        
+        let consumed = parse_english(&tokens[i..], today, &mut when, after_date);
+        if consumed > 0 {
+            after_date = true;
+            i += consumed;
+            continue;
+        }
+        
+        // 中文日期通常与标题连写，在单词开头或末尾查找并移除
+        let rest = strip_chinese(token, today, &mut when);
+        after_date = rest.chars().count() < token.chars().count();
+        if !rest.is_empty() {
+            words.push(rest);
+        }
+        i += 1;
+    }
+    
+    QuickAdd {
+        title: words.join(" "),
+        due_date: resolve_due(&when, now),
+        tags,
+        priority,
+        estimate_minutes,
+    }
+}
+
+/// 根据识别出的时间信息计算截止时间
+fn resolve_due(when: &When, now: DateTime<Local>) -> Option<DateTime<Local>> {
+    if let Some(offset) = when.offset {
+        return now.checked_add_signed(offset);
+    }
+    if when.date.is_none() && when.time.is_none() {
+        return None;
+    }
+    
+    let (mut hour, minute) = when.time.unwrap_or(if when.evening { (EVENING_HOUR, 0) } else { (DEFAULT_HOUR, 0) });
+    if when.evening && hour < 12 {
+        hour += 12;
+    }
+    
+    let at = |date: NaiveDate| {
+        date.and_hms_opt(hour, minute, 0)
+            .and_then(|naive| Local.from_local_datetime(&naive).earliest())
+    };
+    
+    match when.date {
+        Some(date) => at(date),
+        None => {
+            // 只指定了时间：今天已过则顺延到明天
+            let today = now.date_naive();
+            at(today).filter(|due| *due > now).or_else(|| at(today + Duration::days(1)))
+        }
+    }
+}
+
+/// 解析预计用时，例如 30m、1h、1h30m、1.5h、45（分钟）、2小时、20分钟
+fn parse_estimate(text: &str) -> Option<i64> {
+    if let Ok(minutes) = text.parse::<i64>() {
+        return Some(minutes).filter(|m| *m > 0 && Duration::try_minutes(*m).is_some());
+    }
+    
+    let mut total = 0.0;
+    let mut rest = text;
+    while !rest.is_empty() {
+        let number_len = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len());
+        let amount: f64 = rest[..number_len].parse().ok()?;
+        rest = &rest[number_len..];
+        
+        let (factor, unit_len) = ["小时", "h", "分钟", "min", "m"]
+            .iter()
+            .find(|unit| rest.starts_with(*unit))
+            .map(|unit| (if unit.starts_with(['小', 'h']) { 60.0 } else { 1.0 }, unit.len()))?;
+        total += amount * factor;
+        rest = &rest[unit_len..];
+    }
+    
+    let minutes = total.round() as i64;
+    Some(minutes).filter(|m| *m > 0 && Duration::try_minutes(*m).is_some())
+}
+
+/// 解析英文日期和时间短语，返回使用的单词数，不是日期时返回0
+///
+/// 纯数字日期和时间也常用于表示范围、比例等，例如 "chapters 3-5"、"2-3 apples"，
+/// 因此只在日期介词或其他日期之后（`after_date`）以及末尾识别。
+fn parse_english(tokens: &[&str], today: NaiveDate, when: &mut When, after_date: bool) -> usize {
+    let word = |k: usize| tokens.get(k).map(|t| t.to_lowercase()).unwrap_or_default();
+    let first = word(0);
+    
+    match first.as_str() {
+        // 日期前的介词一并移除
+        "on" | "at" | "by" | "due" => {
+            let consumed = parse_english(&tokens[1..], today, when, true);
+            return if consumed > 0 { consumed + 1 } else { 0 };
+        }
+        "today" => when.date = Some(today),
+        "tonight" => {
+            when.date = Some(today);
+            when.evening = true;
+        }
+        "tomorrow" | "tmr" => when.date = Some(today + Duration::days(1)),
+        "next" => {
+            let second = word(1);
+            if second == "week" {
+                when.date = Some(next_week_day(today, Weekday::Mon));
+            } else if let Some(weekday) = english_weekday(&second) {
+                when.date = Some(next_week_day(today, weekday));
+            } else {
+                return 0;
+            }
+            return 2;
+        }
+        "in" => {
+            let Ok(amount) = word(1).parse::<i64>() else {
+                return 0;
+            };
+            let unit = word(2);
+            let unit = unit.trim_end_matches('s');
+            let duration = match unit {
+                "day" => Duration::try_days(amount),
+                "week" => Duration::try_weeks(amount),
+                "hour" => Duration::try_hours(amount),
+                "minute" | "min" => Duration::try_minutes(amount),
+                _ => return 0,
+            };
+            let Some(date) = shift_date(today, duration) else {
+                return 0;
+            };
+            if matches!(unit, "day" | "week") {
+                when.date = Some(date);
+            } else {
+                when.offset = duration;
+            }
+            return 3;
+        }
+        _ => {
+            let last = tokens.len() == 1;
+            let bare_number = !first.ends_with("am") && !first.ends_with("pm");
+            // 带年份的日期不会与范围混淆，随时识别
+            let date_allowed = after_date
+                || NaiveDate::parse_from_str(&first, "%Y-%m-%d").is_ok()
+                || (last && first.contains('/'));
+            let time_allowed = after_date || last || !bare_number;
+            
+            if let Some(weekday) = english_weekday(&first) {
+                when.date = Some(upcoming_day(today, weekday));
+            } else if let Some(date) = parse_date_literal(&first, today).filter(|_| date_allowed) {
+                when.date = Some(date);
+            } else if let Some(time) = parse_time_literal(&first).filter(|_| time_allowed) {
+                when.time = Some(time);
+            } else {
+                return 0;
+            }
+        }
+    }
+    1
+}
+
+/// 英文星期几，只接受完整名称，避免把 sun、sat 等普通单词当作日期
+fn english_weekday(word: &str) -> Option<Weekday> {
+    match word {
+        "monday" => Some(Weekday::Mon),
+        "tuesday" => Some(Weekday::Tue),
+        "wednesday" => Some(Weekday::Wed),
+        "thursday" => Some(Weekday::Thu),
+        "friday" => Some(Weekday::Fri),
+        "saturday" => Some(Weekday::Sat),
+        "sunday" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// 解析 2024-05-20、5/20、5-20 形式的日期，省略年份时取最近的将来日期
+fn parse_date_literal(text: &str, today: NaiveDate) -> Option<NaiveDate> {
+    if let Ok(date) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        return Some(date);
+    }
+    
+    let (month, day) = text.split_once(['/', '-'])?;
+    let month: u32 = month.parse().ok()?;
+    let day: u32 = day.parse().ok()?;
+    let date = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+    if date < today {
+        NaiveDate::from_ymd_opt(today.year() + 1, month, day)
+    } else {
+        Some(date)
+    }
+}
+
+/// 解析 17:00、5pm、5:30pm、9am 形式的时间
+fn parse_time_literal(text: &str) -> Option<(u32, u32)> {
+    let (clock, meridiem) = if let Some(clock) = text.strip_suffix("am") {
+        (clock, Some(false))
+    } else if let Some(clock) = text.strip_suffix("pm") {
+        (clock, Some(true))
+    } else {
+        (text, None)
+    };
+    
+    let (hour, minute) = match clock.split_once(':') {
+        // 分钟必须是两位数，"16:9"、"1:1" 不是时间
+        Some((hour, minute)) if minute.len() == 2 => (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?),
+        Some(_) => return None,
+        // 不带 am/pm 的纯数字不是时间
+        None if meridiem.is_some() => (clock.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+    if minute >= 60 {
+        return None;
+    }
+    
+    let hour = match meridiem {
+        Some(_) if hour == 0 || hour > 12 => return None,
+        Some(false) => hour % 12,
+        Some(true) => hour % 12 + 12,
+        None if hour < 24 => hour,
+        None => return None,
+    };
+    Some((hour, minute))
+}
+
+/// 从单词中移除中文日期和时间短语，返回剩余的内容
+fn strip_chinese(word: &str, today: NaiveDate, when: &mut When) -> String {
+    let chars: Vec<char> = word.chars().collect();
+    // 找到的短语：(开始位置, 字符数, 识别出的时间信息)
+    let mut phrases: Vec<(usize, usize, When)> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        // 短语不能从数字中间开始，例如数值过大的 "99999999天后" 不能从第二位开始识别
+        let inside_number = i > 0 && chars[i - 1].is_ascii_digit() && chars[i].is_ascii_digit();
+        let mut found = When::default();
+        let matched = if inside_number { None } else { match_chinese(&chars[i..], today, &mut found) };
+        match matched {
+            Some(len) => {
+                phrases.push((i, len, found));
+                i += len;
+            }
+            None => i += 1,
+        }
+    }
+    
+    let spans: Vec<(usize, usize)> = phrases.iter().map(|(start, len, _)| (*start, start + len)).collect();
+    let anchored = anchored_phrases(&chars, &spans);
+    
+    let mut rest = String::new();
+    let mut next = 0;
+    for ((start, len, found), anchored) in phrases.into_iter().zip(anchored) {
+        if anchored {
+            rest.extend(&chars[next..start]);
+            when.merge(found);
+            next = start + len;
+        }
+    }
+    rest.extend(&chars[next..]);
+    rest
+}
+
+/// 判断单词中找到的每个短语是否作为日期或时间使用
+///
+/// 短语需要从单词开头或在单词末尾开始连续排列，例如 "明天下午3点开会"、"开会明天"，
+/// 并且不能与单词中剩余的数字相连。单词中间的短语是普通文字，例如 "修复3点问题"、"一周一次"、"两点一线"。
+fn anchored_phrases(chars: &[char], spans: &[(usize, usize)]) -> Vec<bool> {
+    let numeral_at = |index: usize| chars.get(index).is_some_and(|c| is_numeral(*c));
+    let count = spans.len();
+    
+    // 从单词开头连续排列，且后面不是数字
+    let mut from_start = vec![false; count];
+    for j in 0..count {
+        from_start[j] = spans[j].0 == 0 || (j > 0 && spans[j - 1].1 == spans[j].0 && from_start[j - 1]);
+    }
+    for j in (0..count).rev() {
+        let end = spans[j].1;
+        let followed = j + 1 < count && spans[j + 1].0 == end && from_start[j + 1];
+        from_start[j] &= end == chars.len() || followed || !numeral_at(end);
+    }
+    
+    // 连续排列到单词末尾，且前面不是数字
+    let mut to_end = vec![false; count];
+    for j in (0..count).rev() {
+        to_end[j] = spans[j].1 == chars.len() || (j + 1 < count && spans[j + 1].0 == spans[j].1 && to_end[j + 1]);
+    }
+    for j in 0..count {
+        let start = spans[j].0;
+        let preceded = j > 0 && spans[j - 1].1 == start && to_end[j - 1];
+        to_end[j] &= start == 0 || preceded || !numeral_at(start - 1);
+    }
+    
+    from_start.into_iter().zip(to_end).map(|(a, b)| a || b).collect()
+}
+
+/// 是否为阿拉伯数字或中文数字
+fn is_numeral(c: char) -> bool {
+    c.is_ascii_digit() || "零〇一二两三四五六七八九十百千".contains(c)
+}
+
+/// 尝试在开头匹配一个中文日期或时间短语，返回使用的字符数
+fn match_chinese(s: &[char], today: NaiveDate, when: &mut When) -> Option<usize> {
+    let starts_with = |prefix: &str| {
+        let prefix: Vec<char> = prefix.chars().collect();
+        s.starts_with(&prefix).then_some(prefix.len())
+    };
+    
+    // 相对日期
+    for (phrase, days, evening) in [
+        ("大后天", 3, false),
+        ("后天", 2, false),
+        ("明天", 1, false),
+        ("明晚", 1, true),
+        ("今天", 0, false),
+        ("今晚", 0, true),
+    ] {
+        if let Some(len) = starts_with(phrase) {
+            when.date = Some(today + Duration::days(days));
+            when.evening |= evening;
+            return Some(len);
+        }
+    }
+    
+    // 下周X、本周X、周X
+    for prefix in ["下周", "下星期", "下礼拜"] {
+        if let Some(len) = starts_with(prefix) {
+            return match s.get(len).and_then(|c| chinese_weekday(*c)) {
+                Some(weekday) => {
+                    when.date = Some(next_week_day(today, weekday));
+                    Some(len + 1)
+                }
+                None => {
+                    when.date = Some(next_week_day(today, Weekday::Mon));
+                    Some(len)
+                }
+            };
+        }
+    }
+    for (prefix, this_week) in [("本周", true), ("这周", true), ("周", false), ("星期", false), ("礼拜", false)] {
+        if let Some(len) = starts_with(prefix) {
+            if let Some(weekday) = s.get(len).and_then(|c| chinese_weekday(*c)) {
+                when.date = Some(if this_week {
+                    this_week_day(today, weekday)
+                } else {
+                    upcoming_day(today, weekday)
+                });
+                return Some(len + 1);
+            }
+        }
+    }
+    
+    // 带时段的时间，例如 下午3点半
+    for (period, afternoon) in [("上午", false), ("早上", false), ("凌晨", false), ("中午", false), ("下午", true), ("晚上", true)] {
+        if let Some(len) = starts_with(period) {
+            if let Some((hour, minute, time_len)) = match_clock(&s[len..]) {
+                let hour = if afternoon && hour < 12 { hour + 12 } else { hour };
+                when.time = Some((hour, minute));
+                return Some(len + time_len);
+            }
+        }
+    }
+    
+    if let Some((hour, minute, len)) = match_clock(s) {
+        when.time = Some((hour, minute));
+        return Some(len);
+    }
+    
+    // 数字开头的短语：N天后、N周后、N小时后、N分钟后、M月D日
+    let (amount, number_len) = parse_number(s)?;
+    let after = &s[number_len..];
+    let after_starts_with = |suffix: &str| {
+        let suffix: Vec<char> = suffix.chars().collect();
+        after.starts_with(&suffix).then_some(number_len + suffix.len())
+    };
+    
+    // (后缀, 时长, 是否只精确到日期)
+    let relative: [(&[&str], Option<Duration>, bool); 4] = [
+        (&["天后", "日后"], Duration::try_days(amount as i64), true),
+        (&["周后", "个星期后", "星期后", "个礼拜后"], Duration::try_weeks(amount as i64), true),
+        (&["小时后", "个小时后"], Duration::try_hours(amount as i64), false),
+        (&["分钟后"], Duration::try_minutes(amount as i64), false),
+    ];
+    for (suffixes, duration, whole_days) in relative {
+        for suffix in suffixes {
+            if let Some(len) = after_starts_with(suffix) {
+                let date = shift_date(today, duration)?;
+                if whole_days {
+                    when.date = Some(date);
+                } else {
+                    when.offset = duration;
+                }
+                return Some(len);
+            }
+        }
+    }
+    
+    if let Some(month_len) = after_starts_with("月") {
+        let (day, day_len) = parse_number(&s[month_len..])?;
+        let end = month_len + day_len;
+        let suffix_len = match s.get(end) {
+            Some('日') | Some('号') => 1,
+            _ => 0,
+        };
+        let date = parse_date_literal(&format!("{}/{}", amount, day), today)?;
+        when.date = Some(date);
+        return Some(end + suffix_len);
+    }
+    
+    None
+}
+
+/// 匹配 3点、3点半、3点15、3点15分 形式的时间，返回(时, 分, 使用的字符数)
+fn match_clock(s: &[char]) -> Option<(u32, u32, usize)> {
+    let (hour, number_len) = parse_number(s)?;
+    if s.get(number_len) != Some(&'点') || hour >= 24 {
+        return None;
+    }
+    let mut len = number_len + 1;
+    
+    let minute = if s.get(len) == Some(&'半') {
+        len += 1;
+        30
+    } else if let Some((minute, minute_len)) = parse_number(&s[len..])
+        // 中文分钟至少为十，"两点一线" 中的 "一" 不是分钟
+        .filter(|(m, _)| *m < 60 && (*m >= 10 || s[len].is_ascii_digit()))
+    {
+        len += minute_len;
+        if s.get(len) == Some(&'分') {
+            len += 1;
+        }
+        minute
+    } else {
+        0
+    };
+    
+    Some((hour, minute, len))
+}
+
+/// 解析开头的阿拉伯数字或中文数字（最大九十九），返回(数值, 使用的字符数)
+fn parse_number(s: &[char]) -> Option<(u32, usize)> {
+    let digits = s.iter().take_while(|c| c.is_ascii_digit()).count();
+    if digits > 0 {
+        let text: String = s[..digits].iter().collect();
+        return text.parse().ok().map(|n| (n, digits));
+    }
+    
+    let digit = |c: &char| match c {
+        '零' | '〇' => Some(0),
+        '一' => Some(1),
+        '二' | '两' => Some(2),
+        '三' => Some(3),
+        '四' => Some(4),
+        '五' => Some(5),
+        '六' => Some(6),
+        '七' => Some(7),
+        '八' => Some(8),
+        '九' => Some(9),
+        _ => None,
+    };
+    
+    match (s.first(), s.get(1), s.get(2)) {
+        // 十、十五
+        (Some('十'), ones, _) => match ones.and_then(digit) {
+            Some(ones) => Some((10 + ones, 2)),
+            None => Some((10, 1)),
+        },
+        // 二十、二十三
+        (Some(tens), Some('十'), ones) => {
+            let tens = digit(tens)?;
+            match ones.and_then(digit) {
+                Some(ones) => Some((tens * 10 + ones, 3)),
+                None => Some((tens * 10, 2)),
+            }
+        }
+        (Some(ones), _, _) => digit(ones).map(|n| (n, 1)),
+        _ => None,
+    }
+}
+
+/// 中文星期几，日和天都表示星期日
+fn chinese_weekday(c: char) -> Option<Weekday> {
+    match c {
+        '一' => Some(Weekday::Mon),
+        '二' => Some(Weekday::Tue),
+        '三' => Some(Weekday::Wed),
+        '四' => Some(Weekday::Thu),
+        '五' => Some(Weekday::Fri),
+        '六' => Some(Weekday::Sat),
+        '日' | '天' => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// 计算相对今天偏移后的日期，数值过大超出可表示的日期范围时返回None，此时不作为日期识别
+fn shift_date(today: NaiveDate, duration: Option<Duration>) -> Option<NaiveDate> {
+    duration.and_then(|duration| today.checked_add_signed(duration))
+}
+
+/// 今天或之后最近的星期几
+fn upcoming_day(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let days = (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+    today + Duration::days(days as i64)
+}
+
+/// 本周（周一开始）的星期几
+fn this_week_day(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    monday + Duration::days(weekday.num_days_from_monday() as i64)
+}
+
+/// 下周（周一开始）的星期几
+fn next_week_day(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    this_week_day(today, weekday) + Duration::weeks(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Timelike;
+    
+    /// 2024-05-15 是星期三
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 5, 15, 10, 0, 0).unwrap()
+    }
+    
+    fn due(input: &str) -> Option<(NaiveDate, u32, u32)> {
+        parse(input, now()).due_date.map(|d| (d.date_naive(), d.hour(), d.minute()))
+    }
+    
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+    
+    #[test]
+    fn parses_full_english_example() {
+        let result = parse("Deploy api tomorrow 17:00 #work #backend !high ~30m", now());
+        assert_eq!(result.title, "Deploy api");
+        assert_eq!(result.tags, vec!["work", "backend"]);
+        assert_eq!(result.priority, Some(Priority::High));
+        assert_eq!(result.estimate_minutes, Some(30));
+        assert_eq!(due("Deploy api tomorrow 17:00"), Some((date(5, 16), 17, 0)));
+    }
+    
+    #[test]
+    fn plain_text_has_no_metadata() {
+        let result = parse("buy milk", now());
+        assert_eq!(result.title, "buy milk");
+        assert_eq!(result.due_date, None);
+        assert_eq!(result.priority, None);
+        assert_eq!(result.estimate_minutes, None);
+        assert!(result.tags.is_empty());
+    }
+    
+    #[test]
+    fn parses_english_dates() {
+        assert_eq!(due("report today"), Some((date(5, 15), DEFAULT_HOUR, 0)));
+        assert_eq!(due("movie tonight"), Some((date(5, 15), EVENING_HOUR, 0)));
+        assert_eq!(due("report friday"), Some((date(5, 17), DEFAULT_HOUR, 0)));
+        assert_eq!(due("report wednesday"), Some((date(5, 15), DEFAULT_HOUR, 0)));
+        assert_eq!(due("pay rent next friday 9am"), Some((date(5, 24), 9, 0)));
+        assert_eq!(due("plan next week"), Some((date(5, 20), DEFAULT_HOUR, 0)));
+        assert_eq!(due("call bob in 2 days"), Some((date(5, 17), DEFAULT_HOUR, 0)));
+        assert_eq!(due("review on 2024-06-01 at 5:30pm"), Some((date(6, 1), 17, 30)));
+        assert_eq!(due("renew 5/20"), Some((date(5, 20), DEFAULT_HOUR, 0)));
+        assert_eq!(due("renew 1/2"), Some((NaiveDate::from_ymd_opt(2025, 1, 2).unwrap(), DEFAULT_HOUR, 0)));
+    }
+    
+    #[test]
+    fn numeric_ranges_and_ratios_stay_in_title() {
+        for input in ["1:1 with Bob", "Read chapters 3-5", "Buy 2-3 apples", "Export video 16:9", "Call 5/20 hotline"] {
+            let result = parse(input, now());
+            assert_eq!(result.title, input);
+            assert_eq!(result.due_date, None);
+        }
+    }
+    
+    #[test]
+    fn numeric_dates_after_date_words_are_dates() {
+        assert_eq!(due("renew by 5-20"), Some((date(5, 20), DEFAULT_HOUR, 0)));
+        assert_eq!(due("renew due 5/20 noon"), Some((date(5, 20), DEFAULT_HOUR, 0)));
+        assert_eq!(due("sync friday 9:30 with Bob"), Some((date(5, 17), 9, 30)));
+        assert_eq!(parse("sync friday 9:30 with Bob", now()).title, "sync with Bob");
+        assert_eq!(due("sync at 9:30 with Bob"), Some((date(5, 16), 9, 30)));
+        assert_eq!(due("明天 9:30 开会"), Some((date(5, 16), 9, 30)));
+        assert_eq!(due("ship 2024-06-01 release"), Some((date(6, 1), DEFAULT_HOUR, 0)));
+        assert_eq!(due("standup 9am daily"), Some((date(5, 16), 9, 0)));
+    }
+    
+    #[test]
+    fn removes_prepositions_only_before_dates() {
+        assert_eq!(parse("call mom on friday", now()).title, "call mom");
+        assert_eq!(parse("sit on chair", now()).title, "sit on chair");
+        assert_eq!(parse("sat in sun", now()).due_date, None);
+    }
+    
+    #[test]
+    fn time_only_rolls_over_when_passed() {
+        assert_eq!(due("standup 9:00"), Some((date(5, 16), 9, 0)));
+        assert_eq!(due("lunch 12:30"), Some((date(5, 15), 12, 30)));
+    }
+    
+    #[test]
+    fn parses_chinese_dates() {
+        assert_eq!(due("明天 写周报"), Some((date(5, 16), DEFAULT_HOUR, 0)));
+        assert_eq!(due("后天 体检"), Some((date(5, 17), DEFAULT_HOUR, 0)));
+        assert_eq!(due("下周一 开会"), Some((date(5, 20), DEFAULT_HOUR, 0)));
+        assert_eq!(due("下周 规划"), Some((date(5, 20), DEFAULT_HOUR, 0)));
+        assert_eq!(due("周五 交报告"), Some((date(5, 17), DEFAULT_HOUR, 0)));
+        assert_eq!(due("本周一 补交"), Some((date(5, 13), DEFAULT_HOUR, 0)));
+        assert_eq!(due("星期天 休息"), Some((date(5, 19), DEFAULT_HOUR, 0)));
+        assert_eq!(due("3天后 复查"), Some((date(5, 18), DEFAULT_HOUR, 0)));
+        assert_eq!(due("二十天后 续费"), Some((date(6, 4), DEFAULT_HOUR, 0)));
+        assert_eq!(due("两周后 复盘"), Some((date(5, 29), DEFAULT_HOUR, 0)));
+        assert_eq!(due("5月20日 体检"), Some((date(5, 20), DEFAULT_HOUR, 0)));
+    }
+    
+    #[test]
+    fn parses_chinese_times() {
+        assert_eq!(due("明天下午3点开会"), Some((date(5, 16), 15, 0)));
+        assert_eq!(due("今晚8点半 看电影"), Some((date(5, 15), 20, 30)));
+        assert_eq!(due("明晚 聚餐"), Some((date(5, 16), EVENING_HOUR, 0)));
+        assert_eq!(due("周五上午十点十五分 面试"), Some((date(5, 17), 10, 15)));
+        assert_eq!(due("下午两点 电话"), Some((date(5, 15), 14, 0)));
+    }
+    
+    #[test]
+    fn chinese_phrases_are_removed_from_title() {
+        let result = parse("明天下午3点开会 #工作 !紧急", now());
+        assert_eq!(result.title, "开会");
+        assert_eq!(result.tags, vec!["工作"]);
+        assert_eq!(result.priority, Some(Priority::Critical));
+        
+        // "周报"中的"周"后面不是星期几，不是日期
+        assert_eq!(parse("写周报", now()).title, "写周报");
+        assert_eq!(parse("写周报", now()).due_date, None);
+    }
+    
+    #[test]
+    fn chinese_phrases_at_word_end_are_dates() {
+        let result = parse("开会明天下午3点", now());
+        assert_eq!(result.title, "开会");
+        assert_eq!(due("开会明天下午3点"), Some((date(5, 16), 15, 0)));
+        
+        let result = parse("交报告周五", now());
+        assert_eq!(result.title, "交报告");
+        assert_eq!(due("交报告周五"), Some((date(5, 17), DEFAULT_HOUR, 0)));
+    }
+    
+    #[test]
+    fn chinese_phrases_inside_words_stay_in_title() {
+        for input in ["修复3点问题", "一周一次", "两点一线", "每星期五例会"] {
+            let result = parse(input, now());
+            assert_eq!(result.title, input);
+            assert_eq!(result.due_date, None);
+        }
+        
+        // 短语与剩余的数字相连时也不是日期
+        assert_eq!(parse("明天3", now()).title, "明天3");
+        assert_eq!(parse("明天3", now()).due_date, None);
+        assert_eq!(parse("3明天", now()).title, "3明天");
+        
+        // 中文分钟至少为十，阿拉伯数字不限
+        assert_eq!(due("十点十分 开会"), Some((date(5, 15), 10, 10)));
+        assert_eq!(due("十点1 开会"), Some((date(5, 15), 10, 1)));
+        assert_eq!(due("两点一 开会"), None);
+    }
+    
+    #[test]
+    fn relative_hours_use_current_time() {
+        let result = parse("3小时后 提交", now());
+        assert_eq!(result.due_date, Some(now() + Duration::hours(3)));
+        assert_eq!(result.title, "提交");
+        assert_eq!(parse("deploy in 2 hours", now()).due_date, Some(now() + Duration::hours(2)));
+    }
+    
+    #[test]
+    fn parses_estimates() {
+        assert_eq!(parse_estimate("30m"), Some(30));
+        assert_eq!(parse_estimate("1h"), Some(60));
+        assert_eq!(parse_estimate("1h30m"), Some(90));
+        assert_eq!(parse_estimate("1.5h"), Some(90));
+        assert_eq!(parse_estimate("45"), Some(45));
+        assert_eq!(parse_estimate("2小时"), Some(120));
+        assert_eq!(parse_estimate("20分钟"), Some(20));
+        assert_eq!(parse_estimate("soon"), None);
+        assert_eq!(parse_estimate("0m"), None);
+        assert_eq!(parse_estimate("9999999999999999m"), None);
+        assert_eq!(parse_estimate("99999999999999999999h"), None);
+    }
+    
+    #[test]
+    fn huge_relative_dates_stay_in_title() {
+        for input in ["report in 9999999999999 days", "report in 99999999 weeks", "report in 9999999999999 hours"] {
+            let result = parse(input, now());
+            assert_eq!(result.title, input);
+            assert_eq!(result.due_date, None);
+        }
+        for input in ["写报告 99999999天后", "写报告 4000000000周后", "写报告 4000000000小时后"] {
+            let result = parse(input, now());
+            assert_eq!(result.title, input);
+            assert_eq!(result.due_date, None);
+        }
+        
+        // 范围内的大数值仍然识别为日期
+        let result = parse("report in 3650 days", now());
+        assert_eq!(result.title, "report");
+        assert_eq!(due("report in 3650 days").map(|(date, _, _)| date), Some(date(5, 15) + Duration::days(3650)));
+    }
+    
+    #[test]
+    fn unknown_markers_stay_in_title() {
+        let result = parse("fix #1 bug !maybe ~later #", now());
+        assert_eq!(result.title, "fix bug !maybe ~later #");
+        assert_eq!(result.tags, vec!["1"]);
+        assert_eq!(result.priority, None);
+        assert_eq!(result.estimate_minutes, None);
+    }
+}
@@ -0,0 +1,559 @@
+use crate::schema;
+use crate::storage;
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// 提醒时间类型
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ReminderKind {
+    /// 指定时间提醒
+    Absolute(DateTime<Local>),
+    /// 截止时间前若干分钟提醒
+    BeforeDue(i64),
+}
+
+/// 任务提醒
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Reminder {
+    pub id: String,
+    pub kind: ReminderKind,
+    /// 已触发的提醒时间，提醒时间变化后会重新触发
+    #[serde(default)]
+    pub fired_for: Option<DateTime<Local>>,
+}
+
+impl Reminder {
+    pub fn new(kind: ReminderKind) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            kind,
+            fired_for: None,
+        }
+    }
+
+    /// 计算提醒时间，相对截止时间的提醒在没有截止时间时返回None
+    pub fn trigger_time(&self, due_date: Option<DateTime<Local>>) -> Option<DateTime<Local>> {
+        match &self.kind {
+            ReminderKind::Absolute(at) => Some(*at),
+            ReminderKind::BeforeDue(minutes) => due_date.map(|due| due - Duration::minutes(*minutes)),
+        }
+    }
+
+    /// 检查当前提醒时间是否尚未触发
+    pub fn is_pending(&self, due_date: Option<DateTime<Local>>) -> bool {
+        match self.trigger_time(due_date) {
+            Some(at) => self.fired_for != Some(at),
+            None => false,
+        }
+    }
+
+    /// 获取提醒的描述
+    pub fn describe(&self) -> String {
+        match &self.kind {
+            ReminderKind::Absolute(at) => format!("{} 提醒", at.format("%Y-%m-%d %H:%M")),
+            ReminderKind::BeforeDue(0) => "截止时提醒".to_string(),
+            ReminderKind::BeforeDue(minutes) if minutes % 1440 == 0 => format!("截止前{}天提醒", minutes / 1440),
+            ReminderKind::BeforeDue(minutes) if minutes % 60 == 0 => format!("截止前{}小时提醒", minutes / 60),
+            ReminderKind::BeforeDue(minutes) => format!("截止前{}分钟提醒", minutes),
+        }
+    }
+}
+
+/// 待触发的提醒
+#[derive(Clone, Debug)]
+pub struct ScheduledReminder {
+    pub todo_id: String,
+    pub reminder_id: String,
+    pub title: String,
+    pub due_date: Option<DateTime<Local>>,
+    pub at: DateTime<Local>,
+}
+
+/// 已触发的提醒，由调度线程发回界面线程
+#[derive(Clone, Debug)]
+pub struct FiredReminder {
+    pub todo_id: String,
+    pub reminder_id: String,
+    pub title: String,
+    pub at: DateTime<Local>,
+    /// 是否为应用未运行期间错过的提醒
+    pub missed: bool,
+}
+
+/// 通知内容
+#[derive(Clone, Debug)]
+pub struct Notification {
+    pub todo_id: String,
+    pub title: String,
+    pub body: String,
+}
+
+/// 通知发送方式
+pub trait Notifier: Send {
+    /// 发送通知
+    fn notify(&self, notification: &Notification) -> Result<(), String>;
+}
+
+/// 系统桌面通知
+pub struct DesktopNotifier;
+
+impl Notifier for DesktopNotifier {
+    fn notify(&self, notification: &Notification) -> Result<(), String> {
+        // 通过环境变量传递内容，避免命令行转义问题
+        #[cfg(target_os = "windows")]
+        let mut command = {
+            let mut command = Command::new("powershell");
+            command.args([
+                "-NoProfile",
+                "-WindowStyle",
+                "Hidden",
+                "-Command",
+                "Add-Type -AssemblyName System.Windows.Forms; \
+                 $n = New-Object System.Windows.Forms.NotifyIcon; \
+                 $n.Icon = [System.Drawing.SystemIcons]::Information; \
+                 $n.Visible = $true; \
+                 $n.ShowBalloonTip(10000, $env:RODO_TITLE, $env:RODO_BODY, 'Info'); \
+                 Start-Sleep -Seconds 10; $n.Dispose()",
+            ]);
+            command
+        };
+        
+        #[cfg(target_os = "macos")]
+        let mut command = {
+            let mut command = Command::new("osascript");
+            command.args([
+                "-e",
+                "display notification (system attribute \"RODO_BODY\") with title (system attribute \"RODO_TITLE\")",
+            ]);
+            command
+        };
+        
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        let mut command = {
+            let mut command = Command::new("notify-send");
+            command.args(["--app-name=Rodo", &notification.title, &notification.body]);
+            command
+        };
+        
+        command
+            .env("RODO_TITLE", &notification.title)
+            .env("RODO_BODY", &notification.body);
+        spawn_and_reap(&mut command).map_err(|e| format!("发送桌面通知失败: {}", e))
+    }
+}
+
+/// 自定义命令通知，通知内容通过环境变量 RODO_TITLE、RODO_BODY、RODO_TODO_ID 传递
+pub struct CommandNotifier {
+    pub command: String,
+}
+
+impl Notifier for CommandNotifier {
+    fn notify(&self, notification: &Notification) -> Result<(), String> {
+        #[cfg(target_os = "windows")]
+        let mut command = {
+            let mut command = Command::new("cmd");
+            command.args(["/C", &self.command]);
+            command
+        };
+        
+        #[cfg(not(target_os = "windows"))]
+        let mut command = {
+            let mut command = Command::new("sh");
+            command.args(["-c", &self.command]);
+            command
+        };
+        
+        command
+            .env("RODO_TITLE", &notification.title)
+            .env("RODO_BODY", &notification.body)
+            .env("RODO_TODO_ID", &notification.todo_id);
+        spawn_and_reap(&mut command).map_err(|e| format!("执行提醒命令失败: {}", e))
+    }
+}
+
+/// 启动外部程序，并在后台线程中等待它结束，避免留下僵尸进程
+fn spawn_and_reap(command: &mut Command) -> std::io::Result<()> {
+    let mut child = command.spawn()?;
+    std::thread::spawn(move || {
+        if let Err(err) = child.wait() {
+            eprintln!("等待提醒进程结束失败: {}", err);
+        }
+    });
+    Ok(())
+}
+
+/// 提醒设置
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReminderSettings {
+    /// 是否发送系统桌面通知
+    pub desktop_notifications: bool,
+    /// 提醒时执行的自定义命令，为空表示不执行
+    pub command: String,
+}
+
+impl Default for ReminderSettings {
+    fn default() -> Self {
+        Self {
+            desktop_notifications: true,
+            command: String::new(),
+        }
+    }
+}
+
+impl ReminderSettings {
+    /// 根据设置创建通知发送方式
+    pub fn notifiers(&self) -> Vec<Box<dyn Notifier>> {
+        let mut notifiers: Vec<Box<dyn Notifier>> = Vec::new();
+        if self.desktop_notifications {
+            notifiers.push(Box::new(DesktopNotifier));
+        }
+        if !self.command.trim().is_empty() {
+            notifiers.push(Box::new(CommandNotifier {
+                command: self.command.trim().to_string(),
+            }));
+        }
+        notifiers
+    }
+    
+    /// 保存提醒设置到文件
+    pub fn save(&self) -> Result<(), String> {
+        let path = ReminderSettings::get_settings_file_path()?;
+        let serialized = schema::REMINDER_SETTINGS.encode(self).map_err(|e| format!("序列化提醒设置失败: {}", e))?;
+        storage::write_atomic(&path, serialized.as_bytes()).map_err(|e| format!("写入提醒设置文件失败: {}", e))?;
+        Ok(())
+    }
+    
+    /// 从文件加载提醒设置
+    pub fn load() -> Self {
+        // 如果加载失败，使用默认设置
+        ReminderSettings::try_load().unwrap_or_default()
+    }
+    
+    /// 尝试从文件加载提醒设置
+    fn try_load() -> Result<Self, String> {
+        let path = ReminderSettings::get_settings_file_path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        
+        let data = std::fs::read_to_string(path)
+            .map_err(|e| format!("读取提醒设置文件失败: {}", e))?;
+            
+        schema::REMINDER_SETTINGS.decode(&data)
+            .map_err(|e| format!("解析提醒设置JSON失败: {}", e))
+    }
+    
+    /// 获取提醒设置文件路径
+    fn get_settings_file_path() -> Result<PathBuf, String> {
+        let app_dirs = match directories::ProjectDirs::from("com", "rodo", "rodo") {
+            Some(dirs) => dirs,
+            None => return Err("无法获取应用数据目录".to_string()),
+        };
+        
+        let data_dir = app_dirs.data_dir();
+        std::fs::create_dir_all(data_dir).map_err(|e| format!("无法创建数据目录: {}", e))?;
+        
+        Ok(data_dir.join("reminder_settings.json"))
+    }
+}
+
+/// 调度计划中的提醒，以及它首次提交给调度器的时间
+#[derive(Clone, Debug)]
+struct ScheduleEntry {
+    reminder: ScheduledReminder,
+    scheduled_at: DateTime<Local>,
+}
+
+/// 后台提醒调度器
+///
+/// 界面线程通过 `set_schedule` 提交待触发的提醒，调度线程到点后通过通知方式发送提醒，
+/// 并把已触发的提醒发回界面线程，由界面线程显示提示并记录触发状态。
+///
+/// 界面线程只提交当前工作区的提醒，其他工作区的提醒在切换过去后才会触发，
+/// 此时已过提醒时间的提醒标记为错过的提醒。
+pub struct ReminderScheduler {
+    schedule: Arc<Mutex<Vec<ScheduleEntry>>>,
+    notifiers: Arc<Mutex<Vec<Box<dyn Notifier>>>>,
+    fired_rx: Receiver<FiredReminder>,
+}
+
+impl ReminderScheduler {
+    /// 启动调度线程，`ctx` 用于在提醒触发时唤醒界面
+    pub fn start(notifiers: Vec<Box<dyn Notifier>>, ctx: Option<egui::Context>) -> Self {
+        let schedule = Arc::new(Mutex::new(Vec::new()));
+        let notifiers = Arc::new(Mutex::new(notifiers));
+        let (fired_tx, fired_rx) = mpsc::channel();
+        
+        let thread_schedule = Arc::clone(&schedule);
+        let thread_notifiers = Arc::clone(&notifiers);
+        std::thread::spawn(move || {
+            Self::run(thread_schedule, thread_notifiers, fired_tx, ctx);
+        });
+        
+        Self {
+            schedule,
+            notifiers,
+            fired_rx,
+        }
+    }
+    
+    /// 调度线程主循环
+    fn run(
+        schedule: Arc<Mutex<Vec<ScheduleEntry>>>,
+        notifiers: Arc<Mutex<Vec<Box<dyn Notifier>>>>,
+        fired_tx: Sender<FiredReminder>,
+        ctx: Option<egui::Context>,
+    ) {
+        // 已发送的提醒，避免界面线程记录触发状态前重复发送
+        let mut sent: HashSet<(String, DateTime<Local>)> = HashSet::new();
+        
+        loop {
+            let due = match schedule.lock() {
+                Ok(schedule) => Self::take_due(&schedule, &mut sent, Local::now()),
+                Err(_) => return,
+            };
+            
+            for entry in due {
+                let fired = match notifiers.lock() {
+                    Ok(notifiers) => Self::notify_all(entry, &notifiers),
+                    Err(_) => return,
+                };
+                if fired_tx.send(fired).is_err() {
+                    // 界面已关闭
+                    return;
+                }
+                if let Some(ctx) = &ctx {
+                    ctx.request_repaint();
+                }
+            }
+            
+            std::thread::sleep(std::time::Duration::from_secs(1));
+        }
+    }
+    
+    /// 取出已到提醒时间且尚未发送的提醒，并记录为已发送
+    fn take_due(
+        schedule: &[ScheduleEntry],
+        sent: &mut HashSet<(String, DateTime<Local>)>,
+        now: DateTime<Local>,
+    ) -> Vec<ScheduleEntry> {
+        // 界面线程记录触发状态后提醒会从计划中移除，任务删除时也一样，此后不再需要记录
+        sent.retain(|(id, at)| schedule.iter().any(|e| e.reminder.reminder_id == *id && e.reminder.at == *at));
+        
+        let due: Vec<ScheduleEntry> = schedule
+            .iter()
+            .filter(|e| e.reminder.at <= now && !sent.contains(&(e.reminder.reminder_id.clone(), e.reminder.at)))
+            .cloned()
+            .collect();
+        for entry in &due {
+            sent.insert((entry.reminder.reminder_id.clone(), entry.reminder.at));
+        }
+        due
+    }
+    
+    /// 通过所有通知方式发送提醒，返回发回界面线程的触发记录
+    fn notify_all(entry: ScheduleEntry, notifiers: &[Box<dyn Notifier>]) -> FiredReminder {
+        let reminder = entry.reminder;
+        let body = match reminder.due_date {
+            Some(due) => format!("截止时间: {}", due.format("%Y-%m-%d %H:%M")),
+            None => "任务提醒".to_string(),
+        };
+        let notification = Notification {
+            todo_id: reminder.todo_id.clone(),
+            title: reminder.title.clone(),
+            body,
+        };
+        
+        for notifier in notifiers {
+            if let Err(err) = notifier.notify(&notification) {
+                eprintln!("{}", err);
+            }
+        }
+        
+        FiredReminder {
+            todo_id: reminder.todo_id,
+            reminder_id: reminder.reminder_id,
+            title: reminder.title,
+            at: reminder.at,
+            // 提交给调度器时已过提醒时间，说明应用未运行或提醒所在的工作区未打开
+            missed: reminder.at < entry.scheduled_at,
+        }
+    }
+    
+    /// 用新的提醒替换计划，已在计划中的提醒保留首次提交的时间
+    fn merge_schedule(
+        current: &[ScheduleEntry],
+        reminders: Vec<ScheduledReminder>,
+        now: DateTime<Local>,
+    ) -> Vec<ScheduleEntry> {
+        reminders
+            .into_iter()
+            .map(|reminder| {
+                let scheduled_at = current
+                    .iter()
+                    .find(|e| e.reminder.reminder_id == reminder.reminder_id && e.reminder.at == reminder.at)
+                    .map_or(now, |e| e.scheduled_at);
+                ScheduleEntry { reminder, scheduled_at }
+            })
+            .collect()
+    }
+    
+    /// 更新待触发的提醒
+    pub fn set_schedule(&self, reminders: Vec<ScheduledReminder>) {
+        if let Ok(mut schedule) = self.schedule.lock() {
+            *schedule = Self::merge_schedule(&schedule, reminders, Local::now());
+        }
+    }
+    
+    /// 更新通知发送方式
+    pub fn set_notifiers(&self, notifiers: Vec<Box<dyn Notifier>>) {
+        if let Ok(mut current) = self.notifiers.lock() {
+            *current = notifiers;
+        }
+    }
+    
+    /// 取出所有已触发的提醒
+    pub fn poll_fired(&self) -> Vec<FiredReminder> {
+        self.fired_rx.try_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    
+    fn at(hour: u32, minute: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 5, 15, hour, minute, 0).unwrap()
+    }
+    
+    /// 记录收到的通知的通知方式
+    struct RecordingNotifier(Arc<Mutex<Vec<Notification>>>);
+    
+    impl Notifier for RecordingNotifier {
+        fn notify(&self, notification: &Notification) -> Result<(), String> {
+            self.0.lock().unwrap().push(notification.clone());
+            Ok(())
+        }
+    }
+    
+    fn scheduled(reminder_id: &str, time: DateTime<Local>) -> ScheduledReminder {
+        ScheduledReminder {
+            todo_id: format!("todo-{}", reminder_id),
+            reminder_id: reminder_id.to_string(),
+            title: "写周报".to_string(),
+            due_date: Some(at(18, 0)),
+            at: time,
+        }
+    }
+    
+    /// 模拟调度线程在 `now` 的一次检查，返回发回界面线程的触发记录
+    fn tick(
+        schedule: &[ScheduleEntry],
+        sent: &mut HashSet<(String, DateTime<Local>)>,
+        notifiers: &[Box<dyn Notifier>],
+        now: DateTime<Local>,
+    ) -> Vec<FiredReminder> {
+        ReminderScheduler::take_due(schedule, sent, now)
+            .into_iter()
+            .map(|entry| ReminderScheduler::notify_all(entry, notifiers))
+            .collect()
+    }
+    
+    #[test]
+    fn absolute_reminder_ignores_due_date() {
+        let mut reminder = Reminder::new(ReminderKind::Absolute(at(9, 0)));
+        assert_eq!(reminder.trigger_time(None), Some(at(9, 0)));
+        assert_eq!(reminder.trigger_time(Some(at(18, 0))), Some(at(9, 0)));
+        assert!(reminder.is_pending(None));
+        
+        reminder.fired_for = Some(at(9, 0));
+        assert!(!reminder.is_pending(None));
+    }
+    
+    #[test]
+    fn before_due_reminder_follows_due_date() {
+        let mut reminder = Reminder::new(ReminderKind::BeforeDue(90));
+        assert_eq!(reminder.trigger_time(None), None);
+        assert!(!reminder.is_pending(None));
+        assert_eq!(reminder.trigger_time(Some(at(18, 0))), Some(at(16, 30)));
+        assert!(reminder.is_pending(Some(at(18, 0))));
+        
+        reminder.fired_for = Some(at(16, 30));
+        assert!(!reminder.is_pending(Some(at(18, 0))));
+        // 截止时间改变后提醒时间随之改变，需要重新提醒
+        assert!(reminder.is_pending(Some(at(19, 0))));
+    }
+    
+    #[test]
+    fn past_reminders_are_caught_up_as_missed() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let notifiers: Vec<Box<dyn Notifier>> = vec![Box::new(RecordingNotifier(Arc::clone(&received)))];
+        let schedule = ReminderScheduler::merge_schedule(
+            &[],
+            vec![scheduled("past", at(8, 0)), scheduled("future", at(10, 0))],
+            at(9, 0),
+        );
+        let mut sent = HashSet::new();
+        
+        let fired = tick(&schedule, &mut sent, &notifiers, at(9, 0));
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].reminder_id, "past");
+        assert!(fired[0].missed);
+        
+        let fired = tick(&schedule, &mut sent, &notifiers, at(10, 0));
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].reminder_id, "future");
+        assert!(!fired[0].missed);
+        
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[0].todo_id, "todo-past");
+        assert_eq!(received[0].body, "截止时间: 2024-05-15 18:00");
+    }
+    
+    #[test]
+    fn rescheduling_keeps_first_scheduled_time() {
+        let schedule = ReminderScheduler::merge_schedule(&[], vec![scheduled("a", at(10, 0))], at(9, 0));
+        // 提醒时间刚过、调度线程尚未检查时再次提交，提醒仍按时触发而不是错过
+        let schedule = ReminderScheduler::merge_schedule(&schedule, vec![scheduled("a", at(10, 0))], at(10, 0));
+        assert_eq!(schedule[0].scheduled_at, at(9, 0));
+        
+        let fired = tick(&schedule, &mut HashSet::new(), &[], at(10, 0));
+        assert!(!fired[0].missed);
+        
+        // 提醒时间改变后视为新的提醒
+        let schedule = ReminderScheduler::merge_schedule(&schedule, vec![scheduled("a", at(8, 0))], at(10, 0));
+        assert_eq!(schedule[0].scheduled_at, at(10, 0));
+        assert!(tick(&schedule, &mut HashSet::new(), &[], at(10, 0))[0].missed);
+    }
+    
+    #[test]
+    fn sent_reminders_are_not_resent_until_rescheduled() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let notifiers: Vec<Box<dyn Notifier>> = vec![Box::new(RecordingNotifier(Arc::clone(&received)))];
+        let schedule = ReminderScheduler::merge_schedule(&[], vec![scheduled("a", at(9, 0))], at(8, 0));
+        let mut sent = HashSet::new();
+        
+        // 界面线程记录触发状态前提醒仍在计划中，不会重复发送
+        assert_eq!(tick(&schedule, &mut sent, &notifiers, at(9, 0)).len(), 1);
+        assert!(tick(&schedule, &mut sent, &notifiers, at(9, 1)).is_empty());
+        assert_eq!(received.lock().unwrap().len(), 1);
+        
+        // 记录触发状态后提醒从计划中移除，发送记录随之清除
+        assert!(tick(&[], &mut sent, &notifiers, at(9, 2)).is_empty());
+        assert!(sent.is_empty());
+        
+        // 截止时间改变后以新的提醒时间重新提醒
+        let schedule = ReminderScheduler::merge_schedule(&[], vec![scheduled("a", at(9, 30))], at(9, 3));
+        assert!(tick(&schedule, &mut sent, &notifiers, at(9, 29)).is_empty());
+        assert_eq!(tick(&schedule, &mut sent, &notifiers, at(9, 30)).len(), 1);
+        assert_eq!(received.lock().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,377 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// 将数据从某一版本升级到下一版本的迁移函数
+pub type Migration = fn(Value) -> Result<Value, String>;
+
+/// 数据文件的格式版本及其迁移链
+///
+/// 文件保存为 `{"version": N, "data": ...}`，没有版本信息的旧文件视为第0版。
+/// `migrations[i]` 把第 i 版的数据升级到第 i+1 版，当前版本号等于迁移函数的数量。
+/// 修改格式时只能在末尾追加迁移函数，不能修改已有的函数，这样旧的导出文件和备份始终可以加载。
+pub struct Schema {
+    /// 数据名称，用于错误信息
+    pub name: &'static str,
+    pub migrations: &'static [Migration],
+}
+
+/// 任务文件（包括所有工作区的任务文件、备份和导出文件）
+pub const TODOS: Schema = Schema {
+    name: "任务",
+    migrations: &[unversioned],
+};
+
+/// 主题文件 theme.json
+pub const THEME: Schema = Schema {
+    name: "主题",
+    migrations: &[unversioned],
+};
+
+/// 主题预设文件 theme_presets.json
+pub const THEME_PRESETS: Schema = Schema {
+    name: "主题预设",
+    migrations: &[unversioned],
+};
+
+/// Markdown目录信息文件 markdown_info.json
+pub const MARKDOWN_INFO: Schema = Schema {
+    name: "Markdown目录信息",
+    migrations: &[unversioned],
+};
+
+/// 应用风格配置文件 app_style.json
+pub const APP_STYLE: Schema = Schema {
+    name: "应用风格配置",
+    migrations: &[unversioned],
+};
+
+/// 工作区文件 workspaces.json
+pub const WORKSPACES: Schema = Schema {
+    name: "工作区",
+    migrations: &[unversioned],
+};
+
+/// 各工作区的智能列表文件
+pub const SMART_LISTS: Schema = Schema {
+    name: "智能列表",
+    migrations: &[unversioned],
+};
+
+/// 任务模板文件 templates.json
+pub const TEMPLATES: Schema = Schema {
+    name: "任务模板",
+    migrations: &[unversioned],
+};
+
+/// 提醒设置文件 reminder_settings.json
+pub const REMINDER_SETTINGS: Schema = Schema {
+    name: "提醒设置",
+    migrations: &[unversioned],
+};
+
+/// 第0版到第1版：第0版是加入版本信息之前的格式，数据结构与第1版相同，
+/// 之后添加的字段在旧文件中缺失时使用默认值
+fn unversioned(data: Value) -> Result<Value, String> {
+    Ok(data)
+}
+
+impl Schema {
+    /// 当前版本号
+    pub fn version(&self) -> u64 {
+        self.migrations.len() as u64
+    }
+    
+    /// 序列化为带版本信息的JSON
+    pub fn encode<T: Serialize>(&self, data: &T) -> Result<String, String> {
+        serde_json::to_string(&self.envelope(data)?).map_err(|e| e.to_string())
+    }
+    
+    /// 序列化为带版本信息、便于阅读的JSON，用于导出
+    pub fn encode_pretty<T: Serialize>(&self, data: &T) -> Result<String, String> {
+        serde_json::to_string_pretty(&self.envelope(data)?).map_err(|e| e.to_string())
+    }
+    
+    /// 用版本信息包装数据
+    fn envelope<T: Serialize>(&self, data: &T) -> Result<Value, String> {
+        let data = serde_json::to_value(data).map_err(|e| e.to_string())?;
+        Ok(json!({ "version": self.version(), "data": data }))
+    }
+    
+    /// 解析JSON，旧版本的数据依次经过迁移函数升级到当前版本
+    pub fn decode<T: DeserializeOwned>(&self, text: &str) -> Result<T, String> {
+        let value: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        let (version, mut data) = split_envelope(value);
+        
+        if version > self.version() {
+            return Err(format!(
+                "{}文件的版本为 {}，高于当前支持的版本 {}，请升级 Rodo",
+                self.name,
+                version,
+                self.version()
+            ));
+        }
+        
+        for (from, migrate) in self.migrations.iter().enumerate().skip(version as usize) {
+            data = migrate(data).map_err(|e| format!("{}数据从第 {} 版升级失败: {}", self.name, from, e))?;
+        }
+        
+        serde_json::from_value(data).map_err(|e| e.to_string())
+    }
+}
+
+/// 拆分版本信息和数据，没有版本信息的旧文件视为第0版
+fn split_envelope(value: Value) -> (u64, Value) {
+    match value {
+        Value::Object(mut map) if map.len() == 2 && map.contains_key("data") => {
+            match map.get("version").and_then(Value::as_u64) {
+                Some(version) => (version, map.remove("data").unwrap_or(Value::Null)),
+                None => (0, Value::Object(map)),
+            }
+        }
+        value => (0, value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reminder::ReminderSettings;
+    use crate::smart_list::SmartLists;
+    use crate::template::TaskTemplates;
+    use crate::theme::{Theme, ThemePresets, ThemeType};
+    use crate::todo::{Emoji, Priority, Recurrence, SubTask, TagMatch, TodoList};
+    use crate::workspace::Workspaces;
+    use chrono::NaiveTime;
+    use egui::Color32;
+    
+    /// 加入版本信息之前的导出文件格式（与 todos_export.json 相同）
+    const TODOS_EXPORT_V0: &str = r#"{
+  "todos": {
+    "43e9230f-02d6-4e5d-81d8-4385bb4d2bd0": {
+      "id": "43e9230f-02d6-4e5d-81d8-4385bb4d2bd0",
+      "title": "完成Rodo项目功能开发",
+      "description": "实现所有计划的功能并进行测试",
+      "completed": false,
+      "created_at": "2025-04-21T08:35:01.567247700+08:00",
+      "due_date": null,
+      "priority": "Critical",
+      "emoji": "Work",
+      "tags": ["工作", "编程"],
+      "subtasks": [
+        {
+          "id": "a1af6aa7-fb2f-447e-b718-4f1fbb75694b",
+          "title": "设计用户界面",
+          "completed": true
+        }
+      ]
+    },
+    "05b8992c-3101-4c13-aa61-1b9cc8035bc3": {
+      "id": "05b8992c-3101-4c13-aa61-1b9cc8035bc3",
+      "title": "阅读《Rust编程》",
+      "description": "",
+      "completed": true,
+      "created_at": "2025-04-21T08:35:01.567321200+08:00",
+      "due_date": "2025-04-28T18:00:00+08:00",
+      "priority": "High",
+      "emoji": "Shopping",
+      "tags": ["学习"],
+      "subtasks": []
+    }
+  },
+  "active_tags": ["工作"],
+  "filter_completed": true,
+  "priority_sort": true
+}"#;
+
+    /// 测试迁移链用的数据是一个数字
+    fn number(data: Value) -> Result<i64, String> {
+        data.as_i64().ok_or_else(|| "不是数字".to_string())
+    }
+    
+    fn add_one(data: Value) -> Result<Value, String> {
+        Ok(json!(number(data)? + 1))
+    }
+    
+    fn double(data: Value) -> Result<Value, String> {
+        Ok(json!(number(data)? * 2))
+    }
+    
+    const CHAIN: Schema = Schema {
+        name: "测试",
+        migrations: &[add_one, double],
+    };
+    
+    #[test]
+    fn loads_unversioned_export() {
+        let list: TodoList = TODOS.decode(TODOS_EXPORT_V0).unwrap();
+        assert_eq!(list.todos.len(), 2);
+        assert_eq!(list.active_tags, vec!["工作"]);
+        assert!(list.filter_completed);
+        assert_eq!(list.priority_sort, Some(true));
+        
+        let project = &list.todos["43e9230f-02d6-4e5d-81d8-4385bb4d2bd0"];
+        assert_eq!(project.priority, Priority::Critical);
+        assert_eq!(project.tags, vec!["工作", "编程"]);
+        assert_eq!(project.subtasks.len(), 1);
+        assert!(project.subtasks[0].completed);
+        assert!(project.subtasks[0].children.is_empty());
+        
+        let book = &list.todos["05b8992c-3101-4c13-aa61-1b9cc8035bc3"];
+        assert!(book.completed);
+        assert!(book.due_date.is_some());
+        assert!(book.recurrence.is_none());
+    }
+    
+    #[test]
+    fn loads_unversioned_save_with_later_fields() {
+        // 加入版本信息之前、包含之后添加的字段的任务文件
+        let mut list: TodoList = serde_json::from_str(TODOS_EXPORT_V0).unwrap();
+        list.normalize_statuses();
+        let todo = list.todos.get_mut("43e9230f-02d6-4e5d-81d8-4385bb4d2bd0").unwrap();
+        todo.recurrence = Some(Recurrence::Daily);
+        todo.blocked_by = vec!["05b8992c-3101-4c13-aa61-1b9cc8035bc3".to_string()];
+        todo.estimate_minutes = Some(30);
+        todo.defer_until = Some(todo.created_at);
+        todo.subtasks[0].children.push(SubTask::new("画草图".to_string()));
+        let unversioned = serde_json::to_string(&list).unwrap();
+        
+        let loaded: TodoList = TODOS.decode(&unversioned).unwrap();
+        assert_eq!(serde_json::to_value(&loaded).unwrap(), serde_json::to_value(&list).unwrap());
+    }
+    
+    #[test]
+    fn round_trips_current_version() {
+        let list: TodoList = TODOS.decode(TODOS_EXPORT_V0).unwrap();
+        let encoded = TODOS.encode(&list).unwrap();
+        
+        let value: Value = serde_json::from_str(&encoded).unwrap();
+        assert_eq!(value["version"], json!(TODOS.version()));
+        assert!(value["data"]["todos"].is_object());
+        
+        let decoded: TodoList = TODOS.decode(&encoded).unwrap();
+        assert_eq!(serde_json::to_value(&decoded).unwrap(), serde_json::to_value(&list).unwrap());
+        
+        let pretty: TodoList = TODOS.decode(&TODOS.encode_pretty(&list).unwrap()).unwrap();
+        assert_eq!(serde_json::to_value(&pretty).unwrap(), serde_json::to_value(&list).unwrap());
+    }
+    
+    #[test]
+    fn rejects_newer_version() {
+        let text = json!({ "version": TODOS.version() + 1, "data": {} }).to_string();
+        let err = TODOS.decode::<TodoList>(&text).unwrap_err();
+        assert!(err.contains("请升级"), "{}", err);
+    }
+    
+    #[test]
+    fn runs_migrations_from_file_version() {
+        assert_eq!(CHAIN.version(), 2);
+        // 第0版依次执行 +1 和 ×2
+        assert_eq!(CHAIN.decode::<i64>("3").unwrap(), 8);
+        assert_eq!(CHAIN.decode::<i64>(r#"{"version": 0, "data": 3}"#).unwrap(), 8);
+        // 第1版只执行 ×2
+        assert_eq!(CHAIN.decode::<i64>(r#"{"version": 1, "data": 3}"#).unwrap(), 6);
+        // 当前版本不执行迁移
+        assert_eq!(CHAIN.decode::<i64>(r#"{"version": 2, "data": 3}"#).unwrap(), 3);
+        
+        let err = CHAIN.decode::<i64>(r#"{"version": 0, "data": "x"}"#).unwrap_err();
+        assert!(err.contains("第 0 版"), "{}", err);
+    }
+    
+    #[test]
+    fn object_with_other_keys_is_unversioned() {
+        let text = r#"{"version": 1, "data": 2, "extra": 3}"#;
+        let value: Value = TODOS.decode(text).unwrap();
+        assert_eq!(value, serde_json::from_str::<Value>(text).unwrap());
+    }
+    
+    #[test]
+    fn loads_unversioned_theme() {
+        let text = r#"{
+            "theme_type": "Dark",
+            "background": [30, 30, 35, 255],
+            "card_background": [45, 45, 50, 255],
+            "accent": [130, 170, 255, 255],
+            "text": [230, 230, 230, 255],
+            "text_secondary": [180, 180, 180, 255],
+            "success": [76, 175, 80, 255],
+            "warning": [255, 152, 0, 255],
+            "error": [244, 67, 54, 255],
+            "selection": [60, 70, 90, 255]
+        }"#;
+        let theme: Theme = THEME.decode(text).unwrap();
+        assert_eq!(theme.theme_type, ThemeType::Dark);
+        assert_eq!(theme.accent, Color32::from_rgb(130, 170, 255));
+        
+        let decoded: Theme = THEME.decode(&THEME.encode(&theme).unwrap()).unwrap();
+        assert_eq!(decoded.selection, theme.selection);
+    }
+    
+    #[test]
+    fn loads_unversioned_theme_presets() {
+        let mut presets = ThemePresets::new();
+        presets.add_preset("我的主题".to_string(), Theme::dark()).unwrap();
+        let unversioned = serde_json::to_string(&presets).unwrap();
+        
+        let loaded: ThemePresets = THEME_PRESETS.decode(&unversioned).unwrap();
+        assert_eq!(loaded.get_preset_names(), vec!["我的主题".to_string()]);
+        
+        let decoded: ThemePresets = THEME_PRESETS.decode(&THEME_PRESETS.encode(&loaded).unwrap()).unwrap();
+        assert!(decoded.get_preset("我的主题").is_some());
+    }
+    
+    #[test]
+    fn loads_unversioned_workspaces() {
+        let text = r#"{"workspaces":[{"id":"default","name":"默认"},{"id":"7f3c","name":"工作"}],"active":"7f3c"}"#;
+        let workspaces: Workspaces = WORKSPACES.decode(text).unwrap();
+        assert_eq!(workspaces.workspaces.len(), 2);
+        assert_eq!(workspaces.active().file_name(), "todos-7f3c.json");
+        
+        let decoded: Workspaces = WORKSPACES.decode(&WORKSPACES.encode(&workspaces).unwrap()).unwrap();
+        assert_eq!(decoded.workspaces, workspaces.workspaces);
+    }
+    
+    #[test]
+    fn loads_unversioned_smart_lists() {
+        // 最初的智能列表格式，还没有标签匹配方式、排除标签和推迟筛选
+        let text = r#"{"lists":[{"id":"a1","name":"本周工作","query":"due<7d","tags":["工作"],
+            "hide_completed":true,"hide_blocked":false,"status":null}]}"#;
+        let lists: SmartLists = SMART_LISTS.decode(text).unwrap();
+        let list = &lists.lists[0];
+        assert_eq!(list.query, "due<7d");
+        assert_eq!(list.tag_match, TagMatch::Any);
+        assert!(list.excluded_tags.is_empty());
+        assert!(list.hide_completed);
+        assert!(!list.show_deferred);
+        
+        let decoded: SmartLists = SMART_LISTS.decode(&SMART_LISTS.encode(&lists).unwrap()).unwrap();
+        assert_eq!(decoded.lists, lists.lists);
+    }
+    
+    #[test]
+    fn loads_unversioned_templates() {
+        let text = r#"{"templates":[{"id":"t1","name":"周报","title":"第{{week}}周周报",
+            "priority":"High","emoji":"Work"}]}"#;
+        let templates: TaskTemplates = TEMPLATES.decode(text).unwrap();
+        let template = &templates.templates[0];
+        assert_eq!(template.title, "第{{week}}周周报");
+        assert_eq!(template.emoji, Emoji::Work);
+        assert!(template.subtasks.is_empty());
+        assert_eq!(template.due_offset_days, None);
+        assert_eq!(template.due_time, NaiveTime::from_hms_opt(18, 0, 0).unwrap());
+        
+        let decoded: TaskTemplates = TEMPLATES.decode(&TEMPLATES.encode(&templates).unwrap()).unwrap();
+        assert_eq!(decoded, templates);
+    }
+    
+    #[test]
+    fn loads_unversioned_reminder_settings() {
+        let text = r#"{"desktop_notifications":false,"command":"notify-send Rodo"}"#;
+        let settings: ReminderSettings = REMINDER_SETTINGS.decode(text).unwrap();
+        assert!(!settings.desktop_notifications);
+        assert_eq!(settings.command, "notify-send Rodo");
+        
+        let decoded: ReminderSettings = REMINDER_SETTINGS.decode(&REMINDER_SETTINGS.encode(&settings).unwrap()).unwrap();
+        assert_eq!(decoded.command, settings.command);
+    }
+}
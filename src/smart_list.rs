@@ -0,0 +1,283 @@
+use crate::query::Query;
+use crate::schema;
+use crate::storage;
+use crate::todo::{TagMatch, TodoFilter, TodoList};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use uuid::Uuid;
+
+/// 智能列表，保存一组命名的筛选条件
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SmartList {
+    pub id: String,
+    pub name: String,
+    /// 搜索语句
+    #[serde(default)]
+    pub query: String,
+    /// 筛选的标签
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// 筛选标签的匹配方式
+    #[serde(default)]
+    pub tag_match: TagMatch,
+    /// 排除的标签
+    #[serde(default)]
+    pub excluded_tags: Vec<String>,
+    #[serde(default)]
+    pub hide_completed: bool,
+    #[serde(default)]
+    pub hide_blocked: bool,
+    #[serde(default)]
+    pub show_deferred: bool,
+    /// 筛选的工作流状态ID
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+impl SmartList {
+    /// 根据任务列表当前的筛选条件创建智能列表
+    pub fn from_current(name: &str, list: &TodoList, query: &str) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name: name.trim().to_string(),
+            query: query.trim().to_string(),
+            tags: list.active_tags.clone(),
+            tag_match: list.tag_match,
+            excluded_tags: list.excluded_tags.clone(),
+            hide_completed: list.filter_completed,
+            hide_blocked: list.hide_blocked,
+            show_deferred: list.show_deferred,
+            status: list.status_filter.clone(),
+        }
+    }
+    
+    /// 检查任务列表当前的筛选条件是否与智能列表相同
+    pub fn is_current(&self, list: &TodoList, query: &str) -> bool {
+        self.query == query.trim()
+            && self.tags == list.active_tags
+            && self.tag_match == list.tag_match
+            && self.excluded_tags == list.excluded_tags
+            && self.hide_completed == list.filter_completed
+            && self.hide_blocked == list.hide_blocked
+            && self.show_deferred == list.show_deferred
+            && self.status == list.status_filter
+    }
+    
+    /// 将智能列表的筛选条件应用到任务列表，返回需要放入搜索框的搜索语句
+    pub fn apply(&self, list: &mut TodoList) -> String {
+        list.active_tags = self.tags.clone();
+        list.tag_match = self.tag_match;
+        list.excluded_tags = self.excluded_tags.clone();
+        list.filter_completed = self.hide_completed;
+        list.hide_blocked = self.hide_blocked;
+        list.show_deferred = self.show_deferred;
+        list.status_filter = self.status.clone();
+        self.query.clone()
+    }
+    
+    /// 统计满足智能列表条件的任务数量，搜索语句无效时返回错误
+    pub fn count(&self, list: &TodoList) -> Result<usize, String> {
+        let query = Query::parse(&self.query)?;
+        let filter = TodoFilter {
+            tags: &self.tags,
+            tag_match: self.tag_match,
+            excluded_tags: &self.excluded_tags,
+            hide_completed: self.hide_completed,
+            hide_blocked: self.hide_blocked,
+            show_deferred: self.show_deferred,
+            status: self.status.as_ref(),
+            search: Some(&query).filter(|q| !q.is_empty()),
+        };
+        Ok(list.todos.values().filter(|t| list.matches_filter(t, &filter)).count())
+    }
+}
+
+/// 当前工作区的智能列表，保存在任务文件旁边
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SmartLists {
+    pub lists: Vec<SmartList>,
+}
+
+impl SmartLists {
+    /// 检查名称是否可用
+    fn validate_name(&self, name: &str, except_id: Option<&str>) -> Result<(), String> {
+        if name.trim().is_empty() {
+            return Err("智能列表名称不能为空".to_string());
+        }
+        if self.lists.iter().any(|l| l.name == name.trim() && Some(l.id.as_str()) != except_id) {
+            return Err(format!("智能列表 '{}' 已存在", name.trim()));
+        }
+        Ok(())
+    }
+    
+    /// 添加智能列表
+    pub fn add(&mut self, list: SmartList) -> Result<(), String> {
+        self.validate_name(&list.name, None)?;
+        self.lists.push(list);
+        Ok(())
+    }
+    
+    /// 重命名智能列表
+    pub fn rename(&mut self, id: &str, name: &str) -> Result<(), String> {
+        self.validate_name(name, Some(id))?;
+        
+        let list = self.lists.iter_mut()
+            .find(|l| l.id == id)
+            .ok_or_else(|| "智能列表不存在".to_string())?;
+        list.name = name.trim().to_string();
+        Ok(())
+    }
+    
+    /// 删除智能列表
+    pub fn remove(&mut self, id: &str) {
+        self.lists.retain(|l| l.id != id);
+    }
+    
+    /// 保存到数据目录中的指定文件
+    pub fn save(&self, file_name: &str) -> Result<(), String> {
+        let path = TodoList::data_file_path(file_name)?;
+        let serialized = schema::SMART_LISTS.encode(self).map_err(|e| format!("序列化智能列表失败: {}", e))?;
+        storage::write_atomic(&path, serialized.as_bytes()).map_err(|e| format!("写入智能列表文件失败: {}", e))?;
+        Ok(())
+    }
+    
+    /// 从数据目录中的指定文件加载，同时返回需要提示用户的问题
+    ///
+    /// 文件无法读取时改名保留原文件，不使用任何智能列表。
+    pub fn load(file_name: &str) -> (Self, Option<String>) {
+        let path = match TodoList::data_file_path(file_name) {
+            Ok(path) => path,
+            Err(err) => return (Self::default(), Some(err)),
+        };
+        
+        match SmartLists::read(&path) {
+            Ok(lists) => (lists, None),
+            Err(err) => (Self::default(), Some(storage::set_aside_unreadable(&path, "智能列表文件", &err, Local::now()))),
+        }
+    }
+    
+    /// 读取智能列表文件，文件不存在时返回空集合
+    fn read(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        
+        let data = std::fs::read_to_string(path)
+            .map_err(|e| format!("读取智能列表文件失败: {}", e))?;
+        
+        schema::SMART_LISTS.decode(&data)
+            .map_err(|e| format!("解析智能列表JSON失败: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::todo::Todo;
+    
+    /// 设置了所有筛选条件的任务列表
+    fn filtered_list() -> TodoList {
+        TodoList {
+            active_tags: vec!["工作".to_string(), "紧急".to_string()],
+            tag_match: TagMatch::All,
+            excluded_tags: vec!["以后".to_string()],
+            filter_completed: true,
+            hide_blocked: true,
+            show_deferred: true,
+            status_filter: Some("in_progress".to_string()),
+            ..Default::default()
+        }
+    }
+    
+    fn add_todo(list: &mut TodoList, title: &str, tags: &[&str], completed: bool) {
+        let mut todo = Todo::new(title.to_string());
+        todo.tags = tags.iter().map(|t| t.to_string()).collect();
+        todo.set_completed(completed);
+        list.add_todo(todo);
+    }
+    
+    #[test]
+    fn from_current_and_apply_round_trip_every_field() {
+        let source = filtered_list();
+        let smart = SmartList::from_current("  本周工作  ", &source, "  priority:high  ");
+        assert_eq!(smart.name, "本周工作");
+        assert_eq!(smart.query, "priority:high");
+        
+        let mut target = TodoList::default();
+        assert!(!smart.is_current(&target, "priority:high"));
+        let query = smart.apply(&mut target);
+        assert_eq!(query, "priority:high");
+        assert_eq!(target.active_tags, source.active_tags);
+        assert_eq!(target.tag_match, source.tag_match);
+        assert_eq!(target.excluded_tags, source.excluded_tags);
+        assert_eq!(target.filter_completed, source.filter_completed);
+        assert_eq!(target.hide_blocked, source.hide_blocked);
+        assert_eq!(target.show_deferred, source.show_deferred);
+        assert_eq!(target.status_filter, source.status_filter);
+        assert!(smart.is_current(&target, &query));
+    }
+    
+    #[test]
+    fn is_current_compares_every_field() {
+        let list = filtered_list();
+        let smart = SmartList::from_current("列表", &list, "priority:high");
+        assert!(smart.is_current(&list, " priority:high "));
+        assert!(!smart.is_current(&list, "priority:low"));
+        
+        let changes: [fn(&mut TodoList); 7] = [
+            |l| { l.active_tags.pop(); },
+            |l| l.tag_match = TagMatch::Any,
+            |l| l.excluded_tags.clear(),
+            |l| l.filter_completed = false,
+            |l| l.hide_blocked = false,
+            |l| l.show_deferred = false,
+            |l| l.status_filter = None,
+        ];
+        for change in changes {
+            let mut changed = list.clone();
+            change(&mut changed);
+            assert!(!smart.is_current(&changed, "priority:high"));
+        }
+    }
+    
+    #[test]
+    fn count_applies_filters_and_rejects_invalid_query() {
+        let mut list = TodoList::default();
+        add_todo(&mut list, "买牛奶", &["购物"], false);
+        add_todo(&mut list, "买面包", &["购物"], false);
+        add_todo(&mut list, "已买的牛奶", &["购物"], true);
+        add_todo(&mut list, "牛奶发票", &["工作"], false);
+        
+        let mut smart = SmartList::from_current("购物", &TodoList::default(), "牛奶");
+        smart.tags = vec!["购物".to_string()];
+        assert_eq!(smart.count(&list), Ok(2));
+        smart.hide_completed = true;
+        assert_eq!(smart.count(&list), Ok(1));
+        smart.query.clear();
+        assert_eq!(smart.count(&list), Ok(2));
+        
+        smart.query = "priority:".to_string();
+        assert!(smart.count(&list).is_err());
+    }
+    
+    #[test]
+    fn names_must_be_unique_and_not_empty() {
+        let list = TodoList::default();
+        let mut lists = SmartLists::default();
+        lists.add(SmartList::from_current("工作", &list, "")).unwrap();
+        let id = lists.lists[0].id.clone();
+        
+        assert_eq!(lists.add(SmartList::from_current("  ", &list, "")), Err("智能列表名称不能为空".to_string()));
+        assert_eq!(lists.add(SmartList::from_current(" 工作 ", &list, "")), Err("智能列表 '工作' 已存在".to_string()));
+        lists.add(SmartList::from_current("生活", &list, "")).unwrap();
+        assert_eq!(lists.lists.len(), 2);
+        
+        // 重命名时可以保留自己的名称，但不能与其他列表重名
+        assert_eq!(lists.rename(&id, " 工作 "), Ok(()));
+        assert_eq!(lists.rename(&id, "生活"), Err("智能列表 '生活' 已存在".to_string()));
+        assert_eq!(lists.rename(&id, ""), Err("智能列表名称不能为空".to_string()));
+        assert_eq!(lists.rename("missing", "其他"), Err("智能列表不存在".to_string()));
+        assert_eq!(lists.lists[0].name, "工作");
+    }
+}
@@ -0,0 +1,307 @@
+use chrono::{DateTime, Duration, Local, NaiveDateTime, TimeZone};
+use std::ffi::OsString;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// 每个数据文件保留的备份数量
+pub const MAX_BACKUPS: usize = 10;
+
+/// 两次自动备份之间的最短间隔（分钟）
+const BACKUP_INTERVAL_MINUTES: i64 = 10;
+
+/// 备份文件名中的时间格式
+const BACKUP_TIME_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// 数据文件的一个备份
+#[derive(Clone, Debug)]
+pub struct Backup {
+    pub path: PathBuf,
+    pub created_at: DateTime<Local>,
+}
+
+/// 原子写入文件：先写入同目录下的临时文件并同步到磁盘，再重命名覆盖目标文件
+///
+/// 写入过程中崩溃或磁盘已满时，原文件保持不变。
+pub fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut temp_name = OsString::from(".");
+    temp_name.push(path.file_name().unwrap_or_default());
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+    
+    let result = write_and_rename(&temp_path, path, contents);
+    if result.is_err() {
+        // 清理未完成的临时文件，忽略清理失败
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// 写入临时文件、同步并重命名为目标文件
+fn write_and_rename(temp_path: &Path, path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut file = fs::File::create(temp_path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    drop(file);
+    
+    fs::rename(temp_path, path)?;
+    
+    // 同步所在目录，确保重命名本身也已写入磁盘；部分文件系统不支持，失败时忽略
+    #[cfg(unix)]
+    if let Some(Ok(dir)) = path.parent().map(fs::File::open) {
+        let _ = dir.sync_all();
+    }
+    
+    Ok(())
+}
+
+/// 备份文件所在的目录
+fn backup_dir(path: &Path) -> PathBuf {
+    path.with_file_name("backups")
+}
+
+/// 获取数据文件的所有备份，最新的在前
+pub fn list_backups(path: &Path) -> Vec<Backup> {
+    let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(backup_dir(path)) else {
+        return Vec::new();
+    };
+    
+    let prefix = format!("{}.", file_name);
+    let mut backups: Vec<Backup> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let time = name.strip_prefix(&prefix)?.strip_suffix(".bak")?;
+            let naive = NaiveDateTime::parse_from_str(time, BACKUP_TIME_FORMAT).ok()?;
+            Some(Backup {
+                path: entry.path(),
+                created_at: Local.from_local_datetime(&naive).earliest()?,
+            })
+        })
+        .collect();
+    
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.created_at));
+    backups
+}
+
+/// 距离上次备份超过间隔时备份数据文件，并删除超出数量的旧备份
+pub fn backup_if_due(path: &Path, now: DateTime<Local>) -> Result<(), String> {
+    if !path.exists() {
+        return Ok(());
+    }
+    
+    let backups = list_backups(path);
+    if backups.first().is_some_and(|latest| now - latest.created_at < Duration::minutes(BACKUP_INTERVAL_MINUTES)) {
+        return Ok(());
+    }
+    
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| format!("无效的文件路径: {}", path.display()))?;
+    let dir = backup_dir(path);
+    fs::create_dir_all(&dir).map_err(|e| format!("无法创建备份目录: {}", e))?;
+    
+    let backup_path = dir.join(format!("{}.{}.bak", file_name, now.format(BACKUP_TIME_FORMAT)));
+    fs::copy(path, &backup_path).map_err(|e| format!("备份文件失败: {}", e))?;
+    
+    // 新备份排在最前，删除超出数量的旧备份
+    for old in list_backups(path).iter().skip(MAX_BACKUPS) {
+        if let Err(err) = fs::remove_file(&old.path) {
+            eprintln!("删除旧备份失败: {}", err);
+        }
+    }
+    
+    Ok(())
+}
+
+/// 将无法读取的数据文件改名保留，返回新的路径
+pub fn preserve_unreadable(path: &Path, now: DateTime<Local>) -> Result<PathBuf, String> {
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| format!("无效的文件路径: {}", path.display()))?;
+    let new_path = path.with_file_name(format!("{}.corrupt-{}", file_name, now.format(BACKUP_TIME_FORMAT)));
+    fs::rename(path, &new_path).map_err(|e| format!("保留损坏的文件失败: {}", e))?;
+    Ok(new_path)
+}
+
+/// 处理无法读取的数据文件：改名保留原文件，使之后的保存不会覆盖它，返回显示给用户的提示
+pub fn set_aside_unreadable(path: &Path, description: &str, error: &str, now: DateTime<Local>) -> String {
+    eprintln!("读取{}失败: {}", description, error);
+    match preserve_unreadable(path, now) {
+        Ok(kept) => format!("{}无法读取：{}\n原文件已改名保留为 {}", description, error, kept.display()),
+        Err(err) => format!("{}无法读取：{}\n{}", description, error, err),
+    }
+}
+
+/// 用备份覆盖数据文件
+pub fn restore_backup(backup: &Path, path: &Path) -> Result<(), String> {
+    let data = fs::read(backup).map_err(|e| format!("读取备份失败: {}", e))?;
+    write_atomic(path, &data).map_err(|e| format!("写入任务文件失败: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// 测试用的临时目录，离开作用域时删除
+    struct TestDir(PathBuf);
+    
+    impl TestDir {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!("rodo-storage-{}", uuid::Uuid::new_v4()));
+            fs::create_dir_all(&dir).unwrap();
+            TestDir(dir)
+        }
+        
+        fn file(&self, name: &str) -> PathBuf {
+            self.0.join(name)
+        }
+    }
+    
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+    
+    fn at(hour: u32, minute: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 5, 15, hour, minute, 0).unwrap()
+    }
+    
+    fn file_names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        names
+    }
+    
+    #[test]
+    fn write_atomic_replaces_file_and_removes_temp() {
+        let dir = TestDir::new();
+        let path = dir.file("todos.json");
+        
+        write_atomic(&path, b"first").unwrap();
+        write_atomic(&path, b"second").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"second");
+        assert_eq!(file_names(&dir.0), vec!["todos.json"]);
+    }
+    
+    #[test]
+    fn failed_write_keeps_original_and_removes_temp() {
+        let dir = TestDir::new();
+        // 目标是非空目录时重命名会失败，模拟写入中途出错
+        let path = dir.file("todos.json");
+        fs::create_dir(&path).unwrap();
+        fs::write(path.join("keep"), b"original").unwrap();
+        
+        assert!(write_atomic(&path, b"new").is_err());
+        assert_eq!(fs::read(path.join("keep")).unwrap(), b"original");
+        assert_eq!(file_names(&dir.0), vec!["todos.json"]);
+    }
+    
+    #[test]
+    fn backup_waits_for_interval() {
+        let dir = TestDir::new();
+        let path = dir.file("todos.json");
+        
+        // 文件不存在时不备份
+        backup_if_due(&path, at(9, 0)).unwrap();
+        assert!(list_backups(&path).is_empty());
+        
+        fs::write(&path, b"v1").unwrap();
+        backup_if_due(&path, at(9, 0)).unwrap();
+        fs::write(&path, b"v2").unwrap();
+        backup_if_due(&path, at(9, 9)).unwrap();
+        assert_eq!(list_backups(&path).len(), 1);
+        
+        backup_if_due(&path, at(9, 10)).unwrap();
+        let backups = list_backups(&path);
+        assert_eq!(backups.len(), 2);
+        assert_eq!(backups[0].created_at, at(9, 10));
+        assert_eq!(fs::read(&backups[0].path).unwrap(), b"v2");
+        assert_eq!(fs::read(&backups[1].path).unwrap(), b"v1");
+    }
+    
+    #[test]
+    fn backups_are_rotated_newest_first() {
+        let dir = TestDir::new();
+        let path = dir.file("todos.json");
+        
+        for i in 0..(MAX_BACKUPS as u32 + 3) {
+            fs::write(&path, format!("v{}", i)).unwrap();
+            backup_if_due(&path, at(9 + i / 4, i % 4 * 15)).unwrap();
+        }
+        
+        let backups = list_backups(&path);
+        assert_eq!(backups.len(), MAX_BACKUPS);
+        assert_eq!(file_names(&backup_dir(&path)).len(), MAX_BACKUPS);
+        assert!(backups.windows(2).all(|pair| pair[0].created_at > pair[1].created_at));
+        assert_eq!(backups[0].created_at, at(12, 0));
+        assert_eq!(fs::read(&backups[0].path).unwrap(), format!("v{}", MAX_BACKUPS + 2).as_bytes());
+        // 最早的三个备份已被删除
+        assert_eq!(backups[MAX_BACKUPS - 1].created_at, at(9, 45));
+    }
+    
+    #[test]
+    fn list_backups_only_parses_own_backups() {
+        let dir = TestDir::new();
+        let path = dir.file("todos.json");
+        let backups = backup_dir(&path);
+        fs::create_dir_all(&backups).unwrap();
+        for name in [
+            "todos.json.20240515-090000.bak",
+            "todos.json.20240515-100000.bak",
+            "todos-other.json.20240515-110000.bak",
+            "todos.json.20240515-120000.tmp",
+            "todos.json.not-a-time.bak",
+            "todos.json.20241345-000000.bak",
+        ] {
+            fs::write(backups.join(name), b"").unwrap();
+        }
+        
+        let found = list_backups(&path);
+        assert_eq!(found.iter().map(|backup| backup.created_at).collect::<Vec<_>>(), vec![at(10, 0), at(9, 0)]);
+        assert_eq!(found[0].path, backups.join("todos.json.20240515-100000.bak"));
+        
+        assert!(list_backups(&dir.file("missing.json")).is_empty());
+    }
+    
+    #[test]
+    fn preserve_unreadable_renames_file() {
+        let dir = TestDir::new();
+        let path = dir.file("todos.json");
+        fs::write(&path, b"{broken").unwrap();
+        
+        let kept = preserve_unreadable(&path, at(9, 30)).unwrap();
+        assert_eq!(kept, dir.file("todos.json.corrupt-20240515-093000"));
+        assert!(!path.exists());
+        assert_eq!(fs::read(&kept).unwrap(), b"{broken");
+        
+        assert!(preserve_unreadable(&path, at(9, 31)).is_err());
+    }
+    
+    #[test]
+    fn restore_backup_overwrites_file() {
+        let dir = TestDir::new();
+        let path = dir.file("todos.json");
+        fs::write(&path, b"old").unwrap();
+        backup_if_due(&path, at(9, 0)).unwrap();
+        fs::write(&path, b"new").unwrap();
+        
+        let backup = list_backups(&path).remove(0);
+        restore_backup(&backup.path, &path).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"old");
+        // 备份本身保留
+        assert!(backup.path.exists());
+        
+        assert!(restore_backup(&dir.file("missing.bak"), &path).is_err());
+        assert_eq!(fs::read(&path).unwrap(), b"old");
+    }
+}
@@ -0,0 +1,265 @@
+use crate::schema;
+use crate::storage;
+use crate::todo::{weekday_name, Emoji, Priority, SubTask, Todo, TodoList};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveTime, TimeZone, Timelike};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use uuid::Uuid;
+
+/// 模板中可用的占位符及说明
+pub const PLACEHOLDERS: [(&str, &str); 6] = [
+    ("{{date}}", "创建日期，例如 2024-05-15"),
+    ("{{week}}", "创建日期所在的周数，例如 20"),
+    ("{{month}}", "创建月份，例如 5"),
+    ("{{year}}", "创建年份，例如 2024"),
+    ("{{weekday}}", "创建日期是星期几，例如 三"),
+    ("{{time}}", "创建时间，例如 09:30"),
+];
+
+/// 任务模板，用于快速创建结构相同的任务
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TaskTemplate {
+    pub id: String,
+    /// 模板名称
+    pub name: String,
+    /// 任务标题，可以包含占位符
+    pub title: String,
+    /// 任务描述，可以包含占位符
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub priority: Priority,
+    pub emoji: Emoji,
+    /// 子任务，标题可以包含占位符
+    #[serde(default)]
+    pub subtasks: Vec<SubTask>,
+    #[serde(default)]
+    pub estimate_minutes: Option<i64>,
+    /// 截止日期相对创建日期的天数，为空表示没有截止时间
+    #[serde(default)]
+    pub due_offset_days: Option<i64>,
+    /// 截止时间的时分
+    #[serde(default = "default_due_time")]
+    pub due_time: NaiveTime,
+}
+
+/// 模板默认的截止时间
+fn default_due_time() -> NaiveTime {
+    NaiveTime::from_hms_opt(18, 0, 0).unwrap_or_default()
+}
+
+impl TaskTemplate {
+    /// 根据已有任务创建模板，截止时间保存为相对今天的天数
+    pub fn from_todo(name: &str, todo: &Todo, now: DateTime<Local>) -> Self {
+        let today = now.date_naive();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name: name.trim().to_string(),
+            title: todo.title.clone(),
+            description: todo.description.clone(),
+            tags: todo.tags.clone(),
+            priority: todo.priority.clone(),
+            emoji: todo.emoji.clone(),
+            subtasks: todo.subtasks.clone(),
+            estimate_minutes: todo.estimate_minutes,
+            due_offset_days: todo.due_date.map(|due| (due.date_naive() - today).num_days().max(0)),
+            due_time: todo.due_date.map(|due| due.time()).unwrap_or_else(default_due_time),
+        }
+    }
+    
+    /// 根据模板创建新任务，替换占位符，子任务全部为未完成状态
+    pub fn instantiate(&self, now: DateTime<Local>) -> Todo {
+        let mut todo = Todo::new(expand_placeholders(&self.title, now));
+        todo.description = expand_placeholders(&self.description, now);
+        todo.tags = self.tags.clone();
+        todo.priority = self.priority.clone();
+        todo.emoji = self.emoji.clone();
+        todo.subtasks = fresh_subtasks(&self.subtasks, now);
+        todo.estimate_minutes = self.estimate_minutes;
+        todo.due_date = self.due_offset_days.and_then(|days| {
+            let date = now.date_naive() + Duration::days(days);
+            Local.from_local_datetime(&date.and_time(self.due_time)).earliest()
+        });
+        todo
+    }
+}
+
+/// 复制子任务树，生成新的ID并替换标题中的占位符
+fn fresh_subtasks(subtasks: &[SubTask], now: DateTime<Local>) -> Vec<SubTask> {
+    subtasks
+        .iter()
+        .map(|subtask| {
+            let mut copy = SubTask::new(expand_placeholders(&subtask.title, now));
+            copy.children = fresh_subtasks(&subtask.children, now);
+            copy
+        })
+        .collect()
+}
+
+/// 替换文本中的占位符，未知的占位符保持不变
+pub fn expand_placeholders(text: &str, now: DateTime<Local>) -> String {
+    let values = [
+        now.format("%Y-%m-%d").to_string(),
+        now.iso_week().week().to_string(),
+        now.month().to_string(),
+        now.year().to_string(),
+        weekday_name(&now.weekday()).to_string(),
+        format!("{:02}:{:02}", now.hour(), now.minute()),
+    ];
+    
+    PLACEHOLDERS
+        .iter()
+        .zip(values)
+        .fold(text.to_string(), |text, ((placeholder, _), value)| text.replace(placeholder, &value))
+}
+
+/// 任务模板集合，保存在单独的文件中
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaskTemplates {
+    pub templates: Vec<TaskTemplate>,
+}
+
+impl TaskTemplates {
+    /// 获取不与已有模板重名的名称，重名时添加序号
+    pub fn unique_name(&self, name: &str) -> String {
+        let base = if name.trim().is_empty() { "新模板" } else { name.trim() };
+        let mut candidate = base.to_string();
+        let mut n = 2;
+        while self.templates.iter().any(|t| t.name == candidate) {
+            candidate = format!("{} ({})", base, n);
+            n += 1;
+        }
+        candidate
+    }
+    
+    /// 获取指定ID的模板
+    pub fn get(&self, id: &str) -> Option<&TaskTemplate> {
+        self.templates.iter().find(|t| t.id == id)
+    }
+    
+    /// 保存模板到文件
+    pub fn save(&self) -> Result<(), String> {
+        let path = TodoList::data_file_path("templates.json")?;
+        let serialized = schema::TEMPLATES.encode(self).map_err(|e| format!("序列化任务模板失败: {}", e))?;
+        storage::write_atomic(&path, serialized.as_bytes()).map_err(|e| format!("写入任务模板文件失败: {}", e))?;
+        Ok(())
+    }
+    
+    /// 从文件加载模板，同时返回需要提示用户的问题
+    ///
+    /// 文件无法读取时改名保留原文件，不使用任何模板。
+    pub fn load() -> (Self, Option<String>) {
+        let path = match TodoList::data_file_path("templates.json") {
+            Ok(path) => path,
+            Err(err) => return (Self::default(), Some(err)),
+        };
+        
+        match TaskTemplates::read(&path) {
+            Ok(templates) => (templates, None),
+            Err(err) => (Self::default(), Some(storage::set_aside_unreadable(&path, "任务模板文件", &err, Local::now()))),
+        }
+    }
+    
+    /// 读取模板文件，文件不存在时返回空集合
+    fn read(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        
+        let data = std::fs::read_to_string(path)
+            .map_err(|e| format!("读取任务模板文件失败: {}", e))?;
+        
+        schema::TEMPLATES.decode(&data)
+            .map_err(|e| format!("解析任务模板JSON失败: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// 2024-05-15 是星期三，位于第20周
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 5, 15, 9, 5, 0).unwrap()
+    }
+    
+    fn template(title: &str) -> TaskTemplate {
+        TaskTemplate::from_todo("周报", &Todo::new(title.to_string()), now())
+    }
+    
+    #[test]
+    fn expands_every_placeholder() {
+        let text: String = PLACEHOLDERS.iter().map(|(placeholder, _)| *placeholder).collect::<Vec<_>>().join(" ");
+        assert_eq!(expand_placeholders(&text, now()), "2024-05-15 20 5 2024 三 09:05");
+        assert_eq!(expand_placeholders("{{date}}/{{date}}", now()), "2024-05-15/2024-05-15");
+    }
+    
+    #[test]
+    fn unknown_and_unterminated_placeholders_stay() {
+        assert_eq!(expand_placeholders("{{day}} {{Date}} {{ date }}", now()), "{{day}} {{Date}} {{ date }}");
+        assert_eq!(expand_placeholders("{{date 周报 {{week", now()), "{{date 周报 {{week");
+        assert_eq!(expand_placeholders("{{{{date}}}}", now()), "{{2024-05-15}}");
+        assert_eq!(expand_placeholders("", now()), "");
+    }
+    
+    #[test]
+    fn instantiate_expands_title_description_and_subtasks() {
+        let mut template = template("第{{week}}周周报");
+        template.description = "{{date}} 提交".to_string();
+        let mut subtask = SubTask::new("整理 {{month}} 月数据".to_string());
+        subtask.completed = true;
+        subtask.children = vec![SubTask::new("{{weekday}} 汇总".to_string())];
+        template.subtasks = vec![subtask.clone()];
+        
+        let todo = template.instantiate(now());
+        assert_eq!(todo.title, "第20周周报");
+        assert_eq!(todo.description, "2024-05-15 提交");
+        assert_eq!(todo.subtasks[0].title, "整理 5 月数据");
+        assert!(!todo.subtasks[0].completed);
+        assert_ne!(todo.subtasks[0].id, subtask.id);
+        assert_eq!(todo.subtasks[0].children[0].title, "三 汇总");
+        // 模板本身保持不变
+        assert_eq!(template.title, "第{{week}}周周报");
+    }
+    
+    #[test]
+    fn instantiate_applies_due_offset() {
+        let mut template = template("周报");
+        assert_eq!(template.instantiate(now()).due_date, None);
+        
+        template.due_offset_days = Some(2);
+        template.due_time = NaiveTime::from_hms_opt(17, 30, 0).unwrap();
+        assert_eq!(template.instantiate(now()).due_date, Some(Local.with_ymd_and_hms(2024, 5, 17, 17, 30, 0).unwrap()));
+        
+        template.due_offset_days = Some(0);
+        assert_eq!(template.instantiate(now()).due_date, Some(Local.with_ymd_and_hms(2024, 5, 15, 17, 30, 0).unwrap()));
+    }
+    
+    #[test]
+    fn from_todo_stores_due_date_relative_to_today() {
+        let mut todo = Todo::new("周报".to_string());
+        assert_eq!(TaskTemplate::from_todo("周报", &todo, now()).due_offset_days, None);
+        
+        todo.due_date = Some(Local.with_ymd_and_hms(2024, 5, 18, 10, 0, 0).unwrap());
+        let template = TaskTemplate::from_todo(" 周报 ", &todo, now());
+        assert_eq!(template.name, "周报");
+        assert_eq!(template.due_offset_days, Some(3));
+        assert_eq!(template.due_time, NaiveTime::from_hms_opt(10, 0, 0).unwrap());
+        
+        // 已过期的截止时间按今天计算
+        todo.due_date = Some(Local.with_ymd_and_hms(2024, 5, 10, 8, 0, 0).unwrap());
+        assert_eq!(TaskTemplate::from_todo("周报", &todo, now()).due_offset_days, Some(0));
+    }
+    
+    #[test]
+    fn unique_name_adds_number() {
+        let mut templates = TaskTemplates::default();
+        assert_eq!(templates.unique_name("  "), "新模板");
+        
+        templates.templates.push(template("周报"));
+        assert_eq!(templates.unique_name("周报"), "周报 (2)");
+        templates.templates.push(TaskTemplate { name: "周报 (2)".to_string(), ..template("周报") });
+        assert_eq!(templates.unique_name("周报"), "周报 (3)");
+    }
+}
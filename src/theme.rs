@@ -1,3 +1,4 @@
+use crate::schema;
 use egui::{Color32, Rounding, Stroke, Vec2, style::Margin, Visuals};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -98,7 +99,7 @@ impl ThemePresets {
     /// 保存预设集合到文件
     pub fn save(&self) -> Result<(), String> {
         let path = ThemePresets::get_presets_file_path()?;
-        let serialized = serde_json::to_string(self).map_err(|e| format!("序列化主题预设失败: {}", e))?;
+        let serialized = schema::THEME_PRESETS.encode(self).map_err(|e| format!("序列化主题预设失败: {}", e))?;
         std::fs::write(path, serialized).map_err(|e| format!("写入主题预设文件失败: {}", e))?;
         Ok(())
     }
@@ -121,7 +122,7 @@ impl ThemePresets {
         let data = std::fs::read_to_string(path)
             .map_err(|e| format!("读取主题预设文件失败: {}", e))?;
             
-        serde_json::from_str(&data)
+        schema::THEME_PRESETS.decode(&data)
             .map_err(|e| format!("解析主题预设JSON失败: {}", e))
     }
     
@@ -327,7 +328,7 @@ impl Theme {
     /// 保存主题设置到文件
     pub fn save(&self) -> Result<(), String> {
         let path = Theme::get_theme_file_path()?;
-        let serialized = serde_json::to_string(self).map_err(|e| format!("序列化主题失败: {}", e))?;
+        let serialized = schema::THEME.encode(self).map_err(|e| format!("序列化主题失败: {}", e))?;
         std::fs::write(path, serialized).map_err(|e| format!("写入主题文件失败: {}", e))?;
         Ok(())
     }
@@ -350,7 +351,7 @@ impl Theme {
         let data = std::fs::read_to_string(path)
             .map_err(|e| format!("读取主题文件失败: {}", e))?;
             
-        serde_json::from_str(&data)
+        schema::THEME.decode(&data)
             .map_err(|e| format!("解析主题JSON失败: {}", e))
     }
     
@@ -1,7 +1,11 @@
-use chrono::{DateTime, Local, Datelike};
+use crate::query::Query;
+use crate::reminder::{Reminder, ReminderKind, ScheduledReminder};
+use crate::schema;
+use crate::storage;
+use chrono::{DateTime, Local, Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone, Timelike, Weekday};
 use rand::seq::SliceRandom;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use uuid::Uuid;
 
 /// 表情符号类型，用于为每个任务添加视觉辨识度
@@ -25,7 +29,6 @@ pub enum Emoji {
 
 impl Emoji {
     /// 获取表情符号的Unicode字符
-    #[allow(dead_code)]
     pub fn as_str(&self) -> &str {
         match self {
             Emoji::None => "",
@@ -82,8 +85,18 @@ pub enum Priority {
 }
 
 impl Priority {
+    /// 根据名称解析优先级，支持英文名称、首字母和中文名称
+    pub fn from_name(name: &str) -> Option<Priority> {
+        match name.to_lowercase().as_str() {
+            "low" | "l" | "低" => Some(Priority::Low),
+            "medium" | "med" | "m" | "中" => Some(Priority::Medium),
+            "high" | "h" | "高" => Some(Priority::High),
+            "critical" | "c" | "urgent" | "紧急" => Some(Priority::Critical),
+            _ => None,
+        }
+    }
+    
     /// 获取优先级的字符表示
-    #[allow(dead_code)]
     pub fn as_str(&self) -> &str {
         match self {
             Priority::Low => "低",
@@ -116,8 +129,337 @@ impl Priority {
     }
 }
 
+/// 标签筛选方式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TagMatch {
+    /// 带有任一筛选标签
+    #[default]
+    Any,
+    /// 带有全部筛选标签
+    All,
+    /// 不带任何筛选标签
+    None,
+}
+
+impl TagMatch {
+    /// 获取筛选方式的显示名称
+    pub fn as_str(&self) -> &str {
+        match self {
+            TagMatch::Any => "任一",
+            TagMatch::All => "全部",
+            TagMatch::None => "都不",
+        }
+    }
+    
+    /// 获取标签在该筛选方式下的简短标记
+    pub fn symbol(&self) -> &str {
+        match self {
+            TagMatch::Any => "或",
+            TagMatch::All => "且",
+            TagMatch::None => "非",
+        }
+    }
+}
+
+/// 截止日期分组，用于在列表中按时间分段显示
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DueBucket {
+    Overdue,
+    Today,
+    ThisWeek,
+    Later,
+    NoDate,
+    /// 已完成的任务排在所有未完成的分组之后
+    Completed,
+}
+
+impl DueBucket {
+    /// 获取分组的显示名称
+    pub fn as_str(&self) -> &str {
+        match self {
+            DueBucket::Overdue => "已过期",
+            DueBucket::Today => "今天",
+            DueBucket::ThisWeek => "本周",
+            DueBucket::Later => "以后",
+            DueBucket::NoDate => "无截止日期",
+            DueBucket::Completed => "已完成",
+        }
+    }
+}
+
+/// 推迟任务的快捷选项
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Snooze {
+    /// 三小时后
+    LaterToday,
+    /// 明天上午9点
+    Tomorrow,
+    /// 下周一上午9点
+    NextWeek,
+}
+
+impl Snooze {
+    pub const ALL: [Snooze; 3] = [Snooze::LaterToday, Snooze::Tomorrow, Snooze::NextWeek];
+
+    /// 获取选项的显示名称
+    pub fn as_str(&self) -> &str {
+        match self {
+            Snooze::LaterToday => "稍后（3小时）",
+            Snooze::Tomorrow => "明天",
+            Snooze::NextWeek => "下周",
+        }
+    }
+
+    /// 计算推迟到的时间
+    pub fn until(&self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        let today = now.date_naive();
+        let date = match self {
+            Snooze::LaterToday => return Some(now + Duration::hours(3)),
+            Snooze::Tomorrow => today + Duration::days(1),
+            Snooze::NextWeek => today + Duration::days(7 - today.weekday().num_days_from_monday() as i64),
+        };
+        Local.from_local_datetime(&date.and_hms_opt(9, 0, 0)?).earliest()
+    }
+}
+
+/// 重复规则
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Recurrence {
+    /// 每天
+    Daily,
+    /// 每周的指定几天
+    Weekly(Vec<Weekday>),
+    /// 每月第N日，超出当月天数时取最后一天
+    MonthlyOnDay(u32),
+    /// 每月第N个星期X，ordinal为-1表示最后一个
+    MonthlyOnWeekday { ordinal: i8, weekday: Weekday },
+    /// 完成后每隔N天
+    AfterCompletion(u32),
+}
+
+impl Recurrence {
+    /// 获取重复规则的描述
+    pub fn describe(&self) -> String {
+        match self {
+            Recurrence::Daily => "每天".to_string(),
+            Recurrence::Weekly(days) if days.is_empty() => "每周".to_string(),
+            Recurrence::Weekly(days) => {
+                let names: Vec<&str> = days.iter().map(weekday_name).collect();
+                format!("每周{}", names.join("、"))
+            }
+            Recurrence::MonthlyOnDay(day) => format!("每月{}日", day),
+            Recurrence::MonthlyOnWeekday { ordinal, weekday } => {
+                if *ordinal < 0 {
+                    format!("每月最后一个周{}", weekday_name(weekday))
+                } else {
+                    format!("每月第{}个周{}", ordinal, weekday_name(weekday))
+                }
+            }
+            Recurrence::AfterCompletion(days) => format!("完成后每{}天", days),
+        }
+    }
+
+    /// 计算下一次的截止时间
+    ///
+    /// `due` 为当前截止时间，`completed_at` 为本次完成时间。
+    /// 返回的时间保证晚于完成时间，错过的周期会被跳过。
+    pub fn next_due<Tz: TimeZone>(&self, due: Option<DateTime<Tz>>, completed_at: DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let tz = completed_at.timezone();
+        
+        // 完成后重复的任务从完成时间开始计算
+        if let Recurrence::AfterCompletion(days) = self {
+            let time = due.unwrap_or_else(|| completed_at.clone()).time();
+            let date = completed_at.date_naive() + Duration::days((*days).max(1) as i64);
+            return resolve_local(&tz, date.and_time(time));
+        }
+        
+        let base = due.unwrap_or_else(|| completed_at.clone());
+        let time = base.time();
+        let mut date = base.date_naive();
+        
+        // 最多向后查找约十年，避免规则异常时死循环
+        for _ in 0..4000 {
+            date = self.next_date_after(date)?;
+            if let Some(next) = resolve_local(&tz, date.and_time(time)) {
+                if next > completed_at {
+                    return Some(next);
+                }
+            }
+        }
+        
+        None
+    }
+
+    /// 计算指定日期之后符合规则的第一个日期
+    fn next_date_after(&self, date: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Recurrence::Daily | Recurrence::AfterCompletion(_) => date.succ_opt(),
+            Recurrence::Weekly(days) => {
+                if days.is_empty() {
+                    return Some(date + Duration::days(7));
+                }
+                (1..=7)
+                    .map(|offset| date + Duration::days(offset))
+                    .find(|d| days.contains(&d.weekday()))
+            }
+            Recurrence::MonthlyOnDay(day) => {
+                let (mut year, mut month) = (date.year(), date.month());
+                loop {
+                    let candidate = clamped_date(year, month, *day)?;
+                    if candidate > date {
+                        return Some(candidate);
+                    }
+                    (year, month) = next_month(year, month);
+                }
+            }
+            Recurrence::MonthlyOnWeekday { ordinal, weekday } => {
+                let (mut year, mut month) = (date.year(), date.month());
+                // 第5个星期X不一定每月都有，最多查找一年
+                for _ in 0..13 {
+                    if let Some(candidate) = nth_weekday_of_month(year, month, *ordinal, *weekday) {
+                        if candidate > date {
+                            return Some(candidate);
+                        }
+                    }
+                    (year, month) = next_month(year, month);
+                }
+                None
+            }
+        }
+    }
+}
+
+/// 获取星期的中文名称
+pub fn weekday_name(weekday: &Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "一",
+        Weekday::Tue => "二",
+        Weekday::Wed => "三",
+        Weekday::Thu => "四",
+        Weekday::Fri => "五",
+        Weekday::Sat => "六",
+        Weekday::Sun => "日",
+    }
+}
+
+/// 将本地日期时间转换为指定时区的时间：夏令时结束时重复的时间取较早的一个，
+/// 夏令时开始时跳过的时间顺延一小时
+fn resolve_local<Tz: TimeZone>(tz: &Tz, local: NaiveDateTime) -> Option<DateTime<Tz>> {
+    tz.from_local_datetime(&local)
+        .earliest()
+        .or_else(|| tz.from_local_datetime(&(local + Duration::hours(1))).earliest())
+}
+
+/// 获取下一个月份
+fn next_month(year: i32, month: u32) -> (i32, u32) {
+    if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    }
+}
+
+/// 构造日期，日超出当月天数时取当月最后一天
+fn clamped_date(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+    (1..=day.clamp(1, 31))
+        .rev()
+        .find_map(|d| NaiveDate::from_ymd_opt(year, month, d))
+}
+
+/// 获取某月第N个星期X，ordinal为负数时从月末开始计算
+fn nth_weekday_of_month(year: i32, month: u32, ordinal: i8, weekday: Weekday) -> Option<NaiveDate> {
+    if ordinal < 0 {
+        let last = clamped_date(year, month, 31)?;
+        let back = (last.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+        let date = last - Duration::days(back as i64 + 7 * (-(ordinal as i64) - 1));
+        (date.month() == month).then_some(date)
+    } else {
+        NaiveDate::from_weekday_of_month_opt(year, month, weekday, ordinal.max(1) as u8)
+    }
+}
+
+/// 时间记录，结束时间为空表示计时器仍在运行
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TimeEntry {
+    pub id: String,
+    pub start: DateTime<Local>,
+    pub end: Option<DateTime<Local>>,
+}
+
+impl TimeEntry {
+    pub fn new(start: DateTime<Local>, end: Option<DateTime<Local>>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            start,
+            end,
+        }
+    }
+
+    /// 是否正在计时
+    pub fn is_running(&self) -> bool {
+        self.end.is_none()
+    }
+
+    /// 获取记录时长，运行中的记录计算到当前时间
+    pub fn duration(&self) -> Duration {
+        let end = self.end.unwrap_or_else(Local::now);
+        (end - self.start).max(Duration::zero())
+    }
+
+    /// 按自然日拆分记录时长
+    pub fn split_by_day(&self) -> Vec<(NaiveDate, Duration)> {
+        let end = self.end.unwrap_or_else(Local::now);
+        let mut result = Vec::new();
+        let mut cursor = self.start;
+        
+        while cursor < end {
+            let day = cursor.date_naive();
+            // 下一个午夜，无法确定时直接截止到结束时间
+            let next_midnight = day
+                .succ_opt()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .and_then(|dt| Local.from_local_datetime(&dt).earliest())
+                .unwrap_or(end);
+            let segment_end = next_midnight.min(end);
+            result.push((day, segment_end - cursor));
+            cursor = segment_end;
+        }
+        
+        result
+    }
+}
+
+/// 格式化时长为友好字符串
+pub fn format_duration(duration: Duration) -> String {
+    let total_minutes = duration.num_minutes();
+    let hours = total_minutes / 60;
+    let minutes = total_minutes % 60;
+    
+    if hours > 0 {
+        format!("{}小时{:02}分", hours, minutes)
+    } else if total_minutes > 0 {
+        format!("{}分钟", minutes)
+    } else {
+        format!("{}秒", duration.num_seconds().max(0))
+    }
+}
+
+/// 计算一组任务从创建到完成的平均用时，没有已完成任务时返回None
+fn average_completion<'a>(todos: impl Iterator<Item = &'a Todo>) -> Option<Duration> {
+    let durations: Vec<Duration> = todos
+        .filter(|t| t.completed)
+        .filter_map(|t| t.completed_at.map(|c| c - t.created_at))
+        .filter(|d| *d >= Duration::zero())
+        .collect();
+    if durations.is_empty() {
+        return None;
+    }
+    let total = durations.iter().fold(Duration::zero(), |sum, d| sum + *d);
+    Some(total / durations.len() as i32)
+}
+
 /// 单个待办事项
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Todo {
     pub id: String,
     pub title: String,
@@ -130,6 +472,30 @@ pub struct Todo {
     pub emoji: Emoji,
     pub tags: Vec<String>,
     pub subtasks: Vec<SubTask>,
+    /// 重复规则
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    /// 重复任务系列ID，同一系列的历史实例共享此ID
+    #[serde(default)]
+    pub series_id: Option<String>,
+    /// 阻塞此任务的前置任务ID
+    #[serde(default)]
+    pub blocked_by: Vec<String>,
+    /// 时间记录
+    #[serde(default)]
+    pub time_entries: Vec<TimeEntry>,
+    /// 提醒
+    #[serde(default)]
+    pub reminders: Vec<Reminder>,
+    /// 预计用时（分钟）
+    #[serde(default)]
+    pub estimate_minutes: Option<i64>,
+    /// 工作流状态ID，为空时根据完成状态取默认状态
+    #[serde(default)]
+    pub status: String,
+    /// 推迟到此时间之前不在列表中显示
+    #[serde(default)]
+    pub defer_until: Option<DateTime<Local>>,
 }
 
 /// 子任务
@@ -138,13 +504,17 @@ pub struct SubTask {
     pub id: String,
     pub title: String,
     pub completed: bool,
+    /// 下级子任务
+    #[serde(default)]
+    pub children: Vec<SubTask>,
 }
 
 impl PartialEq for SubTask {
     fn eq(&self, other: &Self) -> bool {
         self.id == other.id && 
         self.title == other.title && 
-        self.completed == other.completed
+        self.completed == other.completed &&
+        self.children == other.children
     }
 }
 
@@ -154,11 +524,116 @@ impl SubTask {
             id: Uuid::new_v4().to_string(),
             title,
             completed: false,
+            children: Vec::new(),
+        }
+    }
+
+    /// 设置完成状态，标记完成时同时完成所有下级子任务
+    pub fn set_completed(&mut self, completed: bool) {
+        self.completed = completed;
+        if completed {
+            for child in &mut self.children {
+                child.set_completed(true);
+            }
+        }
+    }
+
+    /// 获取完成进度，有下级子任务时取下级进度的平均值
+    pub fn progress(&self) -> f32 {
+        if self.children.is_empty() {
+            if self.completed {
+                1.0
+            } else {
+                0.0
+            }
+        } else {
+            self.children.iter().map(|c| c.progress()).sum::<f32>() / self.children.len() as f32
+        }
+    }
+
+    /// 复制子任务结构，生成新的ID并重置完成状态
+    pub fn fresh_copy(&self) -> Self {
+        let mut copy = SubTask::new(self.title.clone());
+        copy.children = self.children.iter().map(|c| c.fresh_copy()).collect();
+        copy
+    }
+
+    /// 转为独立任务，继承所属任务的标签和优先级，下级子任务成为新任务的子任务
+    pub fn into_todo(self, parent: &Todo) -> Todo {
+        let mut todo = Todo::new(self.title);
+        todo.id = format!("todo-{}", Uuid::new_v4());
+        todo.tags = parent.tags.clone();
+        todo.priority = parent.priority.clone();
+        todo.subtasks = self.children;
+        todo.set_completed(self.completed);
+        todo
+    }
+
+    /// 统计自身及所有下级子任务的(已完成数, 总数)
+    pub fn count(&self) -> (usize, usize) {
+        self.children.iter().fold(
+            (self.completed as usize, 1),
+            |(done, total), child| {
+                let (d, t) = child.count();
+                (done + d, total + t)
+            },
+        )
+    }
+}
+
+/// 在子任务树中查找指定ID的子任务
+pub fn find_subtask_mut<'a>(subtasks: &'a mut [SubTask], id: &str) -> Option<&'a mut SubTask> {
+    for subtask in subtasks {
+        if subtask.id == id {
+            return Some(subtask);
+        }
+        if let Some(found) = find_subtask_mut(&mut subtask.children, id) {
+            return Some(found);
         }
     }
+    None
+}
+
+/// 从子任务树中移除并返回指定ID的子任务
+pub fn take_subtask(subtasks: &mut Vec<SubTask>, id: &str) -> Option<SubTask> {
+    if let Some(index) = subtasks.iter().position(|s| s.id == id) {
+        return Some(subtasks.remove(index));
+    }
+    subtasks.iter_mut().find_map(|subtask| take_subtask(&mut subtask.children, id))
 }
 
 impl Todo {
+    /// 转为子任务，原有的子任务成为下级子任务，描述、截止时间等其他信息不保留
+    ///
+    /// 转换前可用 `demotion_losses` 检查会丢失哪些信息。
+    pub fn into_subtask(self) -> SubTask {
+        let mut subtask = SubTask::new(self.title);
+        subtask.completed = self.completed;
+        subtask.children = self.subtasks;
+        subtask
+    }
+
+    /// 转为子任务时会丢失的信息名称，为空表示除标题和子任务外没有其他信息
+    pub fn demotion_losses(&self) -> Vec<&'static str> {
+        let running = self.running_entry().is_some();
+        [
+            (!self.description.trim().is_empty(), "描述"),
+            (!self.tags.is_empty(), "标签"),
+            (self.due_date.is_some(), "截止时间"),
+            (self.recurrence.is_some(), "重复规则"),
+            (!self.reminders.is_empty(), "提醒"),
+            (!self.blocked_by.is_empty(), "前置任务"),
+            (self.estimate_minutes.is_some(), "预计用时"),
+            (self.defer_until.is_some(), "推迟时间"),
+            (!self.time_entries.is_empty() && !running, "时间记录"),
+            (running, "时间记录（包括正在进行的计时）"),
+        ]
+        .into_iter()
+        .filter(|(lost, _)| *lost)
+        .map(|(_, name)| name)
+        .collect()
+    }
+
     /// 创建新的待办事项
     pub fn new(title: String) -> Self {
         Self {
@@ -173,11 +648,23 @@ impl Todo {
             emoji: Emoji::random(),
             tags: Vec::new(),
             subtasks: Vec::new(),
+            recurrence: None,
+            series_id: None,
+            blocked_by: Vec::new(),
+            time_entries: Vec::new(),
+            reminders: Vec::new(),
+            estimate_minutes: None,
+            status: String::new(),
+            defer_until: None,
         }
     }
 
+    /// 检查任务是否处于推迟状态，已完成的任务不算推迟
+    pub fn is_deferred(&self, now: DateTime<Local>) -> bool {
+        !self.completed && self.defer_until.is_some_and(|until| until > now)
+    }
+
     /// 检查任务是否已过期
-    #[allow(dead_code)]
     pub fn is_overdue(&self) -> bool {
         if let Some(due) = self.due_date {
             !self.completed && due < Local::now()
@@ -186,8 +673,59 @@ impl Todo {
         }
     }
 
-    /// 获取完成百分比
-    #[allow(dead_code)]
+    /// 获取任务在日历中显示的日期：已完成的任务显示在完成日，其余显示在截止日
+    pub fn calendar_date(&self) -> Option<NaiveDate> {
+        if self.completed {
+            self.completed_at.map(|d| d.date_naive())
+        } else {
+            self.due_date.map(|d| d.date_naive())
+        }
+    }
+
+    /// 将截止时间改到指定日期，保留原来的时分，没有截止时间时默认为18:00
+    pub fn reschedule_to(&mut self, date: NaiveDate) {
+        let (hour, minute) = self.due_date.map(|d| (d.hour(), d.minute())).unwrap_or((18, 0));
+        let due = date
+            .and_hms_opt(hour, minute, 0)
+            .and_then(|naive| Local.from_local_datetime(&naive).earliest());
+        if due.is_some() {
+            self.due_date = due;
+        }
+    }
+
+    /// 获取任务所属的截止日期分组，已完成的任务不论截止时间都归入已完成
+    pub fn due_bucket(&self) -> DueBucket {
+        if self.completed {
+            return DueBucket::Completed;
+        }
+        
+        let due = match self.due_date {
+            Some(due) => due,
+            None => return DueBucket::NoDate,
+        };
+        
+        let today = Local::now().date_naive();
+        let due_day = due.date_naive();
+        
+        // 已过截止时间，或截止日期在今天之前
+        if self.is_overdue() || due_day < today {
+            return DueBucket::Overdue;
+        }
+        
+        if due_day == today {
+            return DueBucket::Today;
+        }
+        
+        // 本周剩余的日子（周日为一周的最后一天）
+        let days_to_week_end = 6 - today.weekday().num_days_from_monday() as i64;
+        if due_day <= today + Duration::days(days_to_week_end) {
+            DueBucket::ThisWeek
+        } else {
+            DueBucket::Later
+        }
+    }
+
+    /// 获取完成百分比，逐级汇总子任务进度
     pub fn completion_percentage(&self) -> f32 {
         if self.subtasks.is_empty() {
             if self.completed {
@@ -196,21 +734,99 @@ impl Todo {
                 0.0
             }
         } else {
-            let completed = self.subtasks.iter().filter(|t| t.completed).count();
-            completed as f32 / self.subtasks.len() as f32
+            self.subtasks.iter().map(|t| t.progress()).sum::<f32>() / self.subtasks.len() as f32
+        }
+    }
+
+    /// 统计所有层级子任务的(已完成数, 总数)
+    pub fn subtask_counts(&self) -> (usize, usize) {
+        self.subtasks.iter().fold((0, 0), |(done, total), subtask| {
+            let (d, t) = subtask.count();
+            (done + d, total + t)
+        })
+    }
+
+    /// 获取正在运行的时间记录
+    pub fn running_entry(&self) -> Option<&TimeEntry> {
+        self.time_entries.iter().find(|e| e.is_running())
+    }
+
+    /// 开始计时，已在计时时不做处理
+    pub fn start_timer(&mut self) {
+        if self.running_entry().is_none() {
+            self.time_entries.push(TimeEntry::new(Local::now(), None));
+        }
+    }
+
+    /// 停止计时
+    pub fn stop_timer(&mut self) {
+        let now = Local::now();
+        for entry in self.time_entries.iter_mut().filter(|e| e.is_running()) {
+            entry.end = Some(now);
         }
     }
 
+    /// 获取累计记录的时长
+    pub fn tracked_duration(&self) -> Duration {
+        self.time_entries.iter().fold(Duration::zero(), |sum, e| sum + e.duration())
+    }
+
     /// 设置任务完成状态
-    pub fn set_completed(&mut self, completed: bool) {
+    ///
+    /// 完成重复任务时返回下一次的任务实例，当前实例保留为历史记录且不再重复。
+    pub fn set_completed(&mut self, completed: bool) -> Option<Todo> {
+        let mut next = None;
+        
         // 如果状态变为已完成且之前不是已完成状态，记录完成时间
         if completed && !self.completed {
-            self.completed_at = Some(Local::now());
+            let now = Local::now();
+            self.completed_at = Some(now);
+            self.stop_timer();
+            
+            if let Some(recurrence) = self.recurrence.take() {
+                let series_id = self.series_id.get_or_insert_with(|| self.id.clone()).clone();
+                next = Some(self.next_occurrence(recurrence, series_id, now));
+            }
         } else if !completed {
             // 如果标记为未完成，清除完成时间
             self.completed_at = None;
         }
         self.completed = completed;
+        
+        next
+    }
+
+    /// 生成重复任务的下一次实例
+    fn next_occurrence(&self, recurrence: Recurrence, series_id: String, completed_at: DateTime<Local>) -> Todo {
+        let mut next = Todo::new(self.title.clone());
+        next.description = self.description.clone();
+        next.priority = self.priority.clone();
+        next.emoji = self.emoji.clone();
+        next.tags = self.tags.clone();
+        next.subtasks = self.subtasks.iter().map(|st| st.fresh_copy()).collect();
+        next.due_date = recurrence.next_due(self.due_date, completed_at);
+        next.recurrence = Some(recurrence);
+        next.series_id = Some(series_id);
+        next.blocked_by = self.blocked_by.clone();
+        
+        // 指定时间的提醒随截止时间一起顺延
+        let shift = match (self.due_date, next.due_date) {
+            (Some(old), Some(new)) => new - old,
+            _ => Duration::zero(),
+        };
+        next.reminders = self.reminders.iter().map(|r| {
+            let kind = match &r.kind {
+                ReminderKind::Absolute(at) => ReminderKind::Absolute(*at + shift),
+                kind => kind.clone(),
+            };
+            Reminder::new(kind)
+        }).collect();
+        
+        // 推迟时间与截止时间保持相同的间隔
+        next.defer_until = self.defer_until
+            .filter(|_| self.due_date.is_some() && next.due_date.is_some())
+            .map(|until| until + shift);
+        next
     }
 
     /// 格式化日期时间为友好字符串
@@ -246,6 +862,85 @@ impl Todo {
             dt.format("%Y-%m-%d %H:%M").to_string()
         }
     }
+
+    /// 格式化截止时间为友好字符串（支持未来时间）
+    pub fn format_due_date(dt: &DateTime<Local>) -> String {
+        let now = Local::now();
+        let days = (dt.date_naive() - now.date_naive()).num_days();
+        
+        match days {
+            -1 => format!("昨天 {}", dt.format("%H:%M")),
+            0 => format!("今天 {}", dt.format("%H:%M")),
+            1 => format!("明天 {}", dt.format("%H:%M")),
+            2 => format!("后天 {}", dt.format("%H:%M")),
+            _ if dt.year() == now.year() => dt.format("%m-%d %H:%M").to_string(),
+            _ => dt.format("%Y-%m-%d %H:%M").to_string(),
+        }
+    }
+}
+
+/// 工作流状态
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Status {
+    pub id: String,
+    pub name: String,
+    /// 是否为终止状态，处于终止状态的任务视为已完成
+    pub terminal: bool,
+}
+
+impl Status {
+    pub fn new(name: String, terminal: bool) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name,
+            terminal,
+        }
+    }
+}
+
+/// 默认的工作流状态
+fn default_statuses() -> Vec<Status> {
+    vec![
+        Status { id: "todo".to_string(), name: "待办".to_string(), terminal: false },
+        Status { id: "in_progress".to_string(), name: "进行中".to_string(), terminal: false },
+        Status { id: "done".to_string(), name: "已完成".to_string(), terminal: true },
+    ]
+}
+
+/// 回收站中的任务
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrashedTodo {
+    pub todo: Todo,
+    pub deleted_at: DateTime<Local>,
+}
+
+/// 任务筛选条件，可以来自任务列表当前的设置或智能列表
+pub struct TodoFilter<'a> {
+    pub tags: &'a [String],
+    pub tag_match: TagMatch,
+    pub excluded_tags: &'a [String],
+    pub hide_completed: bool,
+    pub hide_blocked: bool,
+    pub show_deferred: bool,
+    /// 只显示指定工作流状态的任务
+    pub status: Option<&'a String>,
+    pub search: Option<&'a Query>,
+}
+
+/// 单个标签的统计数据
+#[derive(Clone, Debug)]
+pub struct TagStats {
+    pub tag: String,
+    pub total: usize,
+    pub done: usize,
+    pub overdue: usize,
+    /// 平均完成用时（从创建到完成）
+    pub average_completion: Option<Duration>,
+}
+
+/// 回收站默认保留天数
+fn default_trash_retention_days() -> u32 {
+    30
 }
 
 /// 待办事项列表
@@ -253,8 +948,30 @@ impl Todo {
 pub struct TodoList {
     pub todos: HashMap<String, Todo>,
     pub active_tags: Vec<String>,
+    #[serde(default)]
+    pub tag_match: TagMatch, // 筛选标签的匹配方式
+    #[serde(default)]
+    pub excluded_tags: Vec<String>, // 排除的标签，带有其中任一标签的任务不显示
     pub filter_completed: bool,
     pub priority_sort: Option<bool>, // true表示从高到低排序，false表示从低到高，None表示默认按时间排序
+    #[serde(default)]
+    pub hide_blocked: bool, // 是否隐藏被阻塞的任务
+    #[serde(default)]
+    pub show_deferred: bool, // 是否显示尚未到开始时间的推迟任务
+    #[serde(default)]
+    pub trash: Vec<TrashedTodo>, // 回收站，按删除时间排列
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u32, // 回收站自动清除天数，0表示永不清除
+    #[serde(default)]
+    pub manual_sort: bool, // 是否按手动顺序排序，优先于优先级排序
+    #[serde(default)]
+    pub manual_order: Vec<String>, // 手动排序的任务ID顺序，不在其中的任务排在最后
+    #[serde(default = "default_statuses")]
+    pub statuses: Vec<Status>, // 工作流状态，按流程顺序排列
+    #[serde(default)]
+    pub status_filter: Option<String>, // 只显示指定状态的任务
+    #[serde(skip)]
+    pub search: Option<Query>, // 当前的搜索条件，不保存
 }
 
 impl Default for TodoList {
@@ -262,8 +979,19 @@ impl Default for TodoList {
         Self {
             todos: HashMap::new(),
             active_tags: Vec::new(),
+            tag_match: TagMatch::Any,
+            excluded_tags: Vec::new(),
             filter_completed: false,
             priority_sort: None, // 默认按时间排序
+            hide_blocked: false,
+            show_deferred: false,
+            trash: Vec::new(),
+            trash_retention_days: default_trash_retention_days(),
+            manual_sort: false,
+            manual_order: Vec::new(),
+            statuses: default_statuses(),
+            status_filter: None,
+            search: None,
         }
     }
 }
@@ -275,57 +1003,670 @@ impl TodoList {
         self.todos.insert(todo.id.clone(), todo);
     }
 
-    /// 删除待办事项
-    pub fn remove_todo(&mut self, id: &str) {
-        self.todos.remove(id);
+    /// 将标签加入筛选标签，同时从排除的标签中移除
+    pub fn include_tag(&mut self, tag: &str) {
+        self.excluded_tags.retain(|t| t != tag);
+        if !self.active_tags.iter().any(|t| t == tag) {
+            self.active_tags.push(tag.to_string());
+        }
     }
 
-    /// 获取所有标签
-    #[allow(dead_code)]
-    pub fn all_tags(&self) -> Vec<String> {
-        let mut tags = Vec::new();
-        for todo in self.todos.values() {
-            for tag in &todo.tags {
-                if !tags.contains(tag) {
-                    tags.push(tag.clone());
-                }
-            }
+    /// 将标签加入排除的标签，同时从筛选标签中移除
+    pub fn exclude_tag(&mut self, tag: &str) {
+        self.active_tags.retain(|t| t != tag);
+        if !self.excluded_tags.iter().any(|t| t == tag) {
+            self.excluded_tags.push(tag.to_string());
         }
-        tags.sort();
-        tags
     }
 
-    /// 获取过滤后的待办事项列表
-    pub fn filtered_todos(&self) -> Vec<&Todo> {
-        let mut result: Vec<&Todo> = self.todos.values().collect();
-        
+    /// 取消标签的筛选和排除
+    pub fn clear_tag_filter(&mut self, tag: &str) {
+        self.active_tags.retain(|t| t != tag);
+        self.excluded_tags.retain(|t| t != tag);
+    }
+
+    /// 获取任务列表当前的筛选条件
+    pub fn current_filter(&self) -> TodoFilter<'_> {
+        TodoFilter {
+            tags: &self.active_tags,
+            tag_match: self.tag_match,
+            excluded_tags: &self.excluded_tags,
+            hide_completed: self.filter_completed,
+            hide_blocked: self.hide_blocked,
+            show_deferred: self.show_deferred,
+            status: self.status_filter.as_ref(),
+            search: self.search.as_ref(),
+        }
+    }
+
+    /// 检查任务是否满足筛选条件
+    pub fn matches_filter(&self, todo: &Todo, filter: &TodoFilter) -> bool {
         // 按标签过滤
-        if !self.active_tags.is_empty() {
-            result = result
-                .into_iter()
-                .filter(|todo| {
-                    self.active_tags
-                        .iter()
-                        .any(|tag| todo.tags.contains(tag))
-                })
-                .collect();
+        if !filter.tags.is_empty() {
+            let matched = match filter.tag_match {
+                TagMatch::Any => filter.tags.iter().any(|tag| todo.tags.contains(tag)),
+                TagMatch::All => filter.tags.iter().all(|tag| todo.tags.contains(tag)),
+                TagMatch::None => !filter.tags.iter().any(|tag| todo.tags.contains(tag)),
+            };
+            if !matched {
+                return false;
+            }
+        }
+        if filter.excluded_tags.iter().any(|tag| todo.tags.contains(tag)) {
+            return false;
         }
         
         // 过滤已完成的任务
-        if self.filter_completed {
-            result = result.into_iter().filter(|todo| !todo.completed).collect();
+        if filter.hide_completed && todo.completed {
+            return false;
         }
         
-        // 按优先级和日期排序
-        result.sort_by(|a, b| {
-            // 先按完成状态
-            let comp = a.completed.cmp(&b.completed);
-            if comp != std::cmp::Ordering::Equal {
-                return comp;
+        // 过滤被阻塞的任务
+        if filter.hide_blocked && self.is_blocked(todo) {
+            return false;
+        }
+        
+        // 过滤推迟的任务
+        if !filter.show_deferred && todo.is_deferred(Local::now()) {
+            return false;
+        }
+        
+        // 按工作流状态过滤
+        if let Some(status_id) = filter.status {
+            if self.status_of(todo).map(|s| &s.id) != Some(status_id) {
+                return false;
+            }
+        }
+        
+        // 按搜索条件过滤
+        filter.search.is_none_or(|query| query.matches(todo, self))
+    }
+
+    /// 获取将任务标记为完成或未完成时应使用的状态，当前状态类别不符时取对应类别的默认状态
+    pub fn completion_status_id(&self, todo: &Todo, completed: bool) -> String {
+        match self.status_of(todo) {
+            Some(status) if status.terminal == completed => status.id.clone(),
+            _ => self.default_status_id(completed),
+        }
+    }
+
+    /// 设置任务的工作流状态，完成状态由是否为终止状态决定，完成重复任务时自动添加下一次实例
+    pub fn set_todo_status(&mut self, id: &str, status_id: &str) {
+        let terminal = match self.status(status_id) {
+            Some(status) => status.terminal,
+            None => return,
+        };
+        
+        let next = match self.todos.get_mut(id) {
+            Some(todo) => {
+                todo.status = status_id.to_string();
+                todo.set_completed(terminal)
+            }
+            None => return,
+        };
+        
+        if let Some(mut next) = next {
+            next.status = self.default_status_id(false);
+            self.add_todo(next);
+        }
+    }
+
+    /// 根据ID获取工作流状态
+    pub fn status(&self, id: &str) -> Option<&Status> {
+        self.statuses.iter().find(|s| s.id == id)
+    }
+
+    /// 获取任务的工作流状态，状态无效时取与完成状态对应的默认状态
+    pub fn status_of(&self, todo: &Todo) -> Option<&Status> {
+        self.status(&todo.status)
+            .filter(|s| s.terminal == todo.completed)
+            .or_else(|| self.statuses.iter().find(|s| s.terminal == todo.completed))
+    }
+
+    /// 获取任务状态在流程中的位置
+    pub fn status_position(&self, todo: &Todo) -> usize {
+        self.status_of(todo)
+            .and_then(|status| self.statuses.iter().position(|s| s.id == status.id))
+            .unwrap_or(0)
+    }
+
+    /// 获取终止或非终止类别中的第一个状态ID
+    pub fn default_status_id(&self, terminal: bool) -> String {
+        self.statuses
+            .iter()
+            .find(|s| s.terminal == terminal)
+            .map(|s| s.id.clone())
+            .unwrap_or_default()
+    }
+
+    /// 按流程顺序统计每个状态的任务数量
+    pub fn status_counts(&self) -> Vec<(&Status, usize)> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for todo in self.todos.values() {
+            if let Some(status) = self.status_of(todo) {
+                *counts.entry(status.id.as_str()).or_insert(0) += 1;
+            }
+        }
+        
+        self.statuses
+            .iter()
+            .map(|s| (s, counts.get(s.id.as_str()).copied().unwrap_or(0)))
+            .collect()
+    }
+
+    /// 整理工作流状态：确保终止和非终止状态都至少有一个，并修正任务的无效状态
+    pub fn normalize_statuses(&mut self) {
+        for terminal in [false, true] {
+            if !self.statuses.iter().any(|s| s.terminal == terminal) {
+                if let Some(status) = default_statuses().into_iter().find(|s| s.terminal == terminal && self.status(&s.id).is_none()) {
+                    self.statuses.push(status);
+                } else {
+                    let name = if terminal { "已完成" } else { "待办" };
+                    self.statuses.push(Status::new(name.to_string(), terminal));
+                }
+            }
+        }
+        
+        let fixes: Vec<(String, String)> = self.todos
+            .values()
+            .filter(|t| self.status(&t.status).map(|s| s.terminal) != Some(t.completed))
+            .filter_map(|t| self.status_of(t).map(|s| (t.id.clone(), s.id.clone())))
+            .collect();
+        for (id, status_id) in fixes {
+            if let Some(todo) = self.todos.get_mut(&id) {
+                todo.status = status_id;
+            }
+        }
+        
+        if let Some(filter) = &self.status_filter {
+            if self.status(filter).is_none() {
+                self.status_filter = None;
+            }
+        }
+    }
+
+    /// 添加工作流状态，终止状态添加到末尾，非终止状态添加到第一个终止状态之前
+    pub fn add_status(&mut self, name: &str, terminal: bool) -> Result<(), String> {
+        let name = Self::validate_status_name(name)?;
+        if self.statuses.iter().any(|s| s.name == name) {
+            return Err(format!("状态 '{}' 已存在", name));
+        }
+        
+        let status = Status::new(name, terminal);
+        let index = if terminal {
+            self.statuses.len()
+        } else {
+            self.statuses.iter().position(|s| s.terminal).unwrap_or(self.statuses.len())
+        };
+        self.statuses.insert(index, status);
+        Ok(())
+    }
+
+    /// 重命名工作流状态
+    pub fn rename_status(&mut self, id: &str, name: &str) -> Result<(), String> {
+        let name = Self::validate_status_name(name)?;
+        if self.statuses.iter().any(|s| s.name == name && s.id != id) {
+            return Err(format!("状态 '{}' 已存在", name));
+        }
+        
+        match self.statuses.iter_mut().find(|s| s.id == id) {
+            Some(status) => {
+                status.name = name;
+                Ok(())
+            }
+            None => Err("状态不存在".to_string()),
+        }
+    }
+
+    /// 删除工作流状态，该状态下的任务移到同类别的默认状态
+    pub fn remove_status(&mut self, id: &str) -> Result<(), String> {
+        let terminal = self.status(id).map(|s| s.terminal).ok_or_else(|| "状态不存在".to_string())?;
+        if self.statuses.iter().filter(|s| s.terminal == terminal).count() <= 1 {
+            return Err(if terminal {
+                "至少需要保留一个终止状态".to_string()
+            } else {
+                "至少需要保留一个非终止状态".to_string()
+            });
+        }
+        
+        self.statuses.retain(|s| s.id != id);
+        self.normalize_statuses();
+        Ok(())
+    }
+
+    /// 在流程中上移或下移工作流状态
+    pub fn move_status(&mut self, id: &str, up: bool) {
+        if let Some(index) = self.statuses.iter().position(|s| s.id == id) {
+            if up && index > 0 {
+                self.statuses.swap(index, index - 1);
+            } else if !up && index + 1 < self.statuses.len() {
+                self.statuses.swap(index, index + 1);
+            }
+        }
+    }
+
+    /// 设置工作流状态是否为终止状态，并同步该状态下任务的完成状态
+    ///
+    /// 只修改完成状态和完成时间，不生成重复任务的下一次实例，再次切换时可以完全还原。
+    pub fn set_status_terminal(&mut self, id: &str, terminal: bool) -> Result<(), String> {
+        let current = self.status(id).map(|s| s.terminal).ok_or_else(|| "状态不存在".to_string())?;
+        if current == terminal {
+            return Ok(());
+        }
+        if self.statuses.iter().filter(|s| s.terminal == current).count() <= 1 {
+            return Err(if current {
+                "至少需要保留一个终止状态".to_string()
+            } else {
+                "至少需要保留一个非终止状态".to_string()
+            });
+        }
+        
+        if let Some(status) = self.statuses.iter_mut().find(|s| s.id == id) {
+            status.terminal = terminal;
+        }
+        
+        let now = Local::now();
+        for todo in self.todos.values_mut().filter(|t| t.status == id && t.completed != terminal) {
+            todo.completed = terminal;
+            todo.completed_at = terminal.then_some(now);
+        }
+        Ok(())
+    }
+
+    /// 验证状态名称
+    fn validate_status_name(name: &str) -> Result<String, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("状态名称不能为空".to_string());
+        }
+        Ok(name.to_string())
+    }
+
+    /// 获取同一重复系列中已完成的历史实例（按完成时间从新到旧）
+    pub fn series_history(&self, series_id: &str) -> Vec<&Todo> {
+        let mut history: Vec<&Todo> = self.todos
+            .values()
+            .filter(|t| t.completed && t.series_id.as_deref() == Some(series_id))
+            .collect();
+        history.sort_by_key(|t| std::cmp::Reverse(t.completed_at));
+        history
+    }
+
+    /// 获取阻塞指定任务且尚未完成的前置任务
+    pub fn open_blockers(&self, todo: &Todo) -> Vec<&Todo> {
+        todo.blocked_by
+            .iter()
+            .filter_map(|id| self.todos.get(id))
+            .filter(|blocker| !blocker.completed)
+            .collect()
+    }
+
+    /// 检查任务是否被未完成的前置任务阻塞
+    pub fn is_blocked(&self, todo: &Todo) -> bool {
+        !self.open_blockers(todo).is_empty()
+    }
+
+    /// 检查为任务添加前置任务后是否会产生循环依赖
+    pub fn would_create_cycle(&self, todo_id: &str, blocker_id: &str) -> bool {
+        // 从前置任务出发沿依赖链查找，能回到当前任务即为循环
+        let mut stack = vec![blocker_id];
+        let mut visited = std::collections::HashSet::new();
+        
+        while let Some(id) = stack.pop() {
+            if id == todo_id {
+                return true;
+            }
+            if !visited.insert(id) {
+                continue;
+            }
+            if let Some(todo) = self.todos.get(id) {
+                stack.extend(todo.blocked_by.iter().map(|s| s.as_str()));
+            }
+        }
+        
+        false
+    }
+
+    /// 获取所有未完成任务中尚未触发的提醒
+    pub fn scheduled_reminders(&self) -> Vec<ScheduledReminder> {
+        self.todos.values()
+            .filter(|t| !t.completed)
+            .flat_map(|t| {
+                t.reminders.iter()
+                    .filter(|r| r.is_pending(t.due_date))
+                    .filter_map(|r| r.trigger_time(t.due_date).map(|at| ScheduledReminder {
+                        todo_id: t.id.clone(),
+                        reminder_id: r.id.clone(),
+                        title: t.title.clone(),
+                        due_date: t.due_date,
+                        at,
+                    }))
+            })
+            .collect()
+    }
+
+    /// 记录提醒已在指定时间触发
+    pub fn mark_reminder_fired(&mut self, todo_id: &str, reminder_id: &str, at: DateTime<Local>) {
+        if let Some(reminder) = self.todos.get_mut(todo_id)
+            .and_then(|t| t.reminders.iter_mut().find(|r| r.id == reminder_id))
+        {
+            reminder.fired_for = Some(at);
+        }
+    }
+
+    /// 获取正在计时的任务
+    pub fn running_timer(&self) -> Option<&Todo> {
+        self.todos.values().find(|t| t.running_entry().is_some())
+    }
+
+    /// 开始为任务计时，同一时间只允许一个计时器运行
+    pub fn start_timer(&mut self, id: &str) {
+        if !self.todos.contains_key(id) {
+            return;
+        }
+        for todo in self.todos.values_mut() {
+            if todo.id == id {
+                todo.start_timer();
+            } else {
+                todo.stop_timer();
+            }
+        }
+    }
+
+    /// 按任务汇总记录时长（从多到少）
+    pub fn time_by_task(&self) -> Vec<(&Todo, Duration)> {
+        let mut result: Vec<(&Todo, Duration)> = self.todos
+            .values()
+            .map(|t| (t, t.tracked_duration()))
+            .filter(|(_, d)| *d > Duration::zero())
+            .collect();
+        result.sort_by_key(|(_, d)| std::cmp::Reverse(*d));
+        result
+    }
+
+    /// 按标签汇总记录时长（从多到少），没有标签的任务归入空字符串
+    pub fn time_by_tag(&self) -> Vec<(String, Duration)> {
+        let mut totals: HashMap<String, Duration> = HashMap::new();
+        for todo in self.todos.values() {
+            let duration = todo.tracked_duration();
+            if duration <= Duration::zero() {
+                continue;
+            }
+            if todo.tags.is_empty() {
+                *totals.entry(String::new()).or_insert_with(Duration::zero) += duration;
+            }
+            for tag in &todo.tags {
+                *totals.entry(tag.clone()).or_insert_with(Duration::zero) += duration;
+            }
+        }
+        
+        let mut result: Vec<(String, Duration)> = totals.into_iter().collect();
+        result.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        result
+    }
+
+    /// 按自然日汇总记录时长（按日期排序）
+    pub fn time_by_day(&self) -> BTreeMap<NaiveDate, Duration> {
+        let mut totals = BTreeMap::new();
+        for entry in self.todos.values().flat_map(|t| &t.time_entries) {
+            for (day, duration) in entry.split_by_day() {
+                *totals.entry(day).or_insert_with(Duration::zero) += duration;
+            }
+        }
+        totals
+    }
+
+    /// 按完成日统计完成数量，包含没有完成任务的日期
+    pub fn completions_by_day(&self, from: NaiveDate, to: NaiveDate) -> Vec<(NaiveDate, usize)> {
+        let mut counts: BTreeMap<NaiveDate, usize> = from.iter_days()
+            .take_while(|d| *d <= to)
+            .map(|d| (d, 0))
+            .collect();
+        for date in self.todos.values().filter_map(|t| t.completed_at.filter(|_| t.completed)) {
+            if let Some(count) = counts.get_mut(&date.date_naive()) {
+                *count += 1;
+            }
+        }
+        counts.into_iter().collect()
+    }
+
+    /// 按周（周一开始）统计完成数量，返回每周的周一和完成数
+    pub fn completions_by_week(&self, from: NaiveDate, to: NaiveDate) -> Vec<(NaiveDate, usize)> {
+        let week_start = |d: NaiveDate| d - Duration::days(d.weekday().num_days_from_monday() as i64);
+        let mut counts: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+        let mut week = week_start(from);
+        while week <= to {
+            counts.insert(week, 0);
+            week += Duration::days(7);
+        }
+        for (day, count) in self.completions_by_day(from, to) {
+            *counts.entry(week_start(day)).or_insert(0) += count;
+        }
+        counts.into_iter().collect()
+    }
+
+    /// 每天结束时的(未完成数, 已完成数)走势
+    pub fn open_done_trend(&self, from: NaiveDate, to: NaiveDate) -> Vec<(NaiveDate, usize, usize)> {
+        from.iter_days()
+            .take_while(|d| *d <= to)
+            .map(|day| {
+                let mut open = 0;
+                let mut done = 0;
+                for todo in self.todos.values() {
+                    if todo.created_at.date_naive() > day {
+                        continue;
+                    }
+                    // 没有完成时间的旧数据按创建日计为完成
+                    let completed_on = todo.completed_at.unwrap_or(todo.created_at).date_naive();
+                    if todo.completed && completed_on <= day {
+                        done += 1;
+                    } else {
+                        open += 1;
+                    }
+                }
+                (day, open, done)
+            })
+            .collect()
+    }
+
+    /// 已完成任务的平均完成用时，可以只统计指定优先级
+    pub fn average_completion_time(&self, priority: Option<&Priority>) -> Option<Duration> {
+        average_completion(self.todos.values().filter(|t| priority.is_none_or(|p| &t.priority == p)))
+    }
+
+    /// 按优先级（从高到低）统计逾期任务数量
+    pub fn overdue_by_priority(&self) -> Vec<(Priority, usize)> {
+        [Priority::Critical, Priority::High, Priority::Medium, Priority::Low]
+            .into_iter()
+            .map(|p| {
+                let count = self.todos.values().filter(|t| t.priority == p && t.is_overdue()).count();
+                (p, count)
+            })
+            .collect()
+    }
+
+    /// 按标签统计任务（任务数从多到少），没有标签的任务归入空字符串
+    pub fn tag_stats(&self) -> Vec<TagStats> {
+        let mut groups: HashMap<String, Vec<&Todo>> = HashMap::new();
+        for todo in self.todos.values() {
+            if todo.tags.is_empty() {
+                groups.entry(String::new()).or_default().push(todo);
+            }
+            for tag in &todo.tags {
+                groups.entry(tag.clone()).or_default().push(todo);
+            }
+        }
+        
+        let mut result: Vec<TagStats> = groups.into_iter()
+            .map(|(tag, todos)| TagStats {
+                tag,
+                total: todos.len(),
+                done: todos.iter().filter(|t| t.completed).count(),
+                overdue: todos.iter().filter(|t| t.is_overdue()).count(),
+                average_completion: average_completion(todos.into_iter()),
+            })
+            .collect();
+        result.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.tag.cmp(&b.tag)));
+        result
+    }
+
+    /// 删除待办事项
+    pub fn remove_todo(&mut self, id: &str) {
+        // 移入回收站而不是直接删除
+        if let Some(mut todo) = self.todos.remove(id) {
+            todo.stop_timer();
+            self.trash.push(TrashedTodo {
+                todo,
+                deleted_at: Local::now(),
+            });
+        }
+    }
+
+    /// 取出任务用于移动到其他工作区，同时移除与剩余任务之间的阻塞关系
+    pub fn take_todos(&mut self, ids: &[String]) -> Vec<Todo> {
+        let mut taken: Vec<Todo> = ids.iter().filter_map(|id| self.todos.remove(id)).collect();
+        for todo in &mut taken {
+            todo.blocked_by.retain(|blocker| ids.contains(blocker));
+        }
+        for todo in self.todos.values_mut() {
+            todo.blocked_by.retain(|blocker| !ids.contains(blocker));
+        }
+        taken
+    }
+
+    /// 将子任务提升为独立任务，排在原任务之后，返回新任务的ID
+    pub fn promote_subtask(&mut self, todo_id: &str, subtask_id: &str) -> Option<String> {
+        let parent = self.todos.get_mut(todo_id)?;
+        let subtask = take_subtask(&mut parent.subtasks, subtask_id)?;
+        let mut todo = subtask.into_todo(parent);
+        todo.status = self.default_status_id(todo.completed);
+
+        let id = todo.id.clone();
+        self.todos.insert(id.clone(), todo);
+        self.move_todo(&id, todo_id, true);
+        Some(id)
+    }
+
+    /// 将任务降级为另一个任务的子任务，任务的子任务成为下级子任务
+    pub fn demote_todo(&mut self, todo_id: &str, target_id: &str) -> Result<(), String> {
+        if todo_id == target_id {
+            return Err("不能将任务转为自身的子任务".to_string());
+        }
+        if !self.todos.contains_key(todo_id) {
+            return Err("任务不存在".to_string());
+        }
+        if !self.todos.contains_key(target_id) {
+            return Err("目标任务不存在".to_string());
+        }
+
+        let subtask = self.take_todos(&[todo_id.to_string()]).remove(0).into_subtask();
+        if let Some(target) = self.todos.get_mut(target_id) {
+            target.subtasks.push(subtask);
+        }
+        Ok(())
+    }
+
+    /// 接收从其他工作区移动来的任务，当前工作区不存在的状态改为对应类别的默认状态
+    pub fn receive_todos(&mut self, todos: Vec<Todo>) {
+        for todo in todos {
+            self.todos.insert(todo.id.clone(), todo);
+        }
+        self.normalize_statuses();
+    }
+
+    /// 从回收站恢复任务
+    pub fn restore_from_trash(&mut self, id: &str) -> bool {
+        match self.trash.iter().position(|t| t.todo.id == id) {
+            Some(index) => {
+                let trashed = self.trash.remove(index);
+                self.todos.insert(trashed.todo.id.clone(), trashed.todo);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 从回收站永久删除任务
+    pub fn purge_from_trash(&mut self, id: &str) {
+        self.trash.retain(|t| t.todo.id != id);
+    }
+
+    /// 清空回收站
+    pub fn empty_trash(&mut self) {
+        self.trash.clear();
+    }
+
+    /// 获取回收站中任务的自动清除时间
+    pub fn trash_purge_time(&self, trashed: &TrashedTodo) -> Option<DateTime<Local>> {
+        if self.trash_retention_days == 0 {
+            None
+        } else {
+            Some(trashed.deleted_at + Duration::days(self.trash_retention_days as i64))
+        }
+    }
+
+    /// 永久删除超过保留天数的任务，返回删除的数量
+    pub fn purge_expired_trash(&mut self, now: DateTime<Local>) -> usize {
+        if self.trash_retention_days == 0 {
+            return 0;
+        }
+        let cutoff = now - Duration::days(self.trash_retention_days as i64);
+        let before = self.trash.len();
+        self.trash.retain(|t| t.deleted_at > cutoff);
+        before - self.trash.len()
+    }
+
+    /// 获取所有标签
+    pub fn all_tags(&self) -> Vec<String> {
+        let mut tags = Vec::new();
+        for todo in self.todos.values() {
+            for tag in &todo.tags {
+                if !tags.contains(tag) {
+                    tags.push(tag.clone());
+                }
+            }
+        }
+        tags.sort();
+        tags
+    }
+
+    /// 获取过滤后的待办事项列表
+    pub fn filtered_todos(&self) -> Vec<&Todo> {
+        let filter = self.current_filter();
+        let mut result: Vec<&Todo> = self.todos
+            .values()
+            .filter(|todo| self.matches_filter(todo, &filter))
+            .collect();
+        
+        // 按优先级和日期排序
+        let positions = self.manual_positions();
+        result.sort_by(|a, b| {
+            // 先按完成状态
+            let comp = a.completed.cmp(&b.completed);
+            if comp != std::cmp::Ordering::Equal {
+                return comp;
+            }
+            
+            // 手动排序时按手动顺序，未排序的任务排在最后
+            if self.manual_sort {
+                let a_pos = positions.get(a.id.as_str()).copied().unwrap_or(usize::MAX);
+                let b_pos = positions.get(b.id.as_str()).copied().unwrap_or(usize::MAX);
+                let pos_comp = a_pos.cmp(&b_pos);
+                if pos_comp != std::cmp::Ordering::Equal {
+                    return pos_comp;
+                }
+            }
+            
+            // 未完成的任务中，流程越靠后的状态排在越前面
+            if !a.completed && !self.manual_sort {
+                let status_comp = self.status_position(b).cmp(&self.status_position(a));
+                if status_comp != std::cmp::Ordering::Equal {
+                    return status_comp;
+                }
             }
             
             // 根据优先级排序设置进行排序
-            if !a.completed {
+            if !a.completed && !self.manual_sort {
                 // 如果启用了优先级排序
                 if let Some(high_to_low) = self.priority_sort {
                     let a_prio = priority_to_number(&a.priority);
@@ -351,50 +1692,95 @@ impl TodoList {
         result
     }
 
-    /// 保存到文件
-    pub fn save(&self) -> Result<(), String> {
+    /// 获取任务在手动顺序中的位置
+    fn manual_positions(&self) -> HashMap<&str, usize> {
+        self.manual_order
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (id.as_str(), i))
+            .collect()
+    }
+
+    /// 整理手动顺序：移除已删除的任务，并将未排序的任务按创建时间（从新到旧）追加到末尾
+    fn normalize_manual_order(&mut self) {
+        let todos = &self.todos;
+        self.manual_order.retain(|id| todos.contains_key(id));
+
+        let mut missing: Vec<&Todo> = self.todos
+            .values()
+            .filter(|t| !self.manual_order.contains(&t.id))
+            .collect();
+        missing.sort_by_key(|t| std::cmp::Reverse(t.created_at));
+        let missing: Vec<String> = missing.into_iter().map(|t| t.id.clone()).collect();
+        self.manual_order.extend(missing);
+    }
+
+    /// 将任务移动到目标任务之前或之后
+    pub fn move_todo(&mut self, id: &str, target_id: &str, after: bool) {
+        if id == target_id || !self.todos.contains_key(id) || !self.todos.contains_key(target_id) {
+            return;
+        }
+
+        self.normalize_manual_order();
+        self.manual_order.retain(|i| i != id);
+
+        let target_index = match self.manual_order.iter().position(|i| i == target_id) {
+            Some(index) => index,
+            None => return,
+        };
+        let insert_at = if after { target_index + 1 } else { target_index };
+        self.manual_order.insert(insert_at, id.to_string());
+    }
+
+    /// 获取数据目录中指定文件的路径
+    pub fn data_file_path(file_name: &str) -> Result<std::path::PathBuf, String> {
         let app_dirs = directories::ProjectDirs::from("com", "rodo", "Rodo")
             .ok_or_else(|| "无法确定应用程序目录".to_string())?;
         
         let data_dir = app_dirs.data_dir();
         std::fs::create_dir_all(data_dir).map_err(|e| format!("无法创建数据目录: {}", e))?;
         
-        let file_path = data_dir.join("todos.json");
-        let serialized = serde_json::to_string(self).map_err(|e| format!("序列化失败: {}", e))?;
-        
-        std::fs::write(file_path, serialized).map_err(|e| format!("写入文件失败: {}", e))?;
-        
-        Ok(())
+        Ok(data_dir.join(file_name))
     }
 
-    /// 从文件加载
-    pub fn load() -> Self {
-        let app_dirs = match directories::ProjectDirs::from("com", "rodo", "Rodo") {
-            Some(dirs) => dirs,
-            None => return Self::default(),
-        };
-        
-        let data_dir = app_dirs.data_dir();
-        let file_path = data_dir.join("todos.json");
+    /// 保存到数据目录中的指定文件，先备份原文件，再原子地写入新内容
+    pub fn save(&self, file_name: &str) -> Result<(), String> {
+        let file_path = Self::data_file_path(file_name)?;
+        let serialized = schema::TODOS.encode(self).map_err(|e| format!("序列化失败: {}", e))?;
         
+        // 备份失败不影响保存
+        if let Err(err) = storage::backup_if_due(&file_path, Local::now()) {
+            eprintln!("备份任务文件失败: {}", err);
+        }
+        storage::write_atomic(&file_path, serialized.as_bytes()).map_err(|e| format!("写入文件失败: {}", e))
+    }
+
+    /// 从数据目录中的指定文件加载，文件不存在时返回空列表，无法读取或解析时返回错误
+    pub fn load(file_name: &str) -> Result<Self, String> {
+        let file_path = Self::data_file_path(file_name)?;
         if !file_path.exists() {
-            return Self::default();
+            return Ok(Self::default());
         }
+        Self::read_file(&file_path)
+    }
+
+    /// 读取并解析任务文件，支持所有历史版本的格式
+    pub fn read_file(file_path: &std::path::Path) -> Result<Self, String> {
+        let data = std::fs::read_to_string(file_path)
+            .map_err(|e| format!("读取任务文件失败: {}", e))?;
         
-        let data = match std::fs::read_to_string(file_path) {
-            Ok(data) => data,
-            Err(_) => return Self::default(),
-        };
+        let mut list: Self = schema::TODOS.decode(&data)
+            .map_err(|e| format!("解析任务文件失败: {}", e))?;
         
-        match serde_json::from_str(&data) {
-            Ok(list) => list,
-            Err(_) => Self::default(),
-        }
+        // 清除回收站中过期的任务
+        list.purge_expired_trash(Local::now());
+        list.normalize_statuses();
+        Ok(list)
     }
 
     /// 导出待办事项列表到指定文件
     pub fn export_to_file(&self, file_path: &std::path::Path) -> Result<(), String> {
-        let serialized = serde_json::to_string_pretty(self)
+        let serialized = schema::TODOS.encode_pretty(self)
             .map_err(|e| format!("序列化失败: {}", e))?;
         
         std::fs::write(file_path, serialized)
@@ -403,24 +1789,528 @@ impl TodoList {
         Ok(())
     }
     
-    /// 从指定文件导入待办事项列表
+    /// 从指定文件导入待办事项列表，支持所有历史版本的导出文件
     pub fn import_from_file(file_path: &std::path::Path) -> Result<Self, String> {
         let data = std::fs::read_to_string(file_path)
             .map_err(|e| format!("读取文件失败: {}", e))?;
         
-        let todo_list: Self = serde_json::from_str(&data)
+        let mut todo_list: Self = schema::TODOS.decode(&data)
             .map_err(|e| format!("解析JSON失败: {}", e))?;
+        todo_list.normalize_statuses();
         
         Ok(todo_list)
     }
 }
 
 /// 将优先级转换为数字以便排序
-fn priority_to_number(priority: &Priority) -> u8 {
+pub fn priority_to_number(priority: &Priority) -> u8 {
     match priority {
         Priority::Low => 0,
         Priority::Medium => 1,
         Priority::High => 2,
         Priority::Critical => 3,
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reminder::{Reminder, ReminderKind};
+    use chrono::{FixedOffset, LocalResult};
+    
+    fn at(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(year, month, day, hour, 0, 0).unwrap()
+    }
+    
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+    
+    /// 测试用的夏令时时区：平时为 UTC+1，2024-03-31 02:00 起为 UTC+2，2024-10-27 03:00 起恢复 UTC+1
+    #[derive(Clone, Copy, Debug)]
+    struct DstZone;
+    
+    impl DstZone {
+        fn winter() -> FixedOffset {
+            FixedOffset::east_opt(3600).unwrap()
+        }
+        
+        fn summer() -> FixedOffset {
+            FixedOffset::east_opt(7200).unwrap()
+        }
+        
+        fn at(month: u32, day: u32, hour: u32, minute: u32) -> DateTime<DstZone> {
+            DstZone.from_local_datetime(&date(2024, month, day).and_hms_opt(hour, minute, 0).unwrap()).earliest().unwrap()
+        }
+    }
+    
+    impl TimeZone for DstZone {
+        type Offset = FixedOffset;
+        
+        fn from_offset(_: &FixedOffset) -> Self {
+            DstZone
+        }
+        
+        fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+            self.offset_from_local_datetime(&local.and_hms_opt(12, 0, 0).unwrap())
+        }
+        
+        fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+            let spring = date(2024, 3, 31).and_hms_opt(2, 0, 0).unwrap();
+            let autumn = date(2024, 10, 27).and_hms_opt(2, 0, 0).unwrap();
+            let hour = Duration::hours(1);
+            if *local >= spring && *local < spring + hour {
+                LocalResult::None
+            } else if *local >= autumn && *local < autumn + hour {
+                LocalResult::Ambiguous(Self::summer(), Self::winter())
+            } else if *local >= spring + hour && *local < autumn {
+                LocalResult::Single(Self::summer())
+            } else {
+                LocalResult::Single(Self::winter())
+            }
+        }
+        
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+            self.offset_from_utc_datetime(&utc.and_hms_opt(12, 0, 0).unwrap())
+        }
+        
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+            let spring = date(2024, 3, 31).and_hms_opt(1, 0, 0).unwrap();
+            let autumn = date(2024, 10, 27).and_hms_opt(1, 0, 0).unwrap();
+            if *utc >= spring && *utc < autumn {
+                Self::summer()
+            } else {
+                Self::winter()
+            }
+        }
+    }
+    
+    /// 截止时间为指定日期9点、当天10点完成时的下一次截止日期
+    fn next_date(recurrence: &Recurrence, due: NaiveDate) -> NaiveDate {
+        let completed_at = Local.from_local_datetime(&due.and_hms_opt(10, 0, 0).unwrap()).unwrap();
+        let due = Local.from_local_datetime(&due.and_hms_opt(9, 0, 0).unwrap()).unwrap();
+        let next = recurrence.next_due(Some(due), completed_at).unwrap();
+        assert_eq!(next.time(), due.time());
+        next.date_naive()
+    }
+    
+    #[test]
+    fn daily_skips_missed_days() {
+        assert_eq!(next_date(&Recurrence::Daily, date(2024, 5, 15)), date(2024, 5, 16));
+        assert_eq!(next_date(&Recurrence::Daily, date(2024, 12, 31)), date(2025, 1, 1));
+        
+        // 晚了几天才完成：跳过错过的日子，下一次截止时间晚于完成时间
+        let next = Recurrence::Daily.next_due(Some(at(2024, 5, 15, 9)), at(2024, 5, 18, 12)).unwrap();
+        assert_eq!(next, at(2024, 5, 19, 9));
+        
+        // 没有截止时间时从完成时间开始计算
+        let next = Recurrence::Daily.next_due(None, at(2024, 5, 15, 20)).unwrap();
+        assert_eq!(next, at(2024, 5, 16, 20));
+    }
+    
+    #[test]
+    fn weekly_on_several_days() {
+        let recurrence = Recurrence::Weekly(vec![Weekday::Mon, Weekday::Wed, Weekday::Fri]);
+        // 2024-05-15 是星期三
+        assert_eq!(next_date(&recurrence, date(2024, 5, 15)), date(2024, 5, 17));
+        assert_eq!(next_date(&recurrence, date(2024, 5, 17)), date(2024, 5, 20));
+        assert_eq!(next_date(&recurrence, date(2024, 5, 20)), date(2024, 5, 22));
+        // 截止日期不在规则中时取之后的第一个规则日期
+        assert_eq!(next_date(&recurrence, date(2024, 5, 18)), date(2024, 5, 20));
+        
+        assert_eq!(next_date(&Recurrence::Weekly(vec![Weekday::Wed]), date(2024, 5, 15)), date(2024, 5, 22));
+        assert_eq!(next_date(&Recurrence::Weekly(Vec::new()), date(2024, 5, 15)), date(2024, 5, 22));
+    }
+    
+    #[test]
+    fn monthly_on_day_clamps_to_month_end() {
+        let recurrence = Recurrence::MonthlyOnDay(31);
+        assert_eq!(next_date(&recurrence, date(2024, 1, 31)), date(2024, 2, 29));
+        // 二月之后恢复到31日
+        assert_eq!(next_date(&recurrence, date(2024, 2, 29)), date(2024, 3, 31));
+        assert_eq!(next_date(&recurrence, date(2024, 3, 31)), date(2024, 4, 30));
+        assert_eq!(next_date(&recurrence, date(2023, 1, 31)), date(2023, 2, 28));
+        assert_eq!(next_date(&recurrence, date(2024, 12, 31)), date(2025, 1, 31));
+        
+        assert_eq!(next_date(&Recurrence::MonthlyOnDay(30), date(2024, 1, 30)), date(2024, 2, 29));
+        assert_eq!(next_date(&Recurrence::MonthlyOnDay(15), date(2024, 5, 15)), date(2024, 6, 15));
+        // 截止日期在规则日期之前时取本月的规则日期
+        assert_eq!(next_date(&Recurrence::MonthlyOnDay(20), date(2024, 5, 15)), date(2024, 5, 20));
+    }
+    
+    #[test]
+    fn monthly_on_weekday() {
+        let last_friday = Recurrence::MonthlyOnWeekday { ordinal: -1, weekday: Weekday::Fri };
+        assert_eq!(next_date(&last_friday, date(2024, 5, 15)), date(2024, 5, 31));
+        assert_eq!(next_date(&last_friday, date(2024, 5, 31)), date(2024, 6, 28));
+        assert_eq!(next_date(&last_friday, date(2024, 2, 1)), date(2024, 2, 23));
+        
+        let second_tuesday = Recurrence::MonthlyOnWeekday { ordinal: 2, weekday: Weekday::Tue };
+        assert_eq!(next_date(&second_tuesday, date(2024, 5, 15)), date(2024, 6, 11));
+        assert_eq!(next_date(&second_tuesday, date(2024, 12, 10)), date(2025, 1, 14));
+        
+        // 第5个星期一不是每月都有，跳过没有的月份
+        let fifth_monday = Recurrence::MonthlyOnWeekday { ordinal: 5, weekday: Weekday::Mon };
+        assert_eq!(next_date(&fifth_monday, date(2024, 4, 29)), date(2024, 7, 29));
+    }
+    
+    #[test]
+    fn after_completion_counts_from_completion() {
+        let recurrence = Recurrence::AfterCompletion(3);
+        // 使用原截止时间的时分，从完成日期开始计算
+        let next = recurrence.next_due(Some(at(2024, 5, 10, 9)), at(2024, 5, 15, 20)).unwrap();
+        assert_eq!(next, at(2024, 5, 18, 9));
+        
+        let next = recurrence.next_due(None, at(2024, 5, 15, 20)).unwrap();
+        assert_eq!(next, at(2024, 5, 18, 20));
+        
+        // 间隔至少为一天
+        let next = Recurrence::AfterCompletion(0).next_due(None, at(2024, 5, 15, 20)).unwrap();
+        assert_eq!(next, at(2024, 5, 16, 20));
+    }
+    
+    #[test]
+    fn ambiguous_local_time_uses_earlier_offset() {
+        // 2024-10-27 02:30 出现两次，取较早的夏令时时间
+        let next = Recurrence::Daily.next_due(Some(DstZone::at(10, 26, 2, 30)), DstZone::at(10, 26, 9, 0)).unwrap();
+        assert_eq!(next.naive_local(), date(2024, 10, 27).and_hms_opt(2, 30, 0).unwrap());
+        assert_eq!(*next.offset(), DstZone::summer());
+    }
+    
+    #[test]
+    fn skipped_local_time_moves_one_hour_later() {
+        // 2024-03-31 02:30 不存在，顺延到 03:30，而不是跳过这一天
+        let next = Recurrence::Daily.next_due(Some(DstZone::at(3, 30, 2, 30)), DstZone::at(3, 30, 9, 0)).unwrap();
+        assert_eq!(next.naive_local(), date(2024, 3, 31).and_hms_opt(3, 30, 0).unwrap());
+        
+        let next = Recurrence::AfterCompletion(1).next_due(Some(DstZone::at(3, 30, 2, 30)), DstZone::at(3, 30, 9, 0)).unwrap();
+        assert_eq!(next.naive_local(), date(2024, 3, 31).and_hms_opt(3, 30, 0).unwrap());
+        
+        // 时分取自上一次截止时间，之后的日期沿用顺延后的时间
+        let next = Recurrence::Daily.next_due(Some(next), DstZone::at(3, 31, 9, 0)).unwrap();
+        assert_eq!(next.naive_local(), date(2024, 4, 1).and_hms_opt(3, 30, 0).unwrap());
+    }
+    
+    #[test]
+    fn completing_again_after_reopen_does_not_spawn_twice() {
+        let mut list = TodoList::default();
+        list.normalize_statuses();
+        
+        let mut todo = Todo::new("浇花".to_string());
+        todo.due_date = Some(Local::now() - Duration::hours(1));
+        todo.recurrence = Some(Recurrence::Daily);
+        let id = todo.id.clone();
+        list.add_todo(todo);
+        
+        list.set_todo_status(&id, "done");
+        assert_eq!(list.todos.len(), 2);
+        let next = list.todos.values().find(|t| t.id != id).unwrap();
+        assert_eq!(next.recurrence, Some(Recurrence::Daily));
+        assert_eq!(next.series_id.as_deref(), Some(id.as_str()));
+        assert!(!next.completed);
+        assert!(next.due_date > list.todos[&id].due_date);
+        assert_eq!(list.todos[&id].recurrence, None);
+        
+        list.set_todo_status(&id, "todo");
+        assert!(!list.todos[&id].completed);
+        list.set_todo_status(&id, "done");
+        assert_eq!(list.todos.len(), 2);
+    }
+    
+    #[test]
+    fn demote_keeps_title_status_and_subtasks_only() {
+        let mut list = TodoList::default();
+        let target = Todo::new("发布".to_string());
+        let blocker = Todo::new("评审".to_string());
+        
+        let mut todo = Todo::new("写文档".to_string());
+        todo.completed = true;
+        todo.description = "包括安装说明".to_string();
+        todo.tags = vec!["文档".to_string()];
+        todo.due_date = Some(at(2024, 5, 20, 18));
+        todo.recurrence = Some(Recurrence::Daily);
+        todo.reminders = vec![Reminder::new(ReminderKind::BeforeDue(30))];
+        todo.blocked_by = vec![blocker.id.clone()];
+        todo.estimate_minutes = Some(60);
+        todo.defer_until = Some(at(2024, 5, 18, 9));
+        todo.time_entries = vec![TimeEntry::new(at(2024, 5, 15, 9), Some(at(2024, 5, 15, 10)))];
+        todo.subtasks = vec![SubTask::new("API".to_string())];
+        
+        assert_eq!(
+            todo.demotion_losses(),
+            vec!["描述", "标签", "截止时间", "重复规则", "提醒", "前置任务", "预计用时", "推迟时间", "时间记录"]
+        );
+        
+        let (todo_id, target_id) = (todo.id.clone(), target.id.clone());
+        list.add_todo(target);
+        list.add_todo(blocker);
+        list.add_todo(todo);
+        list.demote_todo(&todo_id, &target_id).unwrap();
+        
+        assert!(!list.todos.contains_key(&todo_id));
+        let subtask = &list.todos[&target_id].subtasks[0];
+        assert_eq!(subtask.title, "写文档");
+        assert!(subtask.completed);
+        assert_eq!(subtask.children.len(), 1);
+        assert_eq!(subtask.children[0].title, "API");
+        
+        assert_eq!(list.demote_todo(&target_id, &target_id), Err("不能将任务转为自身的子任务".to_string()));
+        assert_eq!(list.demote_todo(&todo_id, &target_id), Err("任务不存在".to_string()));
+    }
+    
+    #[test]
+    fn demotion_losses_of_plain_and_timed_todos() {
+        let mut todo = Todo::new("整理桌面".to_string());
+        todo.subtasks = vec![SubTask::new("擦桌子".to_string())];
+        assert!(todo.demotion_losses().is_empty());
+        
+        todo.start_timer();
+        assert_eq!(todo.demotion_losses(), vec!["时间记录（包括正在进行的计时）"]);
+    }
+    
+    #[test]
+    fn completed_todos_are_never_overdue() {
+        let mut todo = Todo::new("交周报".to_string());
+        todo.due_date = Some(Local::now() - Duration::days(7));
+        assert!(todo.is_overdue());
+        assert_eq!(todo.due_bucket(), DueBucket::Overdue);
+        
+        todo.completed = true;
+        assert!(!todo.is_overdue());
+        assert_eq!(todo.due_bucket(), DueBucket::Completed);
+        
+        // 已完成的分组排在所有未完成的分组之后
+        assert!(DueBucket::Completed > DueBucket::NoDate);
+        assert!(DueBucket::Completed > DueBucket::Overdue);
+    }
+    
+    /// 包含按创建时间先后排列的任务的列表，返回列表和任务ID
+    fn ordered_list(titles: &[&str]) -> (TodoList, Vec<String>) {
+        let mut list = TodoList::default();
+        let mut ids = Vec::new();
+        for (i, title) in titles.iter().enumerate() {
+            let mut todo = Todo::new(title.to_string());
+            todo.created_at = at(2024, 5, 1, 8 + i as u32);
+            ids.push(todo.id.clone());
+            list.add_todo(todo);
+        }
+        (list, ids)
+    }
+    
+    #[test]
+    fn move_todo_before_and_after_target() {
+        let (mut list, ids) = ordered_list(&["a", "b", "c"]);
+        list.manual_order = ids.clone();
+        
+        list.move_todo(&ids[0], &ids[2], true);
+        assert_eq!(list.manual_order, vec![ids[1].clone(), ids[2].clone(), ids[0].clone()]);
+        
+        list.move_todo(&ids[0], &ids[1], false);
+        assert_eq!(list.manual_order, ids);
+        
+        list.move_todo(&ids[2], &ids[0], false);
+        assert_eq!(list.manual_order, vec![ids[2].clone(), ids[0].clone(), ids[1].clone()]);
+    }
+    
+    #[test]
+    fn move_todo_ignores_self_and_unknown_ids() {
+        let (mut list, ids) = ordered_list(&["a", "b"]);
+        list.manual_order = ids.clone();
+        
+        list.move_todo(&ids[0], &ids[0], true);
+        list.move_todo("missing", &ids[0], false);
+        list.move_todo(&ids[0], "missing", true);
+        assert_eq!(list.manual_order, ids);
+    }
+    
+    #[test]
+    fn move_todo_orders_unsorted_todos_newest_first() {
+        let (mut list, ids) = ordered_list(&["a", "b", "c", "d"]);
+        // 手动顺序中只有 b 和一个已删除的任务，其余任务按创建时间从新到旧排在后面
+        list.manual_order = vec![ids[1].clone(), "deleted".to_string()];
+        
+        list.move_todo(&ids[0], &ids[1], false);
+        assert_eq!(list.manual_order, vec![ids[0].clone(), ids[1].clone(), ids[3].clone(), ids[2].clone()]);
+    }
+    
+    #[test]
+    fn normalize_manual_order_drops_deleted_and_appends_missing() {
+        let (mut list, ids) = ordered_list(&["a", "b", "c"]);
+        list.manual_order = vec!["deleted".to_string(), ids[1].clone()];
+        
+        list.normalize_manual_order();
+        assert_eq!(list.manual_order, vec![ids[1].clone(), ids[2].clone(), ids[0].clone()]);
+        
+        // 已整理的顺序保持不变
+        list.normalize_manual_order();
+        assert_eq!(list.manual_order, vec![ids[1].clone(), ids[2].clone(), ids[0].clone()]);
+    }
+    
+    /// 包含每个默认状态各一个任务的列表，返回列表和任务ID（待办、进行中、已完成）
+    fn list_in_statuses() -> (TodoList, [String; 3]) {
+        let mut list = TodoList::default();
+        let ids = ["todo", "in_progress", "done"].map(|status| {
+            let mut todo = Todo::new(status.to_string());
+            todo.status = status.to_string();
+            todo.set_completed(status == "done");
+            let id = todo.id.clone();
+            list.add_todo(todo);
+            id
+        });
+        (list, ids)
+    }
+    
+    #[test]
+    fn remove_status_moves_todos_to_default_status() {
+        let (mut list, [_, in_progress, done]) = list_in_statuses();
+        list.add_status("已取消", true).unwrap();
+        let cancelled = list.statuses.last().unwrap().id.clone();
+        list.set_todo_status(&done, &cancelled);
+        list.status_filter = Some("in_progress".to_string());
+        
+        list.remove_status("in_progress").unwrap();
+        assert!(list.status("in_progress").is_none());
+        assert_eq!(list.todos[&in_progress].status, "todo");
+        assert!(!list.todos[&in_progress].completed);
+        assert_eq!(list.status_filter, None);
+        
+        list.remove_status(&cancelled).unwrap();
+        assert_eq!(list.todos[&done].status, "done");
+        assert!(list.todos[&done].completed);
+        
+        assert_eq!(list.remove_status("missing"), Err("状态不存在".to_string()));
+    }
+    
+    #[test]
+    fn last_status_of_each_kind_is_kept() {
+        let (mut list, _) = list_in_statuses();
+        list.remove_status("in_progress").unwrap();
+        
+        assert_eq!(list.remove_status("todo"), Err("至少需要保留一个非终止状态".to_string()));
+        assert_eq!(list.remove_status("done"), Err("至少需要保留一个终止状态".to_string()));
+        assert_eq!(list.set_status_terminal("todo", true), Err("至少需要保留一个非终止状态".to_string()));
+        assert_eq!(list.set_status_terminal("done", false), Err("至少需要保留一个终止状态".to_string()));
+        assert_eq!(list.statuses.len(), 2);
+        assert!(!list.status("todo").unwrap().terminal);
+        assert!(list.status("done").unwrap().terminal);
+    }
+    
+    #[test]
+    fn terminal_toggle_syncs_completion_without_spawning() {
+        let (mut list, [todo, in_progress, done]) = list_in_statuses();
+        list.todos.get_mut(&in_progress).unwrap().recurrence = Some(Recurrence::Daily);
+        
+        list.set_status_terminal("in_progress", true).unwrap();
+        assert_eq!(list.todos.len(), 3);
+        let toggled = &list.todos[&in_progress];
+        assert!(toggled.completed);
+        assert!(toggled.completed_at.is_some());
+        assert_eq!(toggled.recurrence, Some(Recurrence::Daily));
+        assert_eq!(toggled.status, "in_progress");
+        // 其他状态的任务不受影响
+        assert!(!list.todos[&todo].completed);
+        assert!(list.todos[&done].completed);
+        
+        list.set_status_terminal("in_progress", false).unwrap();
+        assert_eq!(list.todos.len(), 3);
+        let toggled = &list.todos[&in_progress];
+        assert!(!toggled.completed);
+        assert_eq!(toggled.completed_at, None);
+        assert_eq!(toggled.recurrence, Some(Recurrence::Daily));
+        
+        // 设置为当前类别时不做任何修改
+        list.set_status_terminal("todo", false).unwrap();
+        assert!(!list.status("todo").unwrap().terminal);
+    }
+    
+    /// 按标题依次创建任务，返回任务ID
+    fn list_with(titles: &[&str]) -> (TodoList, Vec<String>) {
+        let mut list = TodoList::default();
+        let ids = titles.iter().map(|title| {
+            let todo = Todo::new(title.to_string());
+            let id = todo.id.clone();
+            list.add_todo(todo);
+            id
+        }).collect();
+        (list, ids)
+    }
+    
+    #[test]
+    fn would_create_cycle_detects_direct_transitive_and_self_cycles() {
+        let (mut list, ids) = list_with(&["a", "b", "c", "d"]);
+        let (a, b, c, d) = (&ids[0], &ids[1], &ids[2], &ids[3]);
+        // a 被 b 阻塞，b 被 c 阻塞
+        list.todos.get_mut(a).unwrap().blocked_by.push(b.clone());
+        list.todos.get_mut(b).unwrap().blocked_by.push(c.clone());
+        
+        assert!(list.would_create_cycle(a, a));
+        assert!(list.would_create_cycle(b, a));
+        assert!(list.would_create_cycle(c, a));
+        assert!(list.would_create_cycle(c, b));
+        assert!(!list.would_create_cycle(a, c));
+        assert!(!list.would_create_cycle(a, d));
+        assert!(!list.would_create_cycle(d, a));
+    }
+    
+    #[test]
+    fn open_blockers_skip_completed_and_trashed_blockers() {
+        let (mut list, ids) = list_with(&["a", "b", "c", "d"]);
+        let (a, b, c, d) = (&ids[0], &ids[1], &ids[2], &ids[3]);
+        list.todos.get_mut(a).unwrap().blocked_by = vec![b.clone(), c.clone(), d.clone()];
+        list.todos.get_mut(c).unwrap().set_completed(true);
+        list.remove_todo(d);
+        
+        let blockers: Vec<&str> = list.open_blockers(&list.todos[a]).iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(blockers, vec!["b"]);
+        assert!(list.is_blocked(&list.todos[a]));
+        
+        list.todos.get_mut(b).unwrap().set_completed(true);
+        assert!(!list.is_blocked(&list.todos[a]));
+        
+        // 从回收站恢复后重新阻塞
+        assert!(list.restore_from_trash(d));
+        let blockers: Vec<&str> = list.open_blockers(&list.todos[a]).iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(blockers, vec!["d"]);
+    }
+    
+    #[test]
+    fn purge_expired_trash_respects_retention_days() {
+        let (mut list, ids) = list_with(&["old", "recent", "kept"]);
+        for id in &ids {
+            list.remove_todo(id);
+        }
+        list.trash[0].deleted_at = at(2024, 5, 1, 9);
+        list.trash[1].deleted_at = at(2024, 5, 10, 9);
+        list.trash[2].deleted_at = at(2024, 5, 14, 9);
+        
+        list.trash_retention_days = 0;
+        assert_eq!(list.purge_expired_trash(at(2024, 5, 15, 10)), 0);
+        assert_eq!(list.trash.len(), 3);
+        
+        list.trash_retention_days = 5;
+        assert_eq!(list.trash_purge_time(&list.trash[1]), Some(at(2024, 5, 15, 9)));
+        assert_eq!(list.purge_expired_trash(at(2024, 5, 15, 10)), 2);
+        let remaining: Vec<&str> = list.trash.iter().map(|t| t.todo.title.as_str()).collect();
+        assert_eq!(remaining, vec!["kept"]);
+        assert_eq!(list.purge_expired_trash(at(2024, 5, 15, 10)), 0);
+    }
+    
+    #[test]
+    fn restore_from_trash_returns_todo_to_list() {
+        let (mut list, ids) = list_with(&["a", "b"]);
+        list.todos.get_mut(&ids[0]).unwrap().start_timer();
+        list.remove_todo(&ids[0]);
+        list.remove_todo(&ids[1]);
+        assert!(list.todos.is_empty());
+        
+        assert!(list.restore_from_trash(&ids[0]));
+        assert!(list.todos.contains_key(&ids[0]));
+        // 删除时已停止计时
+        assert!(list.todos[&ids[0]].running_entry().is_none());
+        assert_eq!(list.trash.len(), 1);
+        assert_eq!(list.trash[0].todo.id, ids[1]);
+        
+        assert!(!list.restore_from_trash(&ids[0]));
+        assert!(!list.restore_from_trash("missing"));
+        assert_eq!(list.todos.len(), 1);
+    }
+}
@@ -1,10 +1,14 @@
-use crate::app::{ConfirmationAction, RodoApp, View};
+use crate::app::{BoardGrouping, CalendarMode, ConfirmationAction, RodoApp, Toast, ToastAction, View};
+use crate::reminder::{Reminder, ReminderKind};
 use crate::theme::Theme;
-use crate::todo::{Emoji, Priority, SubTask, Todo};
+use crate::todo::{find_subtask_mut, format_duration, weekday_name, DueBucket, Emoji, Priority, Recurrence, Snooze, Status, SubTask, TagMatch, TimeEntry, Todo, TodoList, TrashedTodo};
 use crate::markdown;
+use crate::query;
+use crate::quick_add;
+use crate::template::PLACEHOLDERS;
 use crate::globals::WINDOW_VISIBLE;
 use egui::{Button, Color32, Layout, RichText, ScrollArea, Ui, Vec2};
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, TimeZone, Timelike, Weekday};
 use uuid::Uuid;
 use rfd::FileDialog;
 use std::sync::atomic::Ordering;
@@ -26,6 +30,771 @@ fn truncate_string(s: &str, max_chars: usize) -> String {
     result
 }
 
+/// 获取优先级对应的颜色
+fn priority_color(priority: &Priority) -> Color32 {
+    match priority {
+        Priority::Low => egui::Color32::from_rgb(76, 175, 80),      // 绿色
+        Priority::Medium => egui::Color32::from_rgb(255, 193, 7),    // 黄色
+        Priority::High => egui::Color32::from_rgb(255, 87, 34),      // 橙色
+        Priority::Critical => egui::Color32::from_rgb(244, 67, 54),  // 红色
+    }
+}
+
+/// 看板按优先级分组时的列，按优先级从高到低排列
+fn board_priorities() -> [(Priority, &'static str, &'static str); 4] {
+    [
+        (Priority::Critical, "Critical", "紧急"),
+        (Priority::High, "High", "高"),
+        (Priority::Medium, "Medium", "中"),
+        (Priority::Low, "Low", "低"),
+    ]
+}
+
+/// 根据年月日时分构造本地时间，日期超出当月天数时自动取当月最后一天
+fn local_date_time(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> Option<DateTime<Local>> {
+    let mut day = day;
+    let date = loop {
+        if let Some(date) = NaiveDate::from_ymd_opt(year, month, day) {
+            break date;
+        }
+        if day <= 28 {
+            return None;
+        }
+        day -= 1;
+    };
+    
+    let naive = date.and_hms_opt(hour, minute, 0)?;
+    Local.from_local_datetime(&naive).earliest()
+}
+
+/// 年月日时分输入框，返回值表示是否发生了修改
+fn date_time_fields(ui: &mut Ui, date_time: &mut DateTime<Local>) -> bool {
+    let mut year = date_time.year();
+    let mut month = date_time.month();
+    let mut day = date_time.day();
+    let mut hour = date_time.hour();
+    let mut minute = date_time.minute();
+    
+    let mut edited = false;
+    edited |= ui.add(egui::DragValue::new(&mut year).clamp_range(2000..=2100).suffix("年")).changed();
+    edited |= ui.add(egui::DragValue::new(&mut month).clamp_range(1..=12).suffix("月")).changed();
+    edited |= ui.add(egui::DragValue::new(&mut day).clamp_range(1..=31).suffix("日")).changed();
+    ui.add_space(8.0);
+    edited |= ui.add(egui::DragValue::new(&mut hour).clamp_range(0..=23).suffix("时")).changed();
+    edited |= ui.add(egui::DragValue::new(&mut minute).clamp_range(0..=59).suffix("分")).changed();
+    
+    if edited {
+        if let Some(new_date_time) = local_date_time(year, month, day, hour, minute) {
+            *date_time = new_date_time;
+            return true;
+        }
+    }
+    
+    false
+}
+
+/// 时间记录编辑器，返回值表示是否发生了修改
+fn time_entries_editor(ui: &mut Ui, entries: &mut Vec<TimeEntry>) -> bool {
+    let mut changed = false;
+    let mut index_to_remove = None;
+    
+    for (i, entry) in entries.iter_mut().enumerate() {
+        let entry_id = entry.id.clone();
+        ui.push_id(entry_id, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("开始");
+                changed |= date_time_fields(ui, &mut entry.start);
+            });
+            ui.horizontal(|ui| {
+                ui.label("结束");
+                match &mut entry.end {
+                    Some(end) => {
+                        changed |= date_time_fields(ui, end);
+                    },
+                    None => {
+                        ui.label(RichText::new("计时中…").italics());
+                    },
+                }
+                
+                ui.label(format!("时长: {}", format_duration(entry.duration())));
+                
+                if ui.small_button("❌").clicked() {
+                    index_to_remove = Some(i);
+                }
+            });
+            
+            // 结束时间不能早于开始时间
+            if let Some(end) = entry.end {
+                if end < entry.start {
+                    ui.label(RichText::new("⚠ 结束时间早于开始时间").small());
+                }
+            }
+        });
+        ui.separator();
+    }
+    
+    if let Some(index) = index_to_remove {
+        entries.remove(index);
+        changed = true;
+    }
+    
+    // 手动补录一小时的记录
+    if ui.button("补录时间").clicked() {
+        let now = Local::now();
+        entries.push(TimeEntry::new(now - Duration::hours(1), Some(now)));
+        changed = true;
+    }
+    
+    changed
+}
+
+/// 截止时间编辑器，返回值表示是否发生了修改
+fn due_date_editor(ui: &mut Ui, due_date: &mut Option<DateTime<Local>>) -> bool {
+    let mut changed = false;
+    
+    ui.horizontal(|ui| {
+        let mut has_due = due_date.is_some();
+        if ui.checkbox(&mut has_due, "截止时间").changed() {
+            *due_date = if has_due {
+                let today = Local::now().date_naive();
+                local_date_time(today.year(), today.month(), today.day(), 18, 0)
+            } else {
+                None
+            };
+            changed = true;
+        }
+        
+        if let Some(due) = due_date {
+            changed |= date_time_fields(ui, due);
+        }
+    });
+    
+    // 快捷选项，保留已设置的时分
+    ui.horizontal(|ui| {
+        let (hour, minute) = due_date.map(|d| (d.hour(), d.minute())).unwrap_or((18, 0));
+        let today = Local::now().date_naive();
+        let next_monday = today + Duration::days(7 - today.weekday().num_days_from_monday() as i64);
+        
+        let shortcuts = [
+            ("今天", today),
+            ("明天", today + Duration::days(1)),
+            ("下周一", next_monday),
+        ];
+        
+        for (label, date) in shortcuts {
+            if ui.small_button(label).clicked() {
+                if let Some(new_due) = local_date_time(date.year(), date.month(), date.day(), hour, minute) {
+                    *due_date = Some(new_due);
+                    changed = true;
+                }
+            }
+        }
+        
+        if due_date.is_some() && ui.small_button("清除").clicked() {
+            *due_date = None;
+            changed = true;
+        }
+    });
+    
+    changed
+}
+
+/// 推迟时间编辑器，返回值表示是否发生了修改
+fn defer_editor(ui: &mut Ui, defer_until: &mut Option<DateTime<Local>>) -> bool {
+    let mut changed = false;
+    
+    ui.horizontal(|ui| {
+        let mut deferred = defer_until.is_some();
+        if ui.checkbox(&mut deferred, "推迟到").on_hover_text("到达此时间之前不在列表中显示").changed() {
+            *defer_until = if deferred { Snooze::Tomorrow.until(Local::now()) } else { None };
+            changed = true;
+        }
+        
+        if let Some(until) = defer_until {
+            changed |= date_time_fields(ui, until);
+        }
+    });
+    
+    ui.horizontal(|ui| {
+        for snooze in Snooze::ALL {
+            if ui.small_button(snooze.as_str()).clicked() {
+                *defer_until = snooze.until(Local::now());
+                changed = true;
+            }
+        }
+        
+        if defer_until.is_some() && ui.small_button("取消推迟").clicked() {
+            *defer_until = None;
+            changed = true;
+        }
+    });
+    
+    changed
+}
+
+/// 推迟菜单的内容，返回选择的推迟时间，`Some(None)` 表示取消推迟
+///
+/// `pick` 保存自选的推迟时间，在多帧之间保留输入。
+fn snooze_menu(ui: &mut Ui, deferred: bool, pick: &mut DateTime<Local>) -> Option<Option<DateTime<Local>>> {
+    let mut choice = None;
+    let now = Local::now();
+    for snooze in Snooze::ALL {
+        if ui.button(snooze.as_str()).clicked() {
+            choice = Some(snooze.until(now));
+            ui.close_menu();
+        }
+    }
+    
+    ui.separator();
+    ui.horizontal(|ui| {
+        date_time_fields(ui, pick);
+    });
+    if ui.add_enabled(*pick > now, Button::new("推迟到此时间")).clicked() {
+        choice = Some(Some(*pick));
+        ui.close_menu();
+    }
+    
+    if deferred && ui.button("取消推迟").clicked() {
+        choice = Some(None);
+        ui.close_menu();
+    }
+    choice
+}
+
+/// 预计用时编辑器，返回值表示是否发生了修改
+fn estimate_editor(ui: &mut Ui, estimate_minutes: &mut Option<i64>) -> bool {
+    let mut changed = false;
+    
+    ui.horizontal(|ui| {
+        let mut has_estimate = estimate_minutes.is_some();
+        if ui.checkbox(&mut has_estimate, "预计用时").changed() {
+            *estimate_minutes = if has_estimate { Some(30) } else { None };
+            changed = true;
+        }
+        
+        if let Some(minutes) = estimate_minutes {
+            changed |= ui.add(egui::DragValue::new(minutes).clamp_range(1..=10000).suffix(" 分钟")).changed();
+        }
+    });
+    
+    changed
+}
+
+/// 重复规则编辑器，返回值表示是否发生了修改
+fn recurrence_editor(ui: &mut Ui, recurrence: &mut Option<Recurrence>) -> bool {
+    let mut changed = false;
+    
+    // 规则类型下标，与下方名称列表一一对应
+    let kind_names = ["不重复", "每天", "每周", "每月第N日", "每月第N个星期X", "完成后每N天"];
+    let kind = match recurrence {
+        None => 0,
+        Some(Recurrence::Daily) => 1,
+        Some(Recurrence::Weekly(_)) => 2,
+        Some(Recurrence::MonthlyOnDay(_)) => 3,
+        Some(Recurrence::MonthlyOnWeekday { .. }) => 4,
+        Some(Recurrence::AfterCompletion(_)) => 5,
+    };
+    
+    ui.horizontal(|ui| {
+        ui.label("重复:");
+        
+        let mut new_kind = kind;
+        egui::ComboBox::from_id_source("recurrence_kind")
+            .selected_text(kind_names[kind])
+            .show_ui(ui, |ui| {
+                for (i, name) in kind_names.iter().enumerate() {
+                    ui.selectable_value(&mut new_kind, i, *name);
+                }
+            });
+        
+        // 切换规则类型时以今天为参考生成默认参数
+        if new_kind != kind {
+            let today = Local::now().date_naive();
+            *recurrence = match new_kind {
+                1 => Some(Recurrence::Daily),
+                2 => Some(Recurrence::Weekly(vec![today.weekday()])),
+                3 => Some(Recurrence::MonthlyOnDay(today.day())),
+                4 => Some(Recurrence::MonthlyOnWeekday { ordinal: -1, weekday: today.weekday() }),
+                5 => Some(Recurrence::AfterCompletion(7)),
+                _ => None,
+            };
+            changed = true;
+        }
+        
+        let weekdays = [
+            Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu,
+            Weekday::Fri, Weekday::Sat, Weekday::Sun,
+        ];
+        
+        // 规则参数
+        match recurrence {
+            Some(Recurrence::Weekly(days)) => {
+                for weekday in weekdays {
+                    let mut selected = days.contains(&weekday);
+                    if ui.toggle_value(&mut selected, weekday_name(&weekday)).changed() {
+                        if selected {
+                            days.push(weekday);
+                            days.sort_by_key(|d| d.num_days_from_monday());
+                        } else {
+                            days.retain(|d| *d != weekday);
+                        }
+                        changed = true;
+                    }
+                }
+            }
+            Some(Recurrence::MonthlyOnDay(day)) => {
+                changed |= ui.add(egui::DragValue::new(day).clamp_range(1..=31).suffix("日")).changed();
+            }
+            Some(Recurrence::MonthlyOnWeekday { ordinal, weekday }) => {
+                let ordinal_text = |o: i8| if o < 0 { "最后一个".to_string() } else { format!("第{}个", o) };
+                egui::ComboBox::from_id_source("recurrence_ordinal")
+                    .selected_text(ordinal_text(*ordinal))
+                    .show_ui(ui, |ui| {
+                        for o in [1, 2, 3, 4, -1] {
+                            changed |= ui.selectable_value(ordinal, o, ordinal_text(o)).changed();
+                        }
+                    });
+                egui::ComboBox::from_id_source("recurrence_weekday")
+                    .selected_text(format!("周{}", weekday_name(weekday)))
+                    .show_ui(ui, |ui| {
+                        for w in weekdays {
+                            changed |= ui.selectable_value(weekday, w, format!("周{}", weekday_name(&w))).changed();
+                        }
+                    });
+            }
+            Some(Recurrence::AfterCompletion(days)) => {
+                changed |= ui.add(egui::DragValue::new(days).clamp_range(1..=365).prefix("每").suffix("天")).changed();
+            }
+            _ => {}
+        }
+    });
+    
+    changed
+}
+
+/// 工作流状态选择器，返回值表示是否选择了新状态
+fn status_selector(ui: &mut Ui, id_source: &str, statuses: &[Status], current: &mut String) -> bool {
+    let mut changed = false;
+    let selected_text = statuses
+        .iter()
+        .find(|s| s.id == *current)
+        .map(|s| s.name.clone())
+        .unwrap_or_default();
+    
+    egui::ComboBox::from_id_source(id_source)
+        .selected_text(selected_text)
+        .show_ui(ui, |ui| {
+            for status in statuses {
+                let text = if status.terminal {
+                    format!("{} ✔", status.name)
+                } else {
+                    status.name.clone()
+                };
+                changed |= ui.selectable_value(current, status.id.clone(), text).changed();
+            }
+        });
+    
+    changed
+}
+
+/// 任务描述编辑器，可在编辑和Markdown预览之间切换，返回值表示是否发生了修改
+fn description_editor(ui: &mut Ui, description: &mut String, preview: &mut bool) -> bool {
+    let mut changed = false;
+    
+    ui.horizontal(|ui| {
+        ui.selectable_value(preview, false, "编辑");
+        ui.selectable_value(preview, true, "预览");
+    });
+    
+    if *preview {
+        egui::Frame::group(ui.style()).show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            if description.trim().is_empty() {
+                ui.label(RichText::new("（无描述）").italics());
+            } else {
+                let is_dark = ui.visuals().dark_mode;
+                markdown::render_markdown(ui, description, is_dark);
+            }
+        });
+    } else {
+        changed = ui.add(
+            egui::TextEdit::multiline(description)
+                .hint_text("任务描述（可选，支持Markdown）")
+                .desired_width(f32::INFINITY)
+                .desired_rows(3)
+        ).changed();
+    }
+    
+    changed
+}
+
+/// 提醒编辑器，返回值表示是否发生了修改
+fn reminders_editor(ui: &mut Ui, reminders: &mut Vec<Reminder>, due_date: Option<DateTime<Local>>) -> bool {
+    let mut changed = false;
+    let mut index_to_remove = None;
+    
+    // 提醒类型下标，与下方名称列表一一对应
+    let kind_names = ["指定时间", "截止前"];
+    
+    for (i, reminder) in reminders.iter_mut().enumerate() {
+        let reminder_id = reminder.id.clone();
+        ui.push_id(reminder_id, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("⏰");
+                
+                let kind = match reminder.kind {
+                    ReminderKind::Absolute(_) => 0,
+                    ReminderKind::BeforeDue(_) => 1,
+                };
+                let mut new_kind = kind;
+                egui::ComboBox::from_id_source("reminder_kind")
+                    .selected_text(kind_names[kind])
+                    .show_ui(ui, |ui| {
+                        for (k, name) in kind_names.iter().enumerate() {
+                            ui.selectable_value(&mut new_kind, k, *name);
+                        }
+                    });
+                
+                // 切换类型时尽量保持原来的提醒时间
+                if new_kind != kind {
+                    reminder.kind = match new_kind {
+                        0 => ReminderKind::Absolute(
+                            reminder.trigger_time(due_date).unwrap_or_else(|| Local::now() + Duration::hours(1)),
+                        ),
+                        _ => ReminderKind::BeforeDue(15),
+                    };
+                    changed = true;
+                }
+                
+                match &mut reminder.kind {
+                    ReminderKind::Absolute(at) => {
+                        changed |= date_time_fields(ui, at);
+                    }
+                    ReminderKind::BeforeDue(minutes) => {
+                        changed |= ui.add(egui::DragValue::new(minutes).clamp_range(0..=43200).suffix("分钟")).changed();
+                    }
+                }
+                
+                let status = match reminder.trigger_time(due_date) {
+                    Some(_) if !reminder.is_pending(due_date) => "已提醒".to_string(),
+                    Some(at) => format!("将于 {} 提醒", at.format("%m-%d %H:%M")),
+                    None => "⚠ 需要先设置截止时间".to_string(),
+                };
+                ui.label(RichText::new(status).small());
+                
+                if ui.small_button("❌").clicked() {
+                    index_to_remove = Some(i);
+                }
+            });
+        });
+    }
+    
+    if let Some(index) = index_to_remove {
+        reminders.remove(index);
+        changed = true;
+    }
+    
+    // 有截止时间时默认提前15分钟提醒，否则一小时后提醒
+    if ui.button("添加提醒").clicked() {
+        let kind = match due_date {
+            Some(_) => ReminderKind::BeforeDue(15),
+            None => ReminderKind::Absolute(Local::now() + Duration::hours(1)),
+        };
+        reminders.push(Reminder::new(kind));
+        changed = true;
+    }
+    
+    changed
+}
+
+/// 子任务树编辑器中将子任务转为任务的请求
+enum PromoteRequest {
+    /// 点击了转为任务按钮
+    Clicked(String),
+    /// 开始拖动子任务
+    DragStarted(String),
+}
+
+/// 子任务树编辑器，递归显示可折叠的子任务，返回值表示是否发生了修改
+///
+/// `parent_id` 记录用户选择添加下级子任务的节点。`promote` 不为空时显示转为任务的按钮，
+/// 点击或拖动按钮时写入对应的请求。
+fn subtask_tree_editor(ui: &mut Ui, subtasks: &mut Vec<SubTask>, parent_id: &mut Option<String>, editable_titles: bool, mut promote: Option<&mut Option<PromoteRequest>>) -> bool {
+    let mut changed = false;
+    let mut index_to_remove = None;
+    
+    for (i, subtask) in subtasks.iter_mut().enumerate() {
+        // 子任务行：勾选框、标题以及操作按钮
+        let mut render_row = |ui: &mut Ui, subtask: &mut SubTask| {
+            let mut completed = subtask.completed;
+            if ui.checkbox(&mut completed, "").clicked() {
+                subtask.set_completed(completed);
+                changed = true;
+            }
+            
+            if editable_titles {
+                changed |= ui.text_edit_singleline(&mut subtask.title).changed();
+            } else {
+                ui.label(&subtask.title);
+            }
+            
+            if !subtask.children.is_empty() {
+                let (done, total) = subtask.count();
+                // 减去自身，只显示下级子任务的完成情况
+                let done = done - subtask.completed as usize;
+                ui.label(RichText::new(format!("{}/{}", done, total - 1)).small());
+            }
+            
+            let is_parent = parent_id.as_deref() == Some(subtask.id.as_str());
+            if ui.selectable_label(is_parent, "➕").on_hover_text("添加下级子任务").clicked() {
+                *parent_id = if is_parent { None } else { Some(subtask.id.clone()) };
+            }
+            
+            if let Some(request) = promote.as_deref_mut() {
+                let response = ui.add(egui::Button::new("⬆").small().sense(egui::Sense::click_and_drag()))
+                    .on_hover_text("转为独立任务，也可以拖动到下方的区域");
+                if response.clicked() {
+                    *request = Some(PromoteRequest::Clicked(subtask.id.clone()));
+                } else if response.drag_started() {
+                    *request = Some(PromoteRequest::DragStarted(subtask.id.clone()));
+                }
+            }
+            
+            if ui.small_button("❌").clicked() {
+                index_to_remove = Some(i);
+            }
+        };
+        
+        if subtask.children.is_empty() {
+            ui.horizontal(|ui| {
+                // 与可折叠节点的展开图标对齐
+                ui.add_space(ui.spacing().icon_width + ui.spacing().item_spacing.x);
+                render_row(ui, subtask);
+            });
+        } else {
+            let id = ui.make_persistent_id(("subtask_tree", subtask.id.clone()));
+            egui::collapsing_header::CollapsingState::load_with_default_open(ui.ctx(), id, true)
+                .show_header(ui, |ui| render_row(ui, subtask))
+                .body(|ui| {
+                    changed |= subtask_tree_editor(ui, &mut subtask.children, parent_id, editable_titles, promote.as_deref_mut());
+                });
+        }
+    }
+    
+    if let Some(index) = index_to_remove {
+        subtasks.remove(index);
+        changed = true;
+    }
+    
+    changed
+}
+
+/// 子任务添加输入行，返回值表示是否添加了子任务
+fn subtask_add_row(ui: &mut Ui, subtasks: &mut Vec<SubTask>, parent_id: &mut Option<String>, input: &mut String) -> bool {
+    let mut added = false;
+    
+    // 父级子任务可能已被删除
+    let parent_title = parent_id
+        .as_deref()
+        .and_then(|id| find_subtask_mut(subtasks, id))
+        .map(|st| st.title.clone());
+    if parent_title.is_none() {
+        *parent_id = None;
+    }
+    
+    if let Some(title) = &parent_title {
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(format!("添加到: {}", title)).small());
+            if ui.small_button("取消").clicked() {
+                *parent_id = None;
+            }
+        });
+    }
+    
+    ui.horizontal(|ui| {
+        ui.label("新子任务:");
+        let response = ui.add(egui::TextEdit::singleline(input).hint_text("子任务标题"));
+        let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        
+        let can_add = !input.trim().is_empty();
+        
+        if (ui.add_enabled(can_add, egui::Button::new("添加")).clicked() || submitted) && can_add {
+            let subtask = SubTask::new(input.trim().to_string());
+            match parent_id.as_deref().and_then(|id| find_subtask_mut(subtasks, id)) {
+                Some(parent) => parent.children.push(subtask),
+                None => subtasks.push(subtask),
+            }
+            input.clear();
+            added = true;
+        }
+    });
+    
+    added
+}
+
+/// 前置任务编辑器，返回值表示是否发生了修改
+fn dependency_editor(ui: &mut Ui, todo_list: &TodoList, todo_id: &str, blocked_by: &mut Vec<String>) -> bool {
+    let mut changed = false;
+    
+    // 已有的前置任务
+    let mut index_to_remove = None;
+    ui.horizontal_wrapped(|ui| {
+        for (i, blocker_id) in blocked_by.iter().enumerate() {
+            let (text, done) = match todo_list.todos.get(blocker_id) {
+                Some(blocker) => (truncate_string(&blocker.title, 20), blocker.completed),
+                None => ("(已删除的任务)".to_string(), true),
+            };
+            let text = if done {
+                RichText::new(format!("✔ {}", text)).strikethrough()
+            } else {
+                RichText::new(format!("⛔ {}", text))
+            };
+            ui.label(text);
+            if ui.small_button("❌").clicked() {
+                index_to_remove = Some(i);
+            }
+        }
+    });
+    
+    if let Some(index) = index_to_remove {
+        blocked_by.remove(index);
+        changed = true;
+    }
+    
+    // 可选的前置任务，排除自身、已添加的任务
+    let mut candidates: Vec<&Todo> = todo_list.todos
+        .values()
+        .filter(|t| t.id != todo_id && !blocked_by.contains(&t.id))
+        .collect();
+    candidates.sort_by(|a, b| a.title.cmp(&b.title));
+    
+    egui::ComboBox::from_id_source("dependency_picker")
+        .selected_text("添加前置任务…")
+        .width(240.0)
+        .show_ui(ui, |ui| {
+            for candidate in candidates {
+                // 会产生循环依赖的任务不可选择
+                let creates_cycle = todo_list.would_create_cycle(todo_id, &candidate.id);
+                let response = ui.add_enabled(
+                    !creates_cycle,
+                    egui::SelectableLabel::new(false, truncate_string(&candidate.title, 30)),
+                );
+                let response = if creates_cycle {
+                    response.on_disabled_hover_text("添加此任务会产生循环依赖")
+                } else {
+                    response
+                };
+                if response.clicked() {
+                    blocked_by.push(candidate.id.clone());
+                    changed = true;
+                }
+            }
+        });
+    
+    changed
+}
+
+/// 格式化较长的时间间隔，超过一天时显示天数
+fn format_elapsed(duration: Duration) -> String {
+    let days = duration.num_days();
+    if days > 0 {
+        format!("{}天{}小时", days, duration.num_hours() % 24)
+    } else {
+        format_duration(duration)
+    }
+}
+
+/// 绘制柱状图，悬停时显示对应柱的标签和数值
+fn bar_chart(ui: &mut Ui, bars: &[(String, usize)], color: Color32, height: f32) {
+    let (rect, response) = ui.allocate_exact_size(Vec2::new(ui.available_width(), height), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let text_color = ui.visuals().text_color();
+    let axis_color = ui.visuals().weak_text_color();
+    let font = egui::FontId::proportional(10.0);
+    
+    // 上方留出数值的位置，下方留出标签的位置
+    let plot = egui::Rect::from_min_max(rect.min + Vec2::new(0.0, 14.0), rect.max - Vec2::new(0.0, 16.0));
+    painter.line_segment([plot.left_bottom(), plot.right_bottom()], egui::Stroke::new(1.0, axis_color));
+    
+    if bars.is_empty() {
+        return;
+    }
+    
+    let max = bars.iter().map(|(_, count)| *count).max().unwrap_or(0).max(1);
+    let slot = plot.width() / bars.len() as f32;
+    // 标签之间至少间隔40像素
+    let label_every = ((40.0 / slot).ceil() as usize).max(1);
+    
+    for (i, (label, count)) in bars.iter().enumerate() {
+        let x = plot.left() + slot * i as f32;
+        let bar_height = plot.height() * *count as f32 / max as f32;
+        let bar = egui::Rect::from_min_max(
+            egui::pos2(x + slot * 0.15, plot.bottom() - bar_height),
+            egui::pos2(x + slot * 0.85, plot.bottom()),
+        );
+        painter.rect_filled(bar, egui::Rounding::same(2.0), color);
+        
+        if *count > 0 && slot >= 14.0 {
+            painter.text(egui::pos2(bar.center().x, bar.top() - 1.0), egui::Align2::CENTER_BOTTOM, count.to_string(), font.clone(), text_color);
+        }
+        if i % label_every == 0 {
+            painter.text(egui::pos2(x + slot / 2.0, plot.bottom() + 2.0), egui::Align2::CENTER_TOP, label, font.clone(), axis_color);
+        }
+    }
+    
+    if let Some(pos) = response.hover_pos() {
+        let index = ((pos.x - plot.left()) / slot) as usize;
+        if let Some((label, count)) = bars.get(index) {
+            response.on_hover_text(format!("{}: {}", label, count));
+        }
+    }
+}
+
+/// 绘制未完成/已完成数量的折线图
+fn trend_chart(ui: &mut Ui, points: &[(NaiveDate, usize, usize)], open_color: Color32, done_color: Color32, height: f32) {
+    let (rect, response) = ui.allocate_exact_size(Vec2::new(ui.available_width(), height), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let axis_color = ui.visuals().weak_text_color();
+    let font = egui::FontId::proportional(10.0);
+    
+    let plot = egui::Rect::from_min_max(rect.min + Vec2::new(28.0, 6.0), rect.max - Vec2::new(4.0, 16.0));
+    painter.line_segment([plot.left_bottom(), plot.right_bottom()], egui::Stroke::new(1.0, axis_color));
+    painter.line_segment([plot.left_top(), plot.left_bottom()], egui::Stroke::new(1.0, axis_color));
+    
+    if points.is_empty() {
+        return;
+    }
+    
+    let max = points.iter().map(|(_, open, done)| (*open).max(*done)).max().unwrap_or(0).max(1);
+    let step = if points.len() > 1 { plot.width() / (points.len() - 1) as f32 } else { 0.0 };
+    let to_pos = |i: usize, value: usize| {
+        egui::pos2(plot.left() + step * i as f32, plot.bottom() - plot.height() * value as f32 / max as f32)
+    };
+    
+    // 纵轴最大值和首尾日期
+    painter.text(egui::pos2(plot.left() - 4.0, plot.top()), egui::Align2::RIGHT_TOP, max.to_string(), font.clone(), axis_color);
+    painter.text(egui::pos2(plot.left() - 4.0, plot.bottom()), egui::Align2::RIGHT_BOTTOM, "0", font.clone(), axis_color);
+    if let (Some(first), Some(last)) = (points.first(), points.last()) {
+        painter.text(egui::pos2(plot.left(), plot.bottom() + 2.0), egui::Align2::LEFT_TOP, first.0.format("%m-%d").to_string(), font.clone(), axis_color);
+        painter.text(egui::pos2(plot.right(), plot.bottom() + 2.0), egui::Align2::RIGHT_TOP, last.0.format("%m-%d").to_string(), font.clone(), axis_color);
+    }
+    
+    let open_line: Vec<egui::Pos2> = points.iter().enumerate().map(|(i, (_, open, _))| to_pos(i, *open)).collect();
+    let done_line: Vec<egui::Pos2> = points.iter().enumerate().map(|(i, (_, _, done))| to_pos(i, *done)).collect();
+    painter.add(egui::Shape::line(open_line, egui::Stroke::new(2.0, open_color)));
+    painter.add(egui::Shape::line(done_line, egui::Stroke::new(2.0, done_color)));
+    
+    // 悬停时标出最近的日期
+    if let Some(pos) = response.hover_pos() {
+        let index = if step > 0.0 { ((pos.x - plot.left()) / step).round().max(0.0) as usize } else { 0 };
+        if let Some((day, open, done)) = points.get(index.min(points.len() - 1)) {
+            let x = plot.left() + step * index.min(points.len() - 1) as f32;
+            painter.line_segment([egui::pos2(x, plot.top()), egui::pos2(x, plot.bottom())], egui::Stroke::new(1.0, axis_color));
+            response.on_hover_text(format!("{}\n未完成: {}\n已完成: {}", day.format("%Y-%m-%d"), open, done));
+        }
+    }
+}
+
 impl eframe::App for RodoApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // 响应窗口可见性变化
@@ -37,6 +806,22 @@ impl eframe::App for RodoApp {
         // 应用主题
         self.theme.apply_to_ctx(ctx);
         
+        // 处理后台调度器触发的提醒
+        self.process_fired_reminders();
+        
+        // 撤销/重做快捷键，文本框获得焦点时交给文本框自己处理
+        if !ctx.wants_keyboard_input() {
+            let (undo, redo) = ctx.input(|i| {
+                let pressed = i.modifiers.command && i.key_pressed(egui::Key::Z);
+                (pressed && !i.modifiers.shift, pressed && i.modifiers.shift)
+            });
+            if undo {
+                self.undo();
+            } else if redo {
+                self.redo();
+            }
+        }
+        
         // 顶部面板 - 设置为固定高度
         egui::TopBottomPanel::top("top_panel")
             .exact_height(36.0)  // 略微增加面板高度
@@ -92,9 +877,95 @@ impl eframe::App for RodoApp {
                         if ui.add_sized([32.0, 32.0], egui::Button::new("⚙️")).clicked() {
                             self.view = View::Settings;
                         }
-                    });
-                    
-                    // 右侧添加一点点空间
+                        
+                        // 时间报表按钮 - 使用固定大小
+                        if ui.add_sized([32.0, 32.0], egui::Button::new("⏱")).clicked() {
+                            self.view = View::TimeReport;
+                        }
+                        
+                        // 统计按钮 - 使用固定大小
+                        if ui.add_sized([32.0, 32.0], egui::Button::new("📊")).on_hover_text("统计").clicked() {
+                            self.view = View::Stats;
+                        }
+                        
+                        // 看板按钮 - 使用固定大小
+                        if ui.add_sized([32.0, 32.0], egui::Button::new("📋")).on_hover_text("看板").clicked() {
+                            self.view = View::Board;
+                        }
+                        
+                        // 日历按钮 - 使用固定大小
+                        if ui.add_sized([32.0, 32.0], egui::Button::new("📅")).on_hover_text("日历").clicked() {
+                            self.view = View::Calendar;
+                        }
+                        
+                        // 回收站按钮 - 使用固定大小
+                        if ui.add_sized([32.0, 32.0], egui::Button::new("🗑")).on_hover_text("回收站").clicked() {
+                            self.view = View::Trash;
+                        }
+                        
+                        ui.add_space(8.0);
+                        
+                        // 撤销/重做按钮
+                        let undo_hint = match self.history.undo_label() {
+                            Some(label) => format!("撤销：{} (Ctrl+Z)", label),
+                            None => "没有可撤销的操作".to_string(),
+                        };
+                        if ui.add_enabled(self.history.undo_label().is_some(), egui::Button::new("↶"))
+                            .on_hover_text(undo_hint)
+                            .on_disabled_hover_text("没有可撤销的操作")
+                            .clicked()
+                        {
+                            self.undo();
+                        }
+                        
+                        let redo_hint = match self.history.redo_label() {
+                            Some(label) => format!("重做：{} (Ctrl+Shift+Z)", label),
+                            None => "没有可重做的操作".to_string(),
+                        };
+                        if ui.add_enabled(self.history.redo_label().is_some(), egui::Button::new("↷"))
+                            .on_hover_text(redo_hint)
+                            .on_disabled_hover_text("没有可重做的操作")
+                            .clicked()
+                        {
+                            self.redo();
+                        }
+                        
+                        ui.add_space(8.0);
+                        
+                        // 工作区切换菜单
+                        self.render_workspace_menu(ui);
+                        
+                        // 正在运行的计时器
+                        let running = self.todo_list.running_timer()
+                            .map(|t| (t.id.clone(), t.title.clone(), t.running_entry().map(|e| e.duration()).unwrap_or_else(Duration::zero)));
+                        if let Some((id, title, elapsed)) = running {
+                            ui.add_space(8.0);
+                            
+                            let seconds = elapsed.num_seconds();
+                            let text = format!(
+                                "⏱ {} {:02}:{:02}:{:02}",
+                                truncate_string(&title, 16),
+                                seconds / 3600,
+                                seconds % 3600 / 60,
+                                seconds % 60,
+                            );
+                            if ui.button(RichText::new(text).color(self.theme.success)).on_hover_text("打开任务").clicked() {
+                                self.editing_todo_id = Some(id.clone());
+                                self.view = View::EditTodo;
+                            }
+                            if ui.button("⏹").on_hover_text("停止计时").clicked() {
+                                if let Some(todo) = self.todo_list.todos.get_mut(&id) {
+                                    todo.stop_timer();
+                                    self.modified = true;
+                                }
+                            }
+                            
+                            // 计时器每秒刷新
+                            ctx.request_repaint_after(std::time::Duration::from_secs(1));
+                        }
+                    });
+                    
+                    // 右侧添加一点点空间
                     ui.add_space(0.0);
                 });
             });
@@ -114,26 +985,359 @@ impl eframe::App for RodoApp {
                 View::Tags => self.render_tags(ui),
                 View::About => self.render_about(ui),
                 View::MarkdownViewer => self.render_markdown_viewer(ui),
+                View::TimeReport => self.render_time_report(ui),
+                View::Trash => self.render_trash(ui),
+                View::Board => self.render_board(ui),
+                View::Calendar => self.render_calendar(ui),
             }
         });
         
+        // 任务文件无法读取时提示从备份恢复
+        if self.load_failure.is_some() {
+            self.render_load_failure_dialog(ctx);
+        }
+        
         // 显示确认对话框
         if self.show_confirmation {
             self.render_confirmation_dialog(ctx);
         }
         
+        // 显示应用内提示
+        if !self.toasts.is_empty() {
+            self.render_toasts(ctx);
+        }
+        
+        // 任务有修改时记录撤销历史并重新安排提醒
+        if self.modified {
+            self.record_history();
+            self.refresh_reminder_schedule();
+        }
+        
         // 每帧自动保存（如果有修改）
         self.save();
     }
 }
 
 impl RodoApp {
+    /// 渲染应用内提示，显示在窗口右下角
+    fn render_toasts(&mut self, ctx: &egui::Context) {
+        let mut dismissed = None;
+        let mut triggered = None;
+        
+        egui::Area::new("toasts")
+            .anchor(egui::Align2::RIGHT_BOTTOM, [-12.0, -12.0])
+            .show(ctx, |ui| {
+                for (i, toast) in self.toasts.iter().enumerate() {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(&toast.message);
+                            
+                            if let Some(action) = &toast.action {
+                                let label = match action {
+                                    ToastAction::OpenTodo(_) => "查看",
+                                    ToastAction::Undo => "撤销",
+                                };
+                                if ui.small_button(label).clicked() {
+                                    triggered = Some(action.clone());
+                                    dismissed = Some(i);
+                                }
+                            }
+                            
+                            if ui.small_button("✖").clicked() {
+                                dismissed = Some(i);
+                            }
+                        });
+                    });
+                    ui.add_space(4.0);
+                }
+            });
+        
+        if let Some(index) = dismissed {
+            self.toasts.remove(index);
+        }
+        
+        match triggered {
+            Some(ToastAction::OpenTodo(id)) if self.todo_list.todos.contains_key(&id) => {
+                self.editing_todo_id = Some(id);
+                self.view = View::EditTodo;
+            }
+            Some(ToastAction::Undo) => self.undo(),
+            _ => {}
+        }
+        
+        // 提示到期后自动消失
+        ctx.request_repaint_after(std::time::Duration::from_secs(1));
+    }
+    
+    /// 渲染工作区切换菜单
+    fn render_workspace_menu(&mut self, ui: &mut Ui) {
+        let active_name = self.workspaces.active().name.clone();
+        
+        ui.menu_button(format!("🗂 {}", active_name), |ui| {
+            // 工作区列表
+            let workspaces = self.workspaces.workspaces.clone();
+            for workspace in &workspaces {
+                let is_active = workspace.id == self.workspaces.active;
+                if ui.selectable_label(is_active, &workspace.name).clicked() {
+                    self.switch_workspace(&workspace.id);
+                    ui.close_menu();
+                }
+            }
+            
+            ui.separator();
+            
+            // 工作区管理
+            ui.add(egui::TextEdit::singleline(&mut self.workspace_name_input).hint_text("工作区名称"));
+            let name = self.workspace_name_input.trim().to_string();
+            let has_name = !name.is_empty();
+            
+            let mut result = None;
+            ui.horizontal(|ui| {
+                if ui.add_enabled(has_name, egui::Button::new("新建")).clicked() {
+                    result = Some(self.create_workspace(&name));
+                }
+                if ui.add_enabled(has_name, egui::Button::new("重命名当前")).clicked() {
+                    result = Some(self.rename_workspace(&name));
+                }
+                if ui.add_enabled(has_name, egui::Button::new("复制当前")).clicked() {
+                    result = Some(self.duplicate_workspace(&name));
+                }
+            });
+            
+            match result {
+                Some(Ok(())) => {
+                    self.workspace_name_input.clear();
+                    ui.close_menu();
+                },
+                Some(Err(err)) => {
+                    eprintln!("工作区操作失败: {}", err);
+                    ui.close_menu();
+                    self.show_notice(&format!("工作区操作失败: {}", err));
+                },
+                None => {},
+            }
+            
+            let can_delete = workspaces.len() > 1;
+            let delete_button = Button::new(RichText::new("删除当前工作区").color(self.theme.error));
+            if ui.add_enabled(can_delete, delete_button).clicked() {
+                ui.close_menu();
+                self.show_confirm(
+                    &format!("确定要删除工作区 \"{}\" 吗？其中的所有任务都将被删除！", active_name),
+                    ConfirmationAction::DeleteWorkspace(self.workspaces.active.clone()),
+                );
+            }
+        });
+    }
+    
+    /// 渲染智能列表栏
+    fn render_smart_lists(&mut self, ui: &mut Ui) {
+        let mut apply = None;
+        let mut result = None;
+        
+        ui.horizontal_wrapped(|ui| {
+            ui.label("⭐ 智能列表:");
+            
+            let lists = self.smart_lists.lists.clone();
+            for list in &lists {
+                let selected = list.is_current(&self.todo_list, &self.search_input);
+                let response = match list.count(&self.todo_list) {
+                    Ok(count) => {
+                        let response = ui.selectable_label(selected, format!("{} ({})", list.name, count));
+                        if list.query.is_empty() { response } else { response.on_hover_text(&list.query) }
+                    }
+                    Err(err) => ui.selectable_label(selected, format!("{} (⚠)", list.name))
+                        .on_hover_text(format!("搜索语句无效: {}", err)),
+                };
+                if response.clicked() {
+                    apply = Some(list.id.clone());
+                }
+                
+                response.context_menu(|ui| {
+                    if ui.button("用当前筛选条件更新").clicked() {
+                        result = Some(self.update_smart_list(&list.id));
+                        ui.close_menu();
+                    }
+                    
+                    ui.separator();
+                    ui.add(egui::TextEdit::singleline(&mut self.smart_list_name_input).hint_text("新名称"));
+                    let name = self.smart_list_name_input.trim().to_string();
+                    if ui.add_enabled(!name.is_empty(), egui::Button::new("重命名")).clicked() {
+                        result = Some(self.rename_smart_list(&list.id, &name));
+                        ui.close_menu();
+                    }
+                    
+                    ui.separator();
+                    if ui.button(RichText::new("删除").color(self.theme.error)).clicked() {
+                        result = Some(self.delete_smart_list(&list.id));
+                        ui.close_menu();
+                    }
+                });
+            }
+            
+            ui.menu_button("＋ 保存当前筛选", |ui| {
+                ui.add(egui::TextEdit::singleline(&mut self.smart_list_name_input).hint_text("智能列表名称"));
+                let name = self.smart_list_name_input.trim().to_string();
+                if ui.add_enabled(!name.is_empty(), egui::Button::new("保存")).clicked() {
+                    result = Some(self.add_smart_list(&name));
+                    ui.close_menu();
+                }
+            });
+        });
+        
+        if let Some(id) = apply {
+            self.apply_smart_list(&id);
+        }
+        
+        match result {
+            Some(Ok(())) => self.smart_list_name_input.clear(),
+            Some(Err(err)) => {
+                eprintln!("智能列表操作失败: {}", err);
+                self.show_notice(&format!("智能列表操作失败: {}", err));
+            }
+            None => {}
+        }
+    }
+    
+    /// 渲染多选模式下的批量操作栏
+    fn render_bulk_actions(&mut self, ui: &mut Ui) {
+        let filtered_ids: Vec<String> = self.todo_list.filtered_todos().iter().map(|t| t.id.clone()).collect();
+        let selected = self.selected_ids();
+        let has_selection = !selected.is_empty();
+        
+        ui.horizontal_wrapped(|ui| {
+            ui.label(RichText::new(format!("已选 {} 个", selected.len())).strong());
+            
+            if ui.button(format!("全选 ({})", filtered_ids.len())).on_hover_text("选中当前筛选结果中的所有任务").clicked() {
+                self.selected_todos.extend(filtered_ids.iter().cloned());
+            }
+            if ui.add_enabled(has_selection, egui::Button::new("取消选择")).clicked() {
+                self.clear_selection();
+            }
+            
+            ui.separator();
+            ui.add_enabled_ui(has_selection, |ui| {
+                if ui.button("✔ 完成").clicked() {
+                    let skipped = self.bulk_set_completed(true);
+                    if skipped > 0 {
+                        self.toasts.push(Toast::new(format!("{} 个被阻塞的任务未完成", skipped), None));
+                    }
+                }
+                if ui.button("↺ 重新打开").clicked() {
+                    self.bulk_set_completed(false);
+                }
+                
+                ui.menu_button("优先级", |ui| {
+                    for (priority, _, name) in board_priorities() {
+                        if ui.button(RichText::new(name).color(priority_color(&priority))).clicked() {
+                            self.bulk_set_priority(priority);
+                            ui.close_menu();
+                        }
+                    }
+                });
+                
+                ui.menu_button("🏷️ 标签", |ui| {
+                    ui.horizontal(|ui| {
+                        ui.add(egui::TextEdit::singleline(&mut self.bulk_tag_input).hint_text("标签名称").desired_width(120.0));
+                        let tag = self.bulk_tag_input.trim().to_string();
+                        if ui.add_enabled(!tag.is_empty(), egui::Button::new("添加")).clicked() {
+                            self.bulk_add_tag(&tag);
+                            self.bulk_tag_input.clear();
+                            ui.close_menu();
+                        }
+                    });
+                    
+                    // 选中任务已有的标签，点击移除
+                    let mut tags: Vec<String> = selected.iter()
+                        .filter_map(|id| self.todo_list.todos.get(id))
+                        .flat_map(|t| t.tags.iter().cloned())
+                        .collect();
+                    tags.sort();
+                    tags.dedup();
+                    if !tags.is_empty() {
+                        ui.separator();
+                        ui.label(RichText::new("点击移除:").small().color(self.theme.text_secondary));
+                        for tag in tags {
+                            if ui.button(format!("✖ {}", tag)).clicked() {
+                                self.bulk_remove_tag(&tag);
+                                ui.close_menu();
+                            }
+                        }
+                    }
+                });
+                
+                ui.menu_button("📅 截止时间", |ui| {
+                    let now = Local::now();
+                    ui.add(egui::TextEdit::singleline(&mut self.bulk_due_input).hint_text("例如 明天 17:00、next friday、2024-05-01").desired_width(200.0));
+                    
+                    // 复用快速添加的日期解析，输入中不能有多余的文字
+                    let parsed = quick_add::parse(&self.bulk_due_input, now);
+                    let due = parsed.due_date.filter(|_| parsed.title.is_empty());
+                    match due {
+                        Some(due) => {
+                            ui.label(RichText::new(format!("📅 {}", Todo::format_due_date(&due))).small().color(self.theme.text_secondary));
+                        }
+                        None if !self.bulk_due_input.trim().is_empty() => {
+                            ui.label(RichText::new("无法识别的日期").small().color(self.theme.error));
+                        }
+                        None => {}
+                    }
+                    
+                    if ui.add_enabled(due.is_some(), egui::Button::new("设置")).clicked() {
+                        self.bulk_set_due_date(due);
+                        self.bulk_due_input.clear();
+                        ui.close_menu();
+                    }
+                    if ui.button("清除截止时间").clicked() {
+                        self.bulk_set_due_date(None);
+                        ui.close_menu();
+                    }
+                });
+                
+                ui.menu_button("💤 推迟", |ui| {
+                    if let Some(until) = snooze_menu(ui, true, &mut self.snooze_pick) {
+                        self.snooze_todos(&selected, until);
+                    }
+                });
+                
+                if self.workspaces.workspaces.len() > 1 {
+                    ui.menu_button("📂 移动到", |ui| {
+                        let targets: Vec<(String, String)> = self.workspaces.workspaces.iter()
+                            .filter(|w| w.id != self.workspaces.active)
+                            .map(|w| (w.id.clone(), w.name.clone()))
+                            .collect();
+                        for (id, name) in targets {
+                            if ui.button(name).clicked() {
+                                if let Err(err) = self.bulk_move_to_workspace(&id) {
+                                    eprintln!("移动任务失败: {}", err);
+                                    self.show_notice(&format!("移动任务失败: {}", err));
+                                }
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                }
+                
+                if ui.button(RichText::new("删除").color(self.theme.error)).clicked() {
+                    self.show_confirm(
+                        &format!("确定要删除选中的 {} 个任务吗？任务将移入回收站。", selected.len()),
+                        ConfirmationAction::DeleteTodos(selected.clone()),
+                    );
+                }
+            });
+        });
+        ui.label(RichText::new("按住 Shift 点击可选择连续的任务").small().color(self.theme.text_secondary));
+    }
+    
     /// 渲染待办事项列表
     fn render_todo_list(&mut self, ui: &mut Ui) {
         // 标题和操作按钮
         ui.horizontal(|ui| {
             ui.heading("待办事项");
             
+            if ui.toggle_value(&mut self.selection_mode, "☑ 多选").on_hover_text("选择多个任务进行批量操作").changed() && !self.selection_mode {
+                self.clear_selection();
+            }
+            
             ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
                 // 标签管理按钮
                 {
@@ -186,17 +1390,18 @@ impl RodoApp {
                 
                 // 优先级排序按钮
                 {
-                    let sort_text = match self.todo_list.priority_sort {
-                        Some(true) => "🔽 优先级高",   // 从高到低
-                        Some(false) => "🔼 优先级低",  // 从低到高
-                        None => "⏺️ 时间排序",       // 默认按时间排序
+                    let sort_text = match (self.todo_list.manual_sort, self.todo_list.priority_sort) {
+                        (true, _) => "✋ 手动排序",          // 按手动顺序
+                        (false, Some(true)) => "🔽 优先级高",   // 从高到低
+                        (false, Some(false)) => "🔼 优先级低",  // 从低到高
+                        (false, None) => "⏺️ 时间排序",       // 默认按时间排序
                     };
                     
                     // 创建一个特殊风格的按钮
                     let mut button = egui::Button::new(RichText::new(sort_text).size(18.0).strong());
                     
                     // 根据排序状态设置按钮样式
-                    if self.todo_list.priority_sort.is_some() {
+                    if self.todo_list.manual_sort || self.todo_list.priority_sort.is_some() {
                         // 激活状态下使用填充色
                         let accent_color = if self.todo_list.priority_sort == Some(true) {
                             self.theme.warning.linear_multiply(1.2) // 高优先级时用警告色
@@ -245,12 +1450,15 @@ impl RodoApp {
                     }
                     
                     if response.clicked() {
-                        // 切换排序状态：时间排序 -> 优先级高 -> 优先级低 -> 时间排序
-                        self.todo_list.priority_sort = match self.todo_list.priority_sort {
-                            None => Some(true),        // 时间排序 -> 优先级高
-                            Some(true) => Some(false), // 优先级高 -> 优先级低
-                            Some(false) => None,       // 优先级低 -> 时间排序
+                        // 切换排序状态：时间排序 -> 优先级高 -> 优先级低 -> 手动排序 -> 时间排序
+                        let (manual_sort, priority_sort) = match (self.todo_list.manual_sort, self.todo_list.priority_sort) {
+                            (true, _) => (false, None),                // 手动排序 -> 时间排序
+                            (false, None) => (false, Some(true)),      // 时间排序 -> 优先级高
+                            (false, Some(true)) => (false, Some(false)), // 优先级高 -> 优先级低
+                            (false, Some(false)) => (true, None),      // 优先级低 -> 手动排序
                         };
+                        self.todo_list.manual_sort = manual_sort;
+                        self.todo_list.priority_sort = priority_sort;
                         self.modified = true;
                     }
                 }
@@ -322,34 +1530,186 @@ impl RodoApp {
             });
         });
         
+        // 快速添加
+        ui.horizontal(|ui| {
+            ui.label("➕");
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.quick_add_input)
+                    .hint_text("快速添加，例如 Deploy api tomorrow 17:00 #work !high ~30m 或 明天下午3点 开会 #工作")
+                    .desired_width(ui.available_width()),
+            );
+            
+            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) && !self.quick_add_input.trim().is_empty() {
+                match self.quick_add_todo() {
+                    Ok(()) => response.request_focus(),
+                    Err(err) => {
+                        self.show_notice(&format!("无法添加任务: {}", err));
+                    }
+                }
+            }
+        });
+        
+        // 输入时预览解析结果
+        if !self.quick_add_input.trim().is_empty() {
+            let parsed = quick_add::parse(&self.quick_add_input, Local::now());
+            let mut parts = vec![format!("标题: {}", if parsed.title.is_empty() { "(空)" } else { &parsed.title })];
+            if let Some(due) = parsed.due_date {
+                parts.push(format!("📅 {}", Todo::format_due_date(&due)));
+            }
+            for tag in &parsed.tags {
+                parts.push(format!("🏷️ {}", tag));
+            }
+            if let Some(priority) = &parsed.priority {
+                parts.push(format!("优先级: {}", priority.as_str()));
+            }
+            if let Some(minutes) = parsed.estimate_minutes {
+                parts.push(format!("⏳ {}", format_duration(Duration::minutes(minutes))));
+            }
+            ui.label(RichText::new(parts.join("   ")).small().color(self.theme.text_secondary));
+        }
+        
         ui.separator();
         
+        // 智能列表，显示每个列表当前的任务数量
+        self.render_smart_lists(ui);
+        
+        // 搜索栏
+        ui.horizontal(|ui| {
+            ui.label("🔍");
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.search_input)
+                    .hint_text("搜索，例如 tag:work -tag:blocked priority>=high due<7d is:open \"release notes\"")
+                    .desired_width(ui.available_width() - 60.0),
+            );
+            
+            // Ctrl+F 聚焦搜索框
+            if ui.input(|i| i.modifiers.command && i.key_pressed(egui::Key::F)) {
+                response.request_focus();
+            }
+            if response.changed() {
+                self.apply_search();
+            }
+            
+            if !self.search_input.is_empty() && ui.small_button("❌").on_hover_text("清除搜索").clicked() {
+                self.search_input.clear();
+                self.apply_search();
+            }
+            ui.label("❓").on_hover_text(query::SYNTAX_HELP);
+        });
+        if let Some(err) = &self.search_error {
+            ui.label(RichText::new(format!("搜索语法错误: {}", err)).color(self.theme.error));
+        }
+        
+        // 被阻塞任务的显示选项
+        if self.todo_list.todos.values().any(|t| !t.blocked_by.is_empty())
+            && ui.checkbox(&mut self.todo_list.hide_blocked, "隐藏被阻塞的任务").changed()
+        {
+            self.modified = true;
+        }
+        
+        // 推迟任务的显示选项
+        let deferred_count = self.todo_list.todos.values().filter(|t| t.is_deferred(Local::now())).count();
+        if (deferred_count > 0 || self.todo_list.show_deferred)
+            && ui.checkbox(&mut self.todo_list.show_deferred, format!("显示推迟的任务 ({})", deferred_count)).changed()
+        {
+            self.modified = true;
+        }
+        
+        // 工作流状态筛选，同时显示每个状态的任务数量
+        ui.horizontal_wrapped(|ui| {
+            ui.label("状态:");
+            
+            let counts: Vec<(String, String, usize)> = self.todo_list.status_counts()
+                .into_iter()
+                .map(|(status, count)| (status.id.clone(), status.name.clone(), count))
+                .collect();
+            
+            let all_selected = self.todo_list.status_filter.is_none();
+            if ui.selectable_label(all_selected, format!("全部 ({})", self.todo_list.todos.len())).clicked() && !all_selected {
+                self.todo_list.status_filter = None;
+                self.modified = true;
+            }
+            
+            for (status_id, name, count) in counts {
+                let selected = self.todo_list.status_filter.as_ref() == Some(&status_id);
+                if ui.selectable_label(selected, format!("{} ({})", name, count)).clicked() {
+                    // 再次点击已选中的状态时取消筛选
+                    self.todo_list.status_filter = if selected { None } else { Some(status_id) };
+                    self.modified = true;
+                }
+            }
+        });
+        
         // 显示活跃标签过滤器（如果有）
-        if !self.todo_list.active_tags.is_empty() {
-            ui.horizontal(|ui| {
+        if !self.todo_list.active_tags.is_empty() || !self.todo_list.excluded_tags.is_empty() {
+            ui.horizontal_wrapped(|ui| {
                 ui.label("筛选标签:");
-                // 先收集需要移除的索引
-                let mut indices_to_remove = Vec::new();
                 
-                for (idx, tag) in self.todo_list.active_tags.iter().enumerate() {
-                    if ui.button(format!("🏷️ {}", tag)).clicked() {
-                        // 记录要移除的标签索引，而不是直接修改
-                        indices_to_remove.push(idx);
+                // 筛选标签的匹配方式
+                if !self.todo_list.active_tags.is_empty() {
+                    for tag_match in [TagMatch::Any, TagMatch::All, TagMatch::None] {
+                        if ui.selectable_value(&mut self.todo_list.tag_match, tag_match, tag_match.as_str()).changed() {
+                            self.modified = true;
+                        }
                     }
+                    ui.add_space(8.0);
                 }
                 
-                // 在循环外移除标签
-                if !indices_to_remove.is_empty() {
-                    // 从后往前移除，以避免索引失效
-                    for idx in indices_to_remove.iter().rev() {
-                        self.todo_list.active_tags.remove(*idx);
-                        self.modified = true;
+                // 先收集需要修改的标签，在循环外修改
+                let mut tag_to_remove = None;
+                let mut tag_to_include = None;
+                let mut tag_to_exclude = None;
+                
+                let tag_match = self.todo_list.tag_match;
+                for tag in &self.todo_list.active_tags {
+                    let hint = match tag_match {
+                        TagMatch::Any => "带有任一筛选标签",
+                        TagMatch::All => "带有全部筛选标签",
+                        TagMatch::None => "不带任何筛选标签",
+                    };
+                    let response = ui.button(format!("{} 🏷️ {}", tag_match.symbol(), tag))
+                        .on_hover_text(format!("{}，点击取消筛选，右键改为排除", hint));
+                    if response.clicked() {
+                        tag_to_remove = Some(tag.clone());
+                    }
+                    if response.secondary_clicked() {
+                        tag_to_exclude = Some(tag.clone());
+                    }
+                }
+                
+                for tag in &self.todo_list.excluded_tags {
+                    let response = ui.button(RichText::new(format!("{} 🏷️ {}", TagMatch::None.symbol(), tag)).color(self.theme.error))
+                        .on_hover_text("排除带有此标签的任务，点击取消排除，右键改为筛选");
+                    if response.clicked() {
+                        tag_to_remove = Some(tag.clone());
+                    }
+                    if response.secondary_clicked() {
+                        tag_to_include = Some(tag.clone());
                     }
                 }
+                
+                if let Some(tag) = tag_to_remove {
+                    self.todo_list.clear_tag_filter(&tag);
+                    self.modified = true;
+                }
+                if let Some(tag) = tag_to_include {
+                    self.todo_list.include_tag(&tag);
+                    self.modified = true;
+                }
+                if let Some(tag) = tag_to_exclude {
+                    self.todo_list.exclude_tag(&tag);
+                    self.modified = true;
+                }
             });
             ui.add_space(8.0);
         }
         
+        // 批量操作
+        if self.selection_mode {
+            self.render_bulk_actions(ui);
+            ui.add_space(4.0);
+        }
+        
         // 渲染任务列表
         let todos = self.todo_list.filtered_todos();
         
@@ -357,7 +1717,11 @@ impl RodoApp {
             // 显示空状态
             ui.vertical_centered(|ui| {
                 ui.add_space(50.0);
-                ui.label("没有待办事项");
+                if self.todo_list.search.is_some() {
+                    ui.label("没有匹配的任务");
+                } else {
+                    ui.label("没有待办事项");
+                }
                 ui.add_space(8.0);
                 if ui.button("添加任务").clicked() {
                     self.view = View::AddTodo;
@@ -367,12 +1731,11 @@ impl RodoApp {
             });
         } else {
             // 预先收集所有任务所需的信息
-            let todo_infos: Vec<(String, String, bool, Priority, String, Vec<String>, usize, usize, DateTime<Local>, Option<DateTime<Local>>)> = todos
+            let mut todo_infos: Vec<(String, String, bool, Priority, String, Vec<String>, usize, usize, DateTime<Local>, Option<DateTime<Local>>, Option<DateTime<Local>>, bool, DueBucket)> = todos
                 .iter()
                 .map(|todo| {
-                    // 计算子任务完成数量
-                    let completed_subtasks = todo.subtasks.iter().filter(|st| st.completed).count();
-                    let total_subtasks = todo.subtasks.len();
+                    // 计算子任务完成数量（包含所有层级）
+                    let (completed_subtasks, total_subtasks) = todo.subtask_counts();
                     
                     // 提取表情符号
                     let emoji = match &todo.emoji {
@@ -392,7 +1755,7 @@ impl RodoApp {
                         Emoji::Custom(ref s) => s.clone(),
                     };
                     
-                    // 返回元组(id, title, completed, priority, emoji, tags, completed_subtasks, total_subtasks, created_at, completed_at)
+                    // 返回元组(id, title, completed, priority, emoji, tags, completed_subtasks, total_subtasks, created_at, completed_at, due_date, overdue, due_bucket)
                     (
                         todo.id.clone(),
                         todo.title.clone(),
@@ -403,14 +1766,46 @@ impl RodoApp {
                         completed_subtasks,
                         total_subtasks,
                         todo.created_at,
-                        todo.completed_at.clone()
+                        todo.completed_at.clone(),
+                        todo.due_date,
+                        todo.is_overdue(),
+                        todo.due_bucket()
                     )
                 })
                 .collect();
             
+            // 按截止日期分组，稳定排序保留组内原有顺序；手动排序时保持用户排列的顺序
+            let manual_sort = self.todo_list.manual_sort;
+            if !manual_sort {
+                todo_infos.sort_by_key(|info| info.12);
+            }
+            
+            // 可见任务的顺序，用于键盘移动
+            let visible_ids: Vec<String> = todo_infos.iter().map(|info| info.0.clone()).collect();
+            // 每个任务卡片的位置，用于拖放
+            let mut row_rects: Vec<(String, egui::Rect)> = Vec::new();
+            // 待执行的移动：(任务ID, 目标任务ID, 是否放在目标之后)
+            let mut move_request: Option<(String, String, bool)> = None;
+            // 待执行的转为子任务：(任务ID, 目标任务ID)
+            let mut demote_request: Option<(String, String)> = None;
+            
             // 显示任务列表
             ScrollArea::vertical().show(ui, |ui| {
-                for (id, title, completed, priority, emoji, tags, completed_subtasks, total_subtasks, created_at, completed_at) in todo_infos {
+                let mut current_bucket = None;
+                
+                for (id, title, completed, priority, emoji, tags, completed_subtasks, total_subtasks, created_at, completed_at, due_date, overdue, due_bucket) in todo_infos {
+                    // 分组标题
+                    if !manual_sort && current_bucket != Some(due_bucket) {
+                        current_bucket = Some(due_bucket);
+                        ui.add_space(8.0);
+                        let header_color = if due_bucket == DueBucket::Overdue {
+                            self.theme.error
+                        } else {
+                            self.theme.text_secondary
+                        };
+                        ui.label(RichText::new(due_bucket.as_str()).strong().color(header_color));
+                    }
+                    
                     ui.add_space(4.0);
                     
                     // 任务卡片背景
@@ -421,36 +1816,96 @@ impl RodoApp {
                     };
                     
                     // 任务卡片边框颜色（基于优先级）
-                    let priority_color = match priority {
-                        Priority::Low => egui::Color32::from_rgb(76, 175, 80),      // 绿色
-                        Priority::Medium => egui::Color32::from_rgb(255, 193, 7),    // 黄色
-                        Priority::High => egui::Color32::from_rgb(255, 87, 34),      // 橙色
-                        Priority::Critical => egui::Color32::from_rgb(244, 67, 54),  // 红色
+                    let priority_color = priority_color(&priority);
+                    
+                    // 过期任务使用错误色边框
+                    let card_stroke = if overdue {
+                        egui::Stroke::new(2.0, self.theme.error)
+                    } else {
+                        egui::Stroke::new(1.0, priority_color)
                     };
                     
                     // 绘制任务卡片
-                    egui::Frame::none()
+                    let card = egui::Frame::none()
                         .fill(card_bg)
-                        .stroke(egui::Stroke::new(1.0, priority_color))
+                        .stroke(card_stroke)
                         .rounding(egui::Rounding::same(4.0))
                         .inner_margin(egui::Margin::same(8.0))
                         .show(ui, |ui| {
+                            // 被阻塞的任务使用暗淡的文字
+                            let blocker_titles: Vec<String> = self.todo_list.todos
+                                .get(&id)
+                                .map(|t| self.todo_list.open_blockers(t).iter().map(|b| b.title.clone()).collect())
+                                .unwrap_or_default();
+                            if !blocker_titles.is_empty() {
+                                ui.visuals_mut().override_text_color = Some(self.theme.text_secondary.linear_multiply(0.6));
+                            }
+                            
                             ui.horizontal(|ui| {
-                                // 完成状态勾选框
-                                let mut is_completed = completed;
-                                if ui.checkbox(&mut is_completed, "").clicked() {
-                                    if let Some(t) = self.todo_list.todos.get_mut(&id) {
-                                        // 使用新的set_completed方法
-                                        t.set_completed(is_completed);
-                                        self.modified = true;
+                                // 拖动手柄：拖到其他任务上转为其子任务；手动排序时还可调整顺序，获得焦点时可用方向键移动
+                                {
+                                    let hint = if manual_sort {
+                                        "拖动调整顺序，拖到其他任务中间转为其子任务，选中后可用 ↑/↓ 键移动"
+                                    } else {
+                                        "拖到其他任务上转为其子任务"
+                                    };
+                                    let handle = ui.push_id(("drag_handle", &id), |ui| {
+                                        ui.add(egui::Button::new("☰").small().frame(false).sense(egui::Sense::click_and_drag()))
+                                    }).inner.on_hover_text(hint);
+                                    
+                                    if handle.drag_started() {
+                                        self.dragging_todo_id = Some(id.clone());
+                                    }
+                                    
+                                    if handle.clicked() {
+                                        handle.request_focus();
+                                    }
+                                    
+                                    if manual_sort && handle.has_focus() {
+                                        let (up, down) = ui.input(|i| (i.key_pressed(egui::Key::ArrowUp), i.key_pressed(egui::Key::ArrowDown)));
+                                        let index = visible_ids.iter().position(|v| *v == id).unwrap_or(0);
+                                        if up && index > 0 {
+                                            move_request = Some((id.clone(), visible_ids[index - 1].clone(), false));
+                                        } else if down && index + 1 < visible_ids.len() {
+                                            move_request = Some((id.clone(), visible_ids[index + 1].clone(), true));
+                                        }
                                     }
                                 }
                                 
-                                // 任务内容区域
-                                ui.vertical(|ui| {
-                                    // 标题行（包含表情符号和标题）
-                                    ui.horizontal(|ui| {
-                                        // 表情符号
+                                // 多选勾选框，按住 Shift 时选择与上次点选之间的所有任务
+                                if self.selection_mode {
+                                    let mut is_selected = self.selected_todos.contains(&id);
+                                    if ui.checkbox(&mut is_selected, "").on_hover_text("选择").clicked() {
+                                        let anchor = self.selection_anchor.as_ref().and_then(|a| visible_ids.iter().position(|v| v == a));
+                                        let index = visible_ids.iter().position(|v| *v == id).unwrap_or(0);
+                                        let range = match anchor {
+                                            Some(a) if ui.input(|input| input.modifiers.shift) => a.min(index)..=a.max(index),
+                                            _ => index..=index,
+                                        };
+                                        for selected_id in &visible_ids[range] {
+                                            if is_selected {
+                                                self.selected_todos.insert(selected_id.clone());
+                                            } else {
+                                                self.selected_todos.remove(selected_id);
+                                            }
+                                        }
+                                        self.selection_anchor = Some(id.clone());
+                                    }
+                                    ui.separator();
+                                }
+                                
+                                // 完成状态勾选框
+                                let mut is_completed = completed;
+                                if ui.checkbox(&mut is_completed, "").clicked() {
+                                    // 统一设置完成状态，以便生成重复任务的下一次实例并检查前置任务
+                                    self.toggle_todo_completed(&id, is_completed);
+                                }
+                                
+                                // 任务内容区域
+                                ui.vertical(|ui| {
+                                    // 标题行（包含表情符号和标题）
+                                    ui.horizontal(|ui| {
+                                        // 表情符号
                                         if !emoji.is_empty() {
                                             ui.label(&emoji);
                                         }
@@ -468,15 +1923,21 @@ impl RodoApp {
                                         }
                                     });
                                     
-                                    // 如果有描述，尝试获取
+                                    // 如果有描述，显示首行摘要，展开后渲染Markdown
                                     if let Some(todo) = self.todo_list.todos.get(&id) {
-                                        if !todo.description.is_empty() {
-                                            let desc = if todo.description.chars().count() > 50 {
-                                                truncate_string(&todo.description, 47)
+                                        if !todo.description.trim().is_empty() {
+                                            let first_line = todo.description.lines().find(|l| !l.trim().is_empty()).unwrap_or_default();
+                                            let desc = if first_line.chars().count() > 50 {
+                                                truncate_string(first_line, 47)
                                             } else {
-                                                todo.description.clone()
+                                                first_line.to_string()
                                             };
-                                            ui.label(RichText::new(desc).italics().small());
+                                            egui::CollapsingHeader::new(RichText::new(desc).italics().small())
+                                                .id_source(("description", &id))
+                                                .show(ui, |ui| {
+                                                    let is_dark = ui.visuals().dark_mode;
+                                                    markdown::render_markdown(ui, &todo.description, is_dark);
+                                                });
                                         }
                                     }
                                     
@@ -495,20 +1956,67 @@ impl RodoApp {
                                             let completed_text = format!("完成: {}", Todo::format_date_time(&completed_time));
                                             ui.label(RichText::new(completed_text).color(date_color).small());
                                         }
+                                        
+                                        // 截止时间，过期时使用错误色
+                                        if let Some(due) = due_date {
+                                            ui.add_space(8.0);
+                                            let due_text = if overdue {
+                                                RichText::new(format!("⚠ 已过期: {}", Todo::format_due_date(&due))).color(self.theme.error).small().strong()
+                                            } else {
+                                                RichText::new(format!("截止: {}", Todo::format_due_date(&due))).color(date_color).small()
+                                            };
+                                            ui.label(due_text);
+                                        }
+                                        
+                                        // 重复规则
+                                        if let Some(rule) = self.todo_list.todos.get(&id).and_then(|t| t.recurrence.as_ref()) {
+                                            ui.add_space(8.0);
+                                            ui.label(RichText::new(format!("🔁 {}", rule.describe())).color(date_color).small());
+                                        }
+                                        
+                                        // 推迟时间
+                                        if let Some(until) = self.todo_list.todos.get(&id).filter(|t| t.is_deferred(Local::now())).and_then(|t| t.defer_until) {
+                                            ui.add_space(8.0);
+                                            ui.label(RichText::new(format!("💤 推迟至: {}", Todo::format_date_time(&until))).color(date_color).small());
+                                        }
+                                        
+                                        // 预计用时
+                                        if let Some(minutes) = self.todo_list.todos.get(&id).and_then(|t| t.estimate_minutes) {
+                                            ui.add_space(8.0);
+                                            ui.label(RichText::new(format!("⏳ {}", format_duration(Duration::minutes(minutes)))).color(date_color).small())
+                                                .on_hover_text("预计用时");
+                                        }
+                                        
+                                        // 提醒
+                                        if let Some(t) = self.todo_list.todos.get(&id).filter(|t| !t.reminders.is_empty()) {
+                                            let descriptions: Vec<String> = t.reminders.iter().map(|r| r.describe()).collect();
+                                            ui.add_space(8.0);
+                                            ui.label(RichText::new(format!("⏰ {}", t.reminders.len())).color(date_color).small())
+                                                .on_hover_text(descriptions.join("\n"));
+                                        }
                                     });
                                     
+                                    // 显示阻塞此任务的前置任务
+                                    if !blocker_titles.is_empty() {
+                                        ui.label(RichText::new(format!("⛔ 被阻塞: {}", blocker_titles.join("、"))).small());
+                                    }
+                                    
                                     // 显示标签（如果有）
                                     if !tags.is_empty() {
                                         ui.horizontal(|ui| {
                                             for tag in &tags {
-                                                let tag_button = ui.button(format!("🏷️ {}", tag));
+                                                let tag_button = ui.button(format!("🏷️ {}", tag))
+                                                    .on_hover_text("点击按此标签筛选，右键排除此标签");
                                                 if tag_button.clicked() {
                                                     // 检查标签是否已经在活跃标签中
                                                     let is_active = self.todo_list.active_tags.contains(tag);
                                                     if !is_active {
-                                                        self.todo_list.active_tags.push(tag.clone());
+                                                        self.todo_list.include_tag(tag);
                                                         self.modified = true;
                                                     }
+                                                } else if tag_button.secondary_clicked() && !self.todo_list.excluded_tags.contains(tag) {
+                                                    self.todo_list.exclude_tag(tag);
+                                                    self.modified = true;
                                                 }
                                             }
                                         });
@@ -516,7 +2024,8 @@ impl RodoApp {
                                     
                                     // 显示子任务进度（如果有子任务）
                                     if total_subtasks > 0 {
-                                        ui.label(format!("子任务: {}/{}", completed_subtasks, total_subtasks));
+                                        let progress = self.todo_list.todos.get(&id).map(|t| t.completion_percentage()).unwrap_or(0.0);
+                                        ui.label(format!("子任务: {}/{} ({:.0}%)", completed_subtasks, total_subtasks, progress * 100.0));
                                     }
                                 });
                                 
@@ -530,13 +2039,95 @@ impl RodoApp {
                                     };
                                     
                                     ui.label(RichText::new(priority_text).color(priority_color).small());
+                                    
+                                    // 推迟任务
+                                    if !completed {
+                                        let deferred = self.todo_list.todos.get(&id).is_some_and(|t| t.is_deferred(Local::now()));
+                                        let mut choice = None;
+                                        ui.menu_button("💤", |ui| choice = snooze_menu(ui, deferred, &mut self.snooze_pick))
+                                            .response
+                                            .on_hover_text("推迟任务，到时间之前不在列表中显示");
+                                        if let Some(until) = choice {
+                                            self.snooze_todos(std::slice::from_ref(&id), until);
+                                        }
+                                    }
+                                    
+                                    // 工作流状态
+                                    let status = self.todo_list.todos.get(&id).and_then(|t| self.todo_list.status_of(t));
+                                    if let Some(status) = status {
+                                        let status_color = if status.terminal {
+                                            self.theme.success
+                                        } else {
+                                            self.theme.text_secondary
+                                        };
+                                        ui.label(RichText::new(&status.name).color(status_color).small());
+                                    }
                                 });
                             });
                         });
+                    row_rects.push((id.clone(), card.response.rect));
                     
                     ui.add_space(4.0);
                 }
+                
+                // 拖放：放在其他任务卡片中间时转为其子任务，手动排序时放在卡片边缘调整顺序
+                if let Some(dragging_id) = self.dragging_todo_id.clone() {
+                    let pointer = ui.ctx().pointer_hover_pos();
+                    let released = ui.input(|i| i.pointer.any_released());
+                    
+                    // 指针位于其他任务卡片中间用于转为子任务的区域时的目标卡片，
+                    // 不论是否手动排序都只看中间区域，避免短距离的误拖动把任务转为子任务
+                    let nest_target = pointer.and_then(|pos| {
+                        row_rects
+                            .iter()
+                            .find(|(target_id, rect)| *target_id != dragging_id && rect.contains(pos))
+                            .filter(|(_, rect)| (pos.y - rect.center().y).abs() < rect.height() / 4.0)
+                            .map(|(target_id, rect)| (target_id.clone(), *rect))
+                    });
+                    
+                    if let Some((target_id, rect)) = nest_target {
+                        ui.painter().rect_stroke(rect.expand(2.0), egui::Rounding::same(4.0), egui::Stroke::new(2.0, self.theme.accent));
+                        
+                        if released {
+                            demote_request = Some((dragging_id.clone(), target_id));
+                        }
+                    } else if manual_sort {
+                        let target = pointer.and_then(|pos| {
+                            row_rects
+                                .iter()
+                                .find(|(_, rect)| pos.y <= rect.bottom())
+                                .or(row_rects.last())
+                                .map(|(target_id, rect)| (target_id.clone(), *rect, pos.y > rect.center().y))
+                        });
+                        
+                        if let Some((target_id, rect, after)) = target {
+                            let y = if after { rect.bottom() + 2.0 } else { rect.top() - 2.0 };
+                            ui.painter().hline(rect.x_range(), y, egui::Stroke::new(2.0, self.theme.accent));
+                            
+                            if released {
+                                move_request = Some((dragging_id, target_id, after));
+                            }
+                        }
+                    }
+                    
+                    if !ui.input(|i| i.pointer.any_down()) {
+                        self.dragging_todo_id = None;
+                    }
+                }
             });
+            
+            if let Some((id, target_id, after)) = move_request {
+                self.history.label_next("调整顺序", false);
+                self.todo_list.move_todo(&id, &target_id, after);
+                self.modified = true;
+            }
+            
+            if let Some((id, target_id)) = demote_request {
+                if let Err(err) = self.request_demote_todo(&id, &target_id) {
+                    eprintln!("转为子任务失败: {}", err);
+                    self.show_notice(&format!("转为子任务失败: {}", err));
+                }
+            }
         }
         
         // 添加浮动的添加任务按钮
@@ -600,7 +2191,25 @@ impl RodoApp {
     fn render_add_todo(&mut self, ui: &mut Ui) {
         // 添加滚动区域，确保所有编辑字段都可见
         ScrollArea::vertical().show(ui, |ui| {
-            ui.heading(if self.editing_todo_id.is_some() { "编辑任务" } else { "添加任务" });
+            ui.horizontal(|ui| {
+                ui.heading(if self.editing_todo_id.is_some() { "编辑任务" } else { "添加任务" });
+                
+                // 新任务可以从模板填写
+                if self.editing_todo_id.is_none() && !self.templates.templates.is_empty() {
+                    let mut chosen = None;
+                    ui.menu_button("📑 从模板创建", |ui| {
+                        for template in &self.templates.templates {
+                            if ui.button(&template.name).on_hover_text(&template.title).clicked() {
+                                chosen = Some(template.id.clone());
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                    if let Some(id) = chosen {
+                        self.add_todo_from_template(&id);
+                    }
+                }
+            });
             ui.separator();
             
             ui.add_space(16.0);
@@ -665,9 +2274,20 @@ impl RodoApp {
             
             ui.add_space(8.0);
             
-            // 完成状态
+            // 工作流状态和完成状态
             ui.horizontal(|ui| {
-                ui.checkbox(&mut self.new_todo.completed, "标记为完成");
+                ui.label("状态:");
+                let mut status_id = self.todo_list.status_of(&self.new_todo).map(|s| s.id.clone()).unwrap_or_default();
+                if status_selector(ui, "new_todo_status", &self.todo_list.statuses, &mut status_id) {
+                    self.new_todo.completed = self.todo_list.status(&status_id).map(|s| s.terminal).unwrap_or(false);
+                    self.new_todo.status = status_id;
+                }
+                
+                ui.add_space(8.0);
+                
+                if ui.checkbox(&mut self.new_todo.completed, "标记为完成").changed() {
+                    self.new_todo.status = self.todo_list.completion_status_id(&self.new_todo, self.new_todo.completed);
+                }
                 
                 // 如果是编辑现有任务，显示创建/完成时间
                 if self.editing_todo_id.is_some() {
@@ -689,18 +2309,38 @@ impl RodoApp {
             
             ui.add_space(8.0);
             
+            // 截止时间
+            if due_date_editor(ui, &mut self.new_todo.due_date) {
+                self.modified = true;
+            }
+            
+            // 推迟时间
+            if defer_editor(ui, &mut self.new_todo.defer_until) {
+                self.modified = true;
+            }
+            
+            // 预计用时
+            if estimate_editor(ui, &mut self.new_todo.estimate_minutes) {
+                self.modified = true;
+            }
+            
+            // 重复规则
+            if recurrence_editor(ui, &mut self.new_todo.recurrence) {
+                self.modified = true;
+            }
+            
+            // 提醒
+            if reminders_editor(ui, &mut self.new_todo.reminders, self.new_todo.due_date) {
+                self.modified = true;
+            }
+            
+            ui.add_space(8.0);
+            
             // 任务描述
-            ui.horizontal(|ui| {
-                ui.label("描述:");
-                ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
-                    ui.add(
-                        egui::TextEdit::multiline(&mut self.new_todo.description)
-                            .hint_text("任务描述（可选）")
-                            .desired_width(f32::INFINITY)
-                            .desired_rows(3)
-                    );
-                });
-            });
+            ui.label("描述:");
+            if description_editor(ui, &mut self.new_todo.description, &mut self.description_preview) {
+                self.modified = true;
+            }
             
             ui.add_space(8.0);
             
@@ -777,57 +2417,25 @@ impl RodoApp {
             
             ui.add_space(16.0);
             
+            // 前置任务
+            ui.label("前置任务:");
+            let new_todo_id = self.new_todo.id.clone();
+            if dependency_editor(ui, &self.todo_list, &new_todo_id, &mut self.new_todo.blocked_by) {
+                self.modified = true;
+            }
+            
+            ui.add_space(16.0);
+            
             // 子任务编辑
             ui.collapsing("子任务", |ui| {
-                // 使用状态变量避免借用冲突
-                let mut subtask_index_to_remove = None;
-                let mut subtask_index_to_toggle = None;
-                
-                // 显示现有子任务
-                for (i, subtask) in self.new_todo.subtasks.iter().enumerate() {
-                    ui.horizontal(|ui| {
-                        let mut completed = subtask.completed;
-                        if ui.checkbox(&mut completed, "").clicked() {
-                            subtask_index_to_toggle = Some(i);
-                        }
-                        
-                        ui.label(&subtask.title);
-                        
-                        ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
-                            if ui.small_button("删除").clicked() {
-                                subtask_index_to_remove = Some(i);
-                            }
-                        });
-                    });
-                }
-                
-                // 处理子任务状态变更
-                if let Some(index) = subtask_index_to_toggle {
-                    let mut subtask = self.new_todo.subtasks[index].clone();
-                    subtask.completed = !subtask.completed;
-                    self.new_todo.subtasks[index] = subtask;
+                if subtask_tree_editor(ui, &mut self.new_todo.subtasks, &mut self.subtask_parent_id, false, None) {
                     self.modified = true;
                 }
                 
-                // 处理子任务删除
-                if let Some(index) = subtask_index_to_remove {
-                    self.new_todo.subtasks.remove(index);
+                // 添加新子任务
+                if subtask_add_row(ui, &mut self.new_todo.subtasks, &mut self.subtask_parent_id, &mut self.temp_input) {
                     self.modified = true;
                 }
-                
-                // 添加新子任务
-                ui.horizontal(|ui| {
-                    ui.label("新子任务:");
-                    ui.add(egui::TextEdit::singleline(&mut self.temp_input).hint_text("子任务标题"));
-                    
-                    let can_add = !self.temp_input.trim().is_empty();
-                    
-                    if ui.add_enabled(can_add, egui::Button::new("添加")).clicked() {
-                        self.new_todo.subtasks.push(SubTask::new(self.temp_input.trim().to_string()));
-                        self.temp_input.clear();
-                        self.modified = true;
-                    }
-                });
             });
             
             ui.add_space(16.0);
@@ -840,6 +2448,7 @@ impl RodoApp {
                     self.new_todo = Todo::new("".to_string());
                     self.temp_tag_input.clear();
                     self.temp_input.clear();
+                    self.subtask_parent_id = None;
                     self.editing_todo_id = None;
                 }
                 
@@ -849,34 +2458,35 @@ impl RodoApp {
                 if ui.add_enabled(can_save, egui::Button::new("保存")).clicked() {
                     let mut todo = self.new_todo.clone();
                     
+                    // 状态通过列表统一设置，这会自动处理完成时间和重复任务
+                    let status_id = self.todo_list.completion_status_id(&todo, todo.completed);
+                    
                     if self.editing_todo_id.is_none() {
                         // 创建新任务
+                        self.history.label_next("添加任务", false);
                         todo.id = format!("todo-{}", Uuid::new_v4());
                         todo.created_at = chrono::Local::now();
-                        // 设置完成状态，这会自动处理完成时间
-                        if todo.completed {
-                            todo.set_completed(true);
-                        }
-                        self.todo_list.todos.insert(todo.id.clone(), todo);
-                    } else if let Some(todo_id) = &self.editing_todo_id {
+                        todo.completed = false;
+                        let todo_id = todo.id.clone();
+                        self.todo_list.todos.insert(todo_id.clone(), todo);
+                        self.todo_list.set_todo_status(&todo_id, &status_id);
+                    } else if let Some(todo_id) = self.editing_todo_id.clone() {
                         // 更新现有任务
-                        if let Some(existing_todo) = self.todo_list.todos.get_mut(todo_id) {
-                            // 保留创建时间
-                            todo.created_at = existing_todo.created_at.clone();
-                            // 保留完成时间，只有状态改变时才更新
-                            if todo.completed != existing_todo.completed {
-                                todo.set_completed(todo.completed);
-                            } else {
-                                todo.completed_at = existing_todo.completed_at.clone();
-                            }
+                        if let Some(existing_todo) = self.todo_list.todos.get_mut(&todo_id) {
+                            // 保留创建时间和完成时间，只有状态改变时才更新
+                            todo.created_at = existing_todo.created_at;
+                            todo.completed = existing_todo.completed;
+                            todo.completed_at = existing_todo.completed_at;
                             *existing_todo = todo;
                         }
+                        self.todo_list.set_todo_status(&todo_id, &status_id);
                     }
                     
                     self.view = View::List;
                     self.new_todo = Todo::new("".to_string());
                     self.temp_tag_input.clear();
                     self.temp_input.clear();
+                    self.subtask_parent_id = None;
                     self.editing_todo_id = None;
                     self.modified = true;
                 }
@@ -944,14 +2554,21 @@ impl RodoApp {
             
             ui.add_space(8.0);
             
-            // 完成状态
-            let mut completed = todo.completed;
-            if ui.checkbox(&mut completed, "标记为完成").clicked() {
-                if let Some(t) = self.todo_list.todos.get_mut(&editing_id) {
-                    t.set_completed(completed);
-                    self.modified = true;
+            // 工作流状态和完成状态
+            ui.horizontal(|ui| {
+                ui.label("状态:");
+                let mut status_id = self.todo_list.status_of(&todo).map(|s| s.id.clone()).unwrap_or_default();
+                if status_selector(ui, "edit_todo_status", &self.todo_list.statuses, &mut status_id) {
+                    self.change_todo_status(&editing_id, &status_id);
                 }
-            }
+                
+                ui.add_space(8.0);
+                
+                let mut completed = todo.completed;
+                if ui.checkbox(&mut completed, "标记为完成").clicked() {
+                    self.toggle_todo_completed(&editing_id, completed);
+                }
+            });
             
             ui.add_space(8.0);
             
@@ -972,6 +2589,76 @@ impl RodoApp {
             
             ui.add_space(12.0);
             
+            // 截止时间
+            let mut due_date = todo.due_date;
+            if due_date_editor(ui, &mut due_date) {
+                if let Some(t) = self.todo_list.todos.get_mut(&editing_id) {
+                    t.due_date = due_date;
+                    self.modified = true;
+                }
+            }
+            
+            if todo.is_overdue() {
+                ui.label(RichText::new("⚠ 此任务已过期").color(self.theme.error));
+            }
+            
+            // 推迟时间
+            let mut defer_until = todo.defer_until;
+            if defer_editor(ui, &mut defer_until) {
+                if let Some(t) = self.todo_list.todos.get_mut(&editing_id) {
+                    t.defer_until = defer_until;
+                    self.modified = true;
+                }
+            }
+            
+            // 预计用时
+            let mut estimate_minutes = todo.estimate_minutes;
+            if estimate_editor(ui, &mut estimate_minutes) {
+                if let Some(t) = self.todo_list.todos.get_mut(&editing_id) {
+                    t.estimate_minutes = estimate_minutes;
+                    self.modified = true;
+                }
+            }
+            
+            // 重复规则
+            let mut recurrence = todo.recurrence.clone();
+            if recurrence_editor(ui, &mut recurrence) {
+                if let Some(t) = self.todo_list.todos.get_mut(&editing_id) {
+                    t.recurrence = recurrence;
+                    self.modified = true;
+                }
+            }
+            
+            // 提醒
+            let mut reminders = todo.reminders.clone();
+            if reminders_editor(ui, &mut reminders, due_date) {
+                if let Some(t) = self.todo_list.todos.get_mut(&editing_id) {
+                    t.reminders = reminders;
+                    self.modified = true;
+                }
+            }
+            
+            // 重复任务的历史完成记录
+            if let Some(series_id) = &todo.series_id {
+                let history = self.todo_list.series_history(series_id);
+                if !history.is_empty() {
+                    let date_color = self.theme.text_secondary;
+                    ui.collapsing(format!("重复历史 ({})", history.len()), |ui| {
+                        for item in history {
+                            let due_text = item.due_date
+                                .map(|d| format!("截止 {}", d.format("%Y-%m-%d %H:%M")))
+                                .unwrap_or_default();
+                            let done_text = item.completed_at
+                                .map(|d| format!("完成于 {}", d.format("%Y-%m-%d %H:%M")))
+                                .unwrap_or_default();
+                            ui.label(RichText::new(format!("✔ {}  {}", done_text, due_text)).color(date_color).small());
+                        }
+                    });
+                }
+            }
+            
+            ui.add_space(12.0);
+            
             // 优先级选择
             ui.horizontal(|ui| {
                 ui.label("优先级:");
@@ -1013,7 +2700,7 @@ impl RodoApp {
             // 任务描述
             ui.label("任务描述");
             let mut description = todo.description.clone();
-            if ui.text_edit_multiline(&mut description).changed() {
+            if description_editor(ui, &mut description, &mut self.description_preview) {
                 if let Some(t) = self.todo_list.todos.get_mut(&editing_id) {
                     t.description = description;
                     self.modified = true;
@@ -1118,64 +2805,92 @@ impl RodoApp {
             
             ui.add_space(12.0);
             
+            // 时间记录
+            ui.horizontal(|ui| {
+                ui.label(format!("已记录时间: {}", format_duration(todo.tracked_duration())));
+                
+                let running = todo.running_entry().is_some();
+                let button_text = if running { "⏸ 停止计时" } else { "▶ 开始计时" };
+                if ui.button(button_text).clicked() {
+                    if running {
+                        if let Some(t) = self.todo_list.todos.get_mut(&editing_id) {
+                            t.stop_timer();
+                        }
+                    } else {
+                        self.todo_list.start_timer(&editing_id);
+                    }
+                    self.modified = true;
+                }
+            });
+            
+            ui.collapsing(format!("时间记录 ({})", todo.time_entries.len()), |ui| {
+                let mut entries = todo.time_entries.clone();
+                if time_entries_editor(ui, &mut entries) {
+                    if let Some(t) = self.todo_list.todos.get_mut(&editing_id) {
+                        t.time_entries = entries;
+                        self.modified = true;
+                    }
+                }
+            });
+            
+            ui.add_space(12.0);
+            
+            // 前置任务
+            ui.label("前置任务");
+            let mut blocked_by = todo.blocked_by.clone();
+            if dependency_editor(ui, &self.todo_list, &editing_id, &mut blocked_by) {
+                if let Some(t) = self.todo_list.todos.get_mut(&editing_id) {
+                    t.blocked_by = blocked_by;
+                    self.modified = true;
+                }
+            }
+            
+            ui.add_space(12.0);
+            
             // 子任务管理
             ui.collapsing("子任务", |ui| {
                 let mut subtasks = todo.subtasks.clone();
-                let mut tasks_to_remove = Vec::new();
+                let mut promote_request = None;
                 
-                // 显示现有子任务
-                for (i, subtask) in subtasks.iter_mut().enumerate() {
-                    ui.horizontal(|ui| {
-                        if ui.checkbox(&mut subtask.completed, "").clicked() {
-                            self.modified = true;
-                        }
-                        
-                        let mut title = subtask.title.clone();
-                        if ui.text_edit_singleline(&mut title).changed() {
-                            subtask.title = title;
-                            self.modified = true;
-                        }
-                        
-                        if ui.small_button("❌").clicked() {
-                            tasks_to_remove.push(i);
-                            self.modified = true;
-                        }
-                    });
-                }
+                let mut changed = subtask_tree_editor(ui, &mut subtasks, &mut self.subtask_parent_id, true, Some(&mut promote_request));
                 
-                // 移除标记的子任务
-                for i in tasks_to_remove.iter().rev() {
-                    subtasks.remove(*i);
-                }
+                // 添加新子任务
+                changed |= subtask_add_row(ui, &mut subtasks, &mut self.subtask_parent_id, &mut self.temp_input);
                 
                 // 更新子任务
-                if subtasks != todo.subtasks {
+                if changed {
                     if let Some(t) = self.todo_list.todos.get_mut(&editing_id) {
                         t.subtasks = subtasks;
+                        self.modified = true;
                     }
                 }
                 
-                // 添加新子任务
-                ui.horizontal(|ui| {
-                    ui.label("新子任务:");
-                    if ui.text_edit_singleline(&mut self.temp_input).lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-                        if !self.temp_input.trim().is_empty() {
-                            if let Some(t) = self.todo_list.todos.get_mut(&editing_id) {
-                                t.subtasks.push(SubTask::new(self.temp_input.trim().to_string()));
-                                self.modified = true;
-                                self.temp_input.clear();
-                            }
-                        }
-                    }
+                match promote_request {
+                    Some(PromoteRequest::Clicked(subtask_id)) => self.promote_subtask(&editing_id, &subtask_id),
+                    Some(PromoteRequest::DragStarted(subtask_id)) => self.dragging_subtask_id = Some(subtask_id),
+                    None => {}
+                }
+                
+                // 拖动子任务时显示放置区域，松开鼠标时转为任务
+                if let Some(subtask_id) = self.dragging_subtask_id.clone() {
+                    let frame = egui::Frame::none()
+                        .stroke(egui::Stroke::new(2.0, self.theme.accent))
+                        .rounding(egui::Rounding::same(4.0))
+                        .inner_margin(egui::Margin::same(12.0))
+                        .show(ui, |ui| {
+                            ui.set_width(ui.available_width());
+                            ui.label(RichText::new("⬆ 拖放到此处转为独立任务").color(self.theme.accent));
+                        });
                     
-                    if ui.button("添加").clicked() && !self.temp_input.trim().is_empty() {
-                        if let Some(t) = self.todo_list.todos.get_mut(&editing_id) {
-                            t.subtasks.push(SubTask::new(self.temp_input.trim().to_string()));
-                            self.modified = true;
-                            self.temp_input.clear();
-                        }
+                    let released = ui.input(|i| i.pointer.any_released());
+                    let hovered = ui.ctx().pointer_hover_pos().is_some_and(|pos| frame.response.rect.contains(pos));
+                    if released && hovered {
+                        self.promote_subtask(&editing_id, &subtask_id);
                     }
-                });
+                    if !ui.input(|i| i.pointer.any_down()) {
+                        self.dragging_subtask_id = None;
+                    }
+                }
                 
                 ui.add_space(4.0);
             });
@@ -1190,11 +2905,42 @@ impl RodoApp {
                 
                 if ui.button("删除").clicked() {
                     self.show_confirm(
-                        "确定要删除此任务吗？",
+                        "确定要删除此任务吗？任务将移入回收站。",
                         ConfirmationAction::DeleteTodo(editing_id.clone()),
                     );
                 }
                 
+                // 转为其他任务的子任务
+                ui.menu_button("⬇ 转为子任务", |ui| {
+                    let mut targets: Vec<(String, String)> = self.todo_list.todos.values()
+                        .filter(|t| t.id != editing_id)
+                        .map(|t| (t.id.clone(), t.title.clone()))
+                        .collect();
+                    targets.sort_by(|a, b| a.1.cmp(&b.1));
+                    
+                    if targets.is_empty() {
+                        ui.label("没有其他任务");
+                    }
+                    ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        for (target_id, title) in targets {
+                            if ui.button(truncate_string(&title, 40)).clicked() {
+                                if let Err(err) = self.request_demote_todo(&editing_id, &target_id) {
+                                    eprintln!("转为子任务失败: {}", err);
+                                    self.show_notice(&format!("转为子任务失败: {}", err));
+                                }
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                }).response.on_hover_text("将此任务及其子任务放入另一个任务的子任务中");
+                
+                if ui.button("📑 保存为模板").on_hover_text("在设置页面中管理模板").clicked() {
+                    if let Err(err) = self.save_todo_as_template(&editing_id) {
+                        eprintln!("保存任务模板失败: {}", err);
+                        self.show_notice(&format!("保存任务模板失败: {}", err));
+                    }
+                }
+                
                 ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
                     if ui.button("保存").clicked() {
                         self.view = View::List;
@@ -1576,132 +3322,1108 @@ impl RodoApp {
                     }
                 });
             });
-        }
-        
-        ui.add_space(16.0);
+        }
+        
+        ui.add_space(16.0);
+        
+        // 应用风格设置
+        ui.heading("应用风格");
+        ui.add_space(8.0);
+        
+        ui.horizontal_wrapped(|ui| {
+            let style_options = [
+                (crate::app::AppStyle::Modern, "现代", Color32::from_rgb(66, 133, 244)),
+                (crate::app::AppStyle::Minimal, "简约", Color32::from_rgb(50, 50, 60)),
+                (crate::app::AppStyle::Classic, "经典", Color32::from_rgb(180, 120, 80)),
+                (crate::app::AppStyle::Retro, "复古", Color32::from_rgb(246, 190, 0)),
+                (crate::app::AppStyle::Business, "商务", Color32::from_rgb(20, 80, 120)),
+            ];
+            
+            for (style_type, name, color) in &style_options {
+                let is_selected = &self.app_style == style_type;
+                
+                let mut button = Button::new(*name);
+                if is_selected {
+                    button = button.fill(*color).stroke(egui::Stroke::new(2.0, self.theme.accent));
+                } else {
+                    button = button.fill(Color32::from_rgba_premultiplied(
+                        color.r(), color.g(), color.b(), 40
+                    )).stroke(egui::Stroke::new(1.0, *color));
+                }
+                
+                // 添加圆角和内边距
+                button = button.rounding(egui::Rounding::same(8.0));
+                
+                if ui.add_sized(Vec2::new(100.0, 40.0), button).clicked() && !is_selected {
+                    // 改变应用风格并保存
+                    if let Err(e) = self.set_app_style(style_type.clone()) {
+                        eprintln!("保存应用风格失败: {}", e);
+                    }
+                }
+            }
+        });
+        
+        ui.add_space(8.0);
+        
+        // 风格说明
+        let style_desc = match self.app_style {
+            crate::app::AppStyle::Modern => "现代风格：圆角按钮，柔和阴影，现代感的界面元素",
+            crate::app::AppStyle::Minimal => "简约风格：扁平化设计，简洁线条，最小装饰元素",
+            crate::app::AppStyle::Classic => "经典风格：传统窗口界面，中等圆角，标准按钮",
+            crate::app::AppStyle::Retro => "复古风格：像素感，强烈边框，鲜明对比色",
+            crate::app::AppStyle::Business => "商务风格：严肃专业的外观，清晰的层次结构",
+        };
+        
+        ui.label(RichText::new(style_desc).italics());
+        
+        ui.add_space(16.0);
+        
+        // 数据导入导出区域
+        ui.heading("数据管理");
+        ui.add_space(8.0);
+        
+        ui.horizontal(|ui| {
+            if ui.button("📤 导出任务").clicked() {
+                self.export_todos_dialog();
+            }
+            
+            if ui.button("📥 导入任务").clicked() {
+                self.import_todos_dialog();
+            }
+            
+            if ui.button("📥 合并导入").clicked() {
+                self.merge_todos_dialog();
+            }
+        });
+        
+        ui.add_space(16.0);
+        
+        // 工作流状态设置区域
+        ui.heading("工作流状态");
+        ui.label(RichText::new(format!("当前工作区: {}。标记为终止的状态视为已完成。", self.workspaces.active().name)).small().color(self.theme.text_secondary));
+        ui.add_space(8.0);
+        
+        let statuses = self.todo_list.statuses.clone();
+        let new_name = self.status_name_input.trim().to_string();
+        // 修改结果及其撤销名称，成功后才设置名称，避免失败时名称留给之后无关的修改
+        let mut result: Option<(&str, bool, Result<(), String>)> = None;
+        
+        egui::Grid::new("status_grid").striped(true).num_columns(3).show(ui, |ui| {
+            for (i, status) in statuses.iter().enumerate() {
+                ui.label(&status.name);
+                
+                let mut terminal = status.terminal;
+                if ui.checkbox(&mut terminal, "终止").changed() {
+                    result = Some(("修改状态类别", false, self.todo_list.set_status_terminal(&status.id, terminal)));
+                }
+                
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(i > 0, egui::Button::new("⬆").small()).clicked() {
+                        self.history.label_next("调整状态顺序", false);
+                        self.todo_list.move_status(&status.id, true);
+                        self.modified = true;
+                    }
+                    if ui.add_enabled(i + 1 < statuses.len(), egui::Button::new("⬇").small()).clicked() {
+                        self.history.label_next("调整状态顺序", false);
+                        self.todo_list.move_status(&status.id, false);
+                        self.modified = true;
+                    }
+                    if ui.add_enabled(!new_name.is_empty(), egui::Button::new("重命名").small())
+                        .on_hover_text("使用下方输入的名称")
+                        .clicked()
+                    {
+                        result = Some(("重命名状态", false, self.todo_list.rename_status(&status.id, &new_name)));
+                    }
+                    if ui.small_button(RichText::new("删除").color(self.theme.error)).clicked() {
+                        result = Some(("删除状态", true, self.todo_list.remove_status(&status.id)));
+                    }
+                });
+                ui.end_row();
+            }
+        });
+        
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.status_name_input).hint_text("状态名称").desired_width(160.0));
+            if ui.add_enabled(!new_name.is_empty(), egui::Button::new("添加状态")).clicked() {
+                result = Some(("添加状态", false, self.todo_list.add_status(&new_name, false)));
+            }
+            if ui.add_enabled(!new_name.is_empty(), egui::Button::new("添加终止状态")).clicked() {
+                result = Some(("添加状态", false, self.todo_list.add_status(&new_name, true)));
+            }
+        });
+        
+        match result {
+            Some((label, destructive, Ok(()))) => {
+                self.history.label_next(label, destructive);
+                self.status_name_input.clear();
+                self.modified = true;
+            },
+            Some((_, _, Err(err))) => {
+                eprintln!("修改工作流状态失败: {}", err);
+                self.show_notice(&format!("修改工作流状态失败: {}", err));
+            },
+            None => {},
+        }
+        
+        ui.add_space(16.0);
+        
+        // 任务模板设置区域
+        ui.heading("任务模板");
+        ui.label(RichText::new("在任务编辑页面点击\"保存为模板\"添加模板。标题、描述和子任务中可以使用以下占位符：").small().color(self.theme.text_secondary));
+        for (placeholder, description) in PLACEHOLDERS {
+            ui.label(RichText::new(format!("  {}  {}", placeholder, description)).small().color(self.theme.text_secondary));
+        }
+        ui.add_space(8.0);
+        
+        // 修改先保存在草稿中，点击"保存模板"后才写入文件
+        let mut templates = self.template_draft.take().unwrap_or_else(|| self.templates.clone());
+        if templates.templates.is_empty() {
+            ui.label(RichText::new("还没有任务模板").italics().color(self.theme.text_secondary));
+        }
+        
+        let all_tags = self.todo_list.all_tags();
+        let mut removed = None;
+        let mut instantiate = None;
+        
+        for template in templates.templates.iter_mut() {
+            egui::CollapsingHeader::new(format!("{} {}", template.emoji.as_str(), template.name))
+                .id_source(&template.id)
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("模板名称:");
+                        ui.text_edit_singleline(&mut template.name);
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("任务标题:");
+                        ui.add(egui::TextEdit::singleline(&mut template.title).desired_width(300.0));
+                    });
+                    ui.label("描述:");
+                    ui.add(egui::TextEdit::multiline(&mut template.description).desired_rows(2).desired_width(f32::INFINITY));
+                    
+                    ui.horizontal_wrapped(|ui| {
+                        ui.label("标签:");
+                        let mut removed_tag = None;
+                        for tag in &template.tags {
+                            if ui.small_button(format!("{} ✖", tag)).on_hover_text("移除标签").clicked() {
+                                removed_tag = Some(tag.clone());
+                            }
+                        }
+                        if let Some(tag) = removed_tag {
+                            template.tags.retain(|t| *t != tag);
+                        }
+                        ui.menu_button("➕", |ui| {
+                            let mut added = None;
+                            for tag in all_tags.iter().filter(|t| !template.tags.contains(t)) {
+                                if ui.button(tag).clicked() {
+                                    added = Some(tag.clone());
+                                    ui.close_menu();
+                                }
+                            }
+                            template.tags.extend(added);
+                        });
+                    });
+                    
+                    ui.horizontal(|ui| {
+                        ui.label("优先级:");
+                        for (priority, _, name) in board_priorities() {
+                            let selected = template.priority == priority;
+                            if ui.selectable_label(selected, name).clicked() {
+                                template.priority = priority;
+                            }
+                        }
+                    });
+                    
+                    ui.horizontal(|ui| {
+                        let mut has_due = template.due_offset_days.is_some();
+                        if ui.checkbox(&mut has_due, "截止时间").changed() {
+                            template.due_offset_days = if has_due { Some(0) } else { None };
+                        }
+                        if let Some(days) = &mut template.due_offset_days {
+                            ui.label("创建后");
+                            ui.add(egui::DragValue::new(days).clamp_range(0..=3650).suffix(" 天"));
+                            
+                            let mut hour = template.due_time.hour();
+                            let mut minute = template.due_time.minute();
+                            let hour_changed = ui.add(egui::DragValue::new(&mut hour).clamp_range(0..=23).suffix(" 时")).changed();
+                            let minute_changed = ui.add(egui::DragValue::new(&mut minute).clamp_range(0..=59).suffix(" 分")).changed();
+                            if hour_changed || minute_changed {
+                                if let Some(time) = chrono::NaiveTime::from_hms_opt(hour, minute, 0) {
+                                    template.due_time = time;
+                                }
+                            }
+                        }
+                    });
+                    
+                    estimate_editor(ui, &mut template.estimate_minutes);
+                    
+                    ui.collapsing(format!("子任务 ({})", template.subtasks.len()), |ui| {
+                        subtask_tree_editor(ui, &mut template.subtasks, &mut self.template_subtask_parent, true, None);
+                        subtask_add_row(ui, &mut template.subtasks, &mut self.template_subtask_parent, &mut self.template_subtask_input);
+                    });
+                    
+                    ui.horizontal(|ui| {
+                        if ui.button("用此模板创建任务").clicked() {
+                            instantiate = Some(template.id.clone());
+                        }
+                        if ui.small_button(RichText::new("删除模板").color(self.theme.error)).clicked() {
+                            removed = Some(template.id.clone());
+                        }
+                    });
+                });
+        }
+        
+        if let Some(id) = removed {
+            templates.templates.retain(|t| t.id != id);
+        }
+        
+        let mut save = instantiate.is_some() && templates != self.templates;
+        if templates != self.templates {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new("模板有未保存的修改").small().color(self.theme.warning));
+                if ui.button("保存模板").clicked() {
+                    save = true;
+                }
+                if ui.button("放弃修改").clicked() {
+                    templates = self.templates.clone();
+                }
+            });
+        }
+        
+        if save {
+            if let Err(err) = self.set_templates(templates) {
+                eprintln!("保存任务模板失败: {}", err);
+                self.show_notice(&format!("保存任务模板失败: {}", err));
+            }
+        } else if templates != self.templates {
+            self.template_draft = Some(templates);
+        }
+        
+        // 使用修改后的模板创建任务
+        if let Some(id) = instantiate {
+            self.add_todo_from_template(&id);
+        }
+        
+        ui.add_space(16.0);
+        
+        // 提醒设置区域
+        ui.heading("提醒");
+        ui.add_space(8.0);
+        
+        let mut reminder_settings = self.reminder_settings.clone();
+        ui.checkbox(&mut reminder_settings.desktop_notifications, "发送系统桌面通知");
+        ui.horizontal(|ui| {
+            ui.label("提醒命令:");
+            // 输入框失去焦点（包括按下回车）时才保存
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.reminder_command_input)
+                    .hint_text("例如: paplay ~/bell.oga")
+                    .desired_width(300.0)
+            );
+            if response.lost_focus() {
+                reminder_settings.command = self.reminder_command_input.clone();
+            }
+        });
+        ui.label(RichText::new("命令通过环境变量 RODO_TITLE、RODO_BODY、RODO_TODO_ID 获取提醒内容，输入后按回车保存").small().color(self.theme.text_secondary));
+        
+        if reminder_settings != self.reminder_settings {
+            if let Err(err) = self.set_reminder_settings(reminder_settings) {
+                eprintln!("保存提醒设置失败: {}", err);
+            }
+        }
+        
+        ui.add_space(16.0);
+        
+        ui.heading("其他设置");
+        
+        // 添加关于按钮
+        if ui.button("关于 Rodo").clicked() {
+            self.view = View::About;
+        }
+        
+        ui.add_space(16.0);
+        
+        ui.horizontal(|ui| {
+            if ui.button("返回").clicked() {
+                self.view = View::List;
+            }
+            
+            ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                // 恢复初始状态按钮 - 使用警告色以表示危险操作
+                let reset_button = Button::new(RichText::new("恢复初始状态").color(self.theme.error));
+                if ui.add(reset_button).clicked() {
+                    self.show_confirm(
+                        "确定要恢复初始状态吗？这将清空所有数据并重置所有设置，此操作不可撤销！",
+                        ConfirmationAction::ResetApp,
+                    );
+                }
+                
+                ui.add_space(8.0);
+                
+                if ui.button("重置默认主题").clicked() {
+                    self.show_confirm(
+                        "确定要重置为默认主题吗？",
+                        ConfirmationAction::ResetSettings,
+                    );
+                }
+            });
+        });
+    }
+    
+    /// 渲染统计页面
+    fn render_stats(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.heading("统计");
+            ui.add_space(16.0);
+            ui.label("范围:");
+            for (days, name) in [(7, "7天"), (30, "30天"), (90, "90天")] {
+                ui.selectable_value(&mut self.stats_range_days, days, name);
+            }
+        });
+        ui.separator();
+        
+        let today = Local::now().date_naive();
+        let from = today - Duration::days(self.stats_range_days - 1);
+        
+        let total = self.todo_list.todos.len();
+        let done = self.todo_list.todos.values().filter(|t| t.completed).count();
+        let overdue = self.todo_list.todos.values().filter(|t| t.is_overdue()).count();
+        let average = self.todo_list.average_completion_time(None);
+        
+        // 概览
+        ui.horizontal_wrapped(|ui| {
+            let summary = [
+                ("全部", total.to_string(), self.theme.text),
+                ("未完成", (total - done).to_string(), self.theme.accent),
+                ("已完成", done.to_string(), self.theme.success),
+                ("逾期", overdue.to_string(), if overdue > 0 { self.theme.error } else { self.theme.text }),
+                ("完成率", if total > 0 { format!("{:.0}%", done as f32 * 100.0 / total as f32) } else { "-".to_string() }, self.theme.text),
+                ("平均完成用时", average.map(format_elapsed).unwrap_or_else(|| "-".to_string()), self.theme.text),
+            ];
+            for (name, value, color) in summary {
+                egui::Frame::group(ui.style()).show(ui, |ui| {
+                    ui.set_min_width(90.0);
+                    ui.vertical(|ui| {
+                        ui.label(RichText::new(value).size(20.0).strong().color(color));
+                        ui.label(RichText::new(name).small().color(self.theme.text_secondary));
+                    });
+                });
+            }
+        });
+        
+        ui.add_space(8.0);
+        
+        ScrollArea::vertical()
+            .id_source("stats_scroll_area")
+            .show(ui, |ui| {
+                // 完成数量
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new("完成数量").strong());
+                    ui.add_space(8.0);
+                    ui.selectable_value(&mut self.stats_weekly, false, "按天");
+                    ui.selectable_value(&mut self.stats_weekly, true, "按周");
+                });
+                let bars: Vec<(String, usize)> = if self.stats_weekly {
+                    self.todo_list.completions_by_week(from, today)
+                        .into_iter()
+                        .map(|(week, count)| (format!("{}周", week.format("%m-%d")), count))
+                        .collect()
+                } else {
+                    self.todo_list.completions_by_day(from, today)
+                        .into_iter()
+                        .map(|(day, count)| (day.format("%m-%d").to_string(), count))
+                        .collect()
+                };
+                bar_chart(ui, &bars, self.theme.success, 160.0);
+                
+                ui.add_space(12.0);
+                
+                // 未完成/已完成走势
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new("走势").strong());
+                    ui.add_space(8.0);
+                    ui.label(RichText::new("━ 未完成").color(self.theme.accent));
+                    ui.label(RichText::new("━ 已完成").color(self.theme.success));
+                });
+                let trend = self.todo_list.open_done_trend(from, today);
+                trend_chart(ui, &trend, self.theme.accent, self.theme.success, 160.0);
+                
+                ui.add_space(12.0);
+                
+                // 逾期和平均用时按优先级统计
+                ui.label(RichText::new("按优先级").strong());
+                egui::Grid::new("stats_by_priority").striped(true).show(ui, |ui| {
+                    ui.label("优先级");
+                    ui.label("逾期");
+                    ui.label("平均完成用时");
+                    ui.end_row();
+                    
+                    for (priority, count) in self.todo_list.overdue_by_priority() {
+                        ui.label(RichText::new(format!("● {}", priority.as_str())).color(priority_color(&priority)));
+                        let count_text = RichText::new(count.to_string());
+                        ui.label(if count > 0 { count_text.color(self.theme.error) } else { count_text });
+                        let average = self.todo_list.average_completion_time(Some(&priority));
+                        ui.label(average.map(format_elapsed).unwrap_or_else(|| "-".to_string()));
+                        ui.end_row();
+                    }
+                });
+                
+                ui.add_space(12.0);
+                
+                // 标签统计
+                ui.label(RichText::new("按标签").strong());
+                let tag_stats = self.todo_list.tag_stats();
+                if tag_stats.is_empty() {
+                    ui.label("暂无任务");
+                } else {
+                    egui::Grid::new("stats_by_tag").striped(true).show(ui, |ui| {
+                        ui.label("标签");
+                        ui.label("任务");
+                        ui.label("完成率");
+                        ui.label("逾期");
+                        ui.label("平均完成用时");
+                        ui.end_row();
+                        
+                        for stats in &tag_stats {
+                            let name = if stats.tag.is_empty() { "(无标签)".to_string() } else { format!("🏷️ {}", stats.tag) };
+                            ui.label(name);
+                            ui.label(format!("{}/{}", stats.done, stats.total));
+                            ui.add(egui::ProgressBar::new(stats.done as f32 / stats.total as f32)
+                                .desired_width(120.0)
+                                .show_percentage());
+                            let overdue_text = RichText::new(stats.overdue.to_string());
+                            ui.label(if stats.overdue > 0 { overdue_text.color(self.theme.error) } else { overdue_text });
+                            ui.label(stats.average_completion.map(format_elapsed).unwrap_or_else(|| "-".to_string()));
+                            ui.end_row();
+                        }
+                    });
+                }
+            });
+    }
+    
+    /// 渲染时间报表页面
+    fn render_time_report(&mut self, ui: &mut Ui) {
+        ui.heading("时间报表");
+        ui.separator();
+        
+        ui.add_space(16.0);
+        
+        let by_task: Vec<(String, chrono::Duration)> = self.todo_list.time_by_task()
+            .into_iter()
+            .map(|(todo, duration)| (todo.title.clone(), duration))
+            .collect();
+        
+        if by_task.is_empty() {
+            ui.vertical_centered(|ui| {
+                ui.add_space(20.0);
+                ui.label("暂无时间记录");
+                ui.add_space(20.0);
+            });
+        } else {
+            let total = by_task.iter().fold(Duration::zero(), |sum, (_, d)| sum + *d);
+            ui.label(RichText::new(format!("总计: {}", format_duration(total))).strong());
+            ui.add_space(12.0);
+            
+            let by_tag = self.todo_list.time_by_tag();
+            let by_day = self.todo_list.time_by_day();
+            
+            ScrollArea::vertical()
+                .id_source("time_report_scroll_area")
+                .show(ui, |ui| {
+                    ui.collapsing("按任务", |ui| {
+                        egui::Grid::new("time_by_task").striped(true).show(ui, |ui| {
+                            for (title, duration) in &by_task {
+                                ui.label(title);
+                                ui.label(format_duration(*duration));
+                                ui.end_row();
+                            }
+                        });
+                    });
+                    
+                    ui.collapsing("按标签", |ui| {
+                        egui::Grid::new("time_by_tag").striped(true).show(ui, |ui| {
+                            for (tag, duration) in &by_tag {
+                                let name = if tag.is_empty() { "(无标签)".to_string() } else { format!("🏷️ {}", tag) };
+                                ui.label(name);
+                                ui.label(format_duration(*duration));
+                                ui.end_row();
+                            }
+                        });
+                    });
+                    
+                    ui.collapsing("按日期", |ui| {
+                        egui::Grid::new("time_by_day").striped(true).show(ui, |ui| {
+                            // 最近的日期在前
+                            for (day, duration) in by_day.iter().rev() {
+                                ui.label(day.format("%Y-%m-%d").to_string());
+                                ui.label(format_duration(*duration));
+                                ui.end_row();
+                            }
+                        });
+                    });
+                });
+        }
+        
+        ui.add_space(16.0);
+        
+        if ui.button("返回").clicked() {
+            self.view = View::List;
+        }
+    }
+    
+    /// 获取看板的列：(列键, 列标题)
+    fn board_columns(&self) -> Vec<(String, String)> {
+        match self.board_grouping {
+            BoardGrouping::Status => self.todo_list.statuses
+                .iter()
+                .map(|s| (s.id.clone(), s.name.clone()))
+                .collect(),
+            BoardGrouping::Priority => board_priorities()
+                .iter()
+                .map(|(_, key, name)| (key.to_string(), name.to_string()))
+                .collect(),
+            BoardGrouping::Tag => {
+                let mut columns: Vec<(String, String)> = self.todo_list.all_tags()
+                    .into_iter()
+                    .map(|tag| (tag.clone(), format!("🏷️ {}", tag)))
+                    .collect();
+                columns.push((String::new(), "(无标签)".to_string()));
+                columns
+            }
+        }
+    }
+    
+    /// 获取任务所在的看板列，按标签分组时一个任务可能出现在多列中
+    fn board_column_keys(&self, todo: &Todo) -> Vec<String> {
+        match self.board_grouping {
+            BoardGrouping::Status => self.todo_list.status_of(todo).map(|s| vec![s.id.clone()]).unwrap_or_default(),
+            BoardGrouping::Priority => board_priorities()
+                .iter()
+                .filter(|(p, _, _)| *p == todo.priority)
+                .map(|(_, key, _)| key.to_string())
+                .collect(),
+            BoardGrouping::Tag => {
+                if todo.tags.is_empty() {
+                    vec![String::new()]
+                } else {
+                    todo.tags.clone()
+                }
+            }
+        }
+    }
+    
+    /// 将看板卡片从一列移到另一列，并更新对应的字段
+    fn move_board_card(&mut self, id: &str, from: &str, to: &str) {
+        match self.board_grouping {
+            BoardGrouping::Status => self.change_todo_status(id, to),
+            BoardGrouping::Priority => {
+                let priority = board_priorities().into_iter().find(|(_, key, _)| *key == to).map(|(p, _, _)| p);
+                if let (Some(priority), Some(todo)) = (priority, self.todo_list.todos.get_mut(id)) {
+                    self.history.label_next("修改优先级", false);
+                    todo.priority = priority;
+                    self.modified = true;
+                }
+            }
+            BoardGrouping::Tag => {
+                if let Some(todo) = self.todo_list.todos.get_mut(id) {
+                    self.history.label_next("修改标签", false);
+                    todo.tags.retain(|t| t != from);
+                    if !to.is_empty() && !todo.tags.iter().any(|t| t == to) {
+                        todo.tags.push(to.to_string());
+                    }
+                    self.modified = true;
+                }
+            }
+        }
+    }
+    
+    /// 渲染看板卡片，样式与任务列表中的卡片一致
+    fn render_board_card(&self, ui: &mut Ui, todo: &Todo, dragging: bool) -> egui::Response {
+        let card_bg = if todo.completed {
+            ui.visuals().faint_bg_color
+        } else {
+            ui.visuals().panel_fill
+        };
+        
+        let card_stroke = if todo.is_overdue() {
+            egui::Stroke::new(2.0, self.theme.error)
+        } else {
+            egui::Stroke::new(1.0, priority_color(&todo.priority))
+        };
+        
+        let card = egui::Frame::none()
+            .fill(card_bg)
+            .stroke(card_stroke)
+            .rounding(egui::Rounding::same(4.0))
+            .inner_margin(egui::Margin::same(8.0))
+            .show(ui, |ui| {
+                ui.set_width(ui.available_width());
+                
+                // 拖动中的卡片显示为半透明
+                if dragging {
+                    ui.visuals_mut().override_text_color = Some(self.theme.text_secondary.linear_multiply(0.5));
+                }
+                
+                let title = if todo.completed {
+                    RichText::new(&todo.title).strikethrough()
+                } else {
+                    RichText::new(&todo.title)
+                };
+                ui.horizontal_wrapped(|ui| {
+                    let emoji = todo.emoji.as_str();
+                    if !emoji.is_empty() {
+                        ui.label(emoji);
+                    }
+                    ui.label(title);
+                });
+                
+                let date_color = self.theme.text_secondary;
+                if let Some(due) = todo.due_date {
+                    let due_text = if todo.is_overdue() {
+                        RichText::new(format!("⚠ 已过期: {}", Todo::format_due_date(&due))).color(self.theme.error).small()
+                    } else {
+                        RichText::new(format!("截止: {}", Todo::format_due_date(&due))).color(date_color).small()
+                    };
+                    ui.label(due_text);
+                }
+                
+                if !todo.tags.is_empty() {
+                    ui.label(RichText::new(todo.tags.iter().map(|t| format!("🏷️ {}", t)).collect::<Vec<_>>().join(" ")).small());
+                }
+                
+                let (completed_subtasks, total_subtasks) = todo.subtask_counts();
+                if total_subtasks > 0 {
+                    ui.label(RichText::new(format!("子任务: {}/{}", completed_subtasks, total_subtasks)).color(date_color).small());
+                }
+            });
+        
+        ui.interact(card.response.rect, ui.id().with(("board_card", &todo.id)), egui::Sense::click_and_drag())
+            .on_hover_cursor(egui::CursorIcon::Grab)
+    }
+    
+    /// 渲染看板页面
+    fn render_board(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.heading("看板");
+            ui.add_space(16.0);
+            ui.label("分组:");
+            for grouping in [BoardGrouping::Status, BoardGrouping::Priority, BoardGrouping::Tag] {
+                ui.selectable_value(&mut self.board_grouping, grouping, grouping.as_str());
+            }
+        });
+        ui.separator();
+        
+        let columns = self.board_columns();
+        let todos: Vec<Todo> = self.todo_list.filtered_todos().into_iter().cloned().collect();
+        
+        // 每一列的位置，用于拖放
+        let mut column_rects: Vec<(String, egui::Rect)> = Vec::new();
+        let mut open_todo = None;
+        
+        ScrollArea::horizontal()
+            .id_source("board_scroll_area")
+            .show(ui, |ui| {
+                ui.horizontal_top(|ui| {
+                    for (key, title) in &columns {
+                        let cards: Vec<&Todo> = todos
+                            .iter()
+                            .filter(|t| self.board_column_keys(t).contains(key))
+                            .collect();
+                        
+                        let column = egui::Frame::group(ui.style()).show(ui, |ui| {
+                            ui.set_width(240.0);
+                            ui.set_min_height(ui.available_height());
+                            
+                            ui.label(RichText::new(format!("{} ({})", title, cards.len())).strong());
+                            ui.separator();
+                            
+                            ScrollArea::vertical()
+                                .id_source(("board_column", key))
+                                .show(ui, |ui| {
+                                    for todo in cards {
+                                        let dragging = self.dragging_todo_id.as_ref() == Some(&todo.id);
+                                        let response = self.render_board_card(ui, todo, dragging);
+                                        
+                                        if response.drag_started() {
+                                            self.dragging_todo_id = Some(todo.id.clone());
+                                            self.board_drag_source = Some(key.clone());
+                                        } else if response.clicked() {
+                                            open_todo = Some(todo.id.clone());
+                                        }
+                                        
+                                        ui.add_space(6.0);
+                                    }
+                                });
+                        });
+                        let rect = column.response.rect;
+                        
+                        // 拖动经过的列使用强调色边框
+                        let hovered = ui.ctx().pointer_hover_pos().is_some_and(|pos| rect.x_range().contains(pos.x));
+                        if self.dragging_todo_id.is_some() && hovered {
+                            ui.painter().rect_stroke(rect, egui::Rounding::same(4.0), egui::Stroke::new(2.0, self.theme.accent));
+                        }
+                        
+                        column_rects.push((key.clone(), rect));
+                    }
+                });
+            });
         
-        // 应用风格设置
-        ui.heading("应用风格");
-        ui.add_space(8.0);
-        
-        ui.horizontal_wrapped(|ui| {
-            let style_options = [
-                (crate::app::AppStyle::Modern, "现代", Color32::from_rgb(66, 133, 244)),
-                (crate::app::AppStyle::Minimal, "简约", Color32::from_rgb(50, 50, 60)),
-                (crate::app::AppStyle::Classic, "经典", Color32::from_rgb(180, 120, 80)),
-                (crate::app::AppStyle::Retro, "复古", Color32::from_rgb(246, 190, 0)),
-                (crate::app::AppStyle::Business, "商务", Color32::from_rgb(20, 80, 120)),
-            ];
-            
-            for (style_type, name, color) in &style_options {
-                let is_selected = &self.app_style == style_type;
-                
-                let mut button = Button::new(*name);
-                if is_selected {
-                    button = button.fill(*color).stroke(egui::Stroke::new(2.0, self.theme.accent));
-                } else {
-                    button = button.fill(Color32::from_rgba_premultiplied(
-                        color.r(), color.g(), color.b(), 40
-                    )).stroke(egui::Stroke::new(1.0, *color));
+        // 拖放：松开鼠标时把卡片移到指针所在的列
+        if let Some(dragging_id) = self.dragging_todo_id.clone() {
+            if let Some(pos) = ui.ctx().pointer_hover_pos() {
+                // 在指针旁显示被拖动的任务标题
+                if let Some(todo) = self.todo_list.todos.get(&dragging_id) {
+                    egui::Area::new("board_drag_preview")
+                        .fixed_pos(pos + Vec2::new(12.0, 12.0))
+                        .order(egui::Order::Tooltip)
+                        .show(ui.ctx(), |ui| {
+                            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                                ui.label(&todo.title);
+                            });
+                        });
                 }
                 
-                // 添加圆角和内边距
-                button = button.rounding(egui::Rounding::same(8.0));
-                
-                if ui.add_sized(Vec2::new(100.0, 40.0), button).clicked() && !is_selected {
-                    // 改变应用风格并保存
-                    if let Err(e) = self.set_app_style(style_type.clone()) {
-                        eprintln!("保存应用风格失败: {}", e);
+                if ui.input(|i| i.pointer.any_released()) {
+                    let target = column_rects.iter().find(|(_, rect)| rect.x_range().contains(pos.x));
+                    let source = self.board_drag_source.clone().unwrap_or_default();
+                    if let Some((target_key, _)) = target {
+                        if *target_key != source {
+                            self.move_board_card(&dragging_id, &source, target_key);
+                        }
                     }
                 }
             }
-        });
+            
+            if !ui.input(|i| i.pointer.any_down()) {
+                self.dragging_todo_id = None;
+                self.board_drag_source = None;
+            }
+        }
         
-        ui.add_space(8.0);
+        if let Some(id) = open_todo {
+            self.editing_todo_id = Some(id);
+            self.view = View::EditTodo;
+        }
+    }
+    
+    /// 渲染日历页面
+    fn render_calendar(&mut self, ui: &mut Ui) {
+        let today = Local::now().date_naive();
+        let anchor = self.calendar_date;
         
-        // 风格说明
-        let style_desc = match self.app_style {
-            crate::app::AppStyle::Modern => "现代风格：圆角按钮，柔和阴影，现代感的界面元素",
-            crate::app::AppStyle::Minimal => "简约风格：扁平化设计，简洁线条，最小装饰元素",
-            crate::app::AppStyle::Classic => "经典风格：传统窗口界面，中等圆角，标准按钮",
-            crate::app::AppStyle::Retro => "复古风格：像素感，强烈边框，鲜明对比色",
-            crate::app::AppStyle::Business => "商务风格：严肃专业的外观，清晰的层次结构",
+        // 当前显示范围：月视图从当月1日所在周的周一开始，周视图从本周周一开始
+        let (first_day, weeks) = match self.calendar_mode {
+            CalendarMode::Month => {
+                let first_of_month = NaiveDate::from_ymd_opt(anchor.year(), anchor.month(), 1).unwrap_or(anchor);
+                let start = first_of_month - Duration::days(first_of_month.weekday().num_days_from_monday() as i64);
+                let (next_year, next_month) = if anchor.month() == 12 { (anchor.year() + 1, 1) } else { (anchor.year(), anchor.month() + 1) };
+                let last_of_month = NaiveDate::from_ymd_opt(next_year, next_month, 1).unwrap_or(anchor) - Duration::days(1);
+                let weeks = ((last_of_month - start).num_days() / 7 + 1) as usize;
+                (start, weeks)
+            }
+            CalendarMode::Week => (anchor - Duration::days(anchor.weekday().num_days_from_monday() as i64), 1),
         };
         
-        ui.label(RichText::new(style_desc).italics());
-        
-        ui.add_space(16.0);
-        
-        // 数据导入导出区域
-        ui.heading("数据管理");
-        ui.add_space(8.0);
-        
+        // 标题和导航
         ui.horizontal(|ui| {
-            if ui.button("📤 导出任务").clicked() {
-                self.export_todos_dialog();
-            }
+            ui.heading("日历");
+            ui.add_space(16.0);
             
-            if ui.button("📥 导入任务").clicked() {
-                self.import_todos_dialog();
-            }
+            ui.selectable_value(&mut self.calendar_mode, CalendarMode::Month, "月");
+            ui.selectable_value(&mut self.calendar_mode, CalendarMode::Week, "周");
             
-            if ui.button("📥 合并导入").clicked() {
-                self.merge_todos_dialog();
+            ui.add_space(16.0);
+            
+            if ui.button("◀").clicked() {
+                self.calendar_date = match self.calendar_mode {
+                    CalendarMode::Month => {
+                        let (year, month) = if anchor.month() == 1 { (anchor.year() - 1, 12) } else { (anchor.year(), anchor.month() - 1) };
+                        NaiveDate::from_ymd_opt(year, month, 1).unwrap_or(anchor)
+                    }
+                    CalendarMode::Week => anchor - Duration::days(7),
+                };
+            }
+            if ui.button("今天").clicked() {
+                self.calendar_date = today;
+            }
+            if ui.button("▶").clicked() {
+                self.calendar_date = match self.calendar_mode {
+                    CalendarMode::Month => {
+                        let (year, month) = if anchor.month() == 12 { (anchor.year() + 1, 1) } else { (anchor.year(), anchor.month() + 1) };
+                        NaiveDate::from_ymd_opt(year, month, 1).unwrap_or(anchor)
+                    }
+                    CalendarMode::Week => anchor + Duration::days(7),
+                };
             }
+            
+            ui.add_space(16.0);
+            
+            let title = match self.calendar_mode {
+                CalendarMode::Month => format!("{}年{}月", anchor.year(), anchor.month()),
+                CalendarMode::Week => {
+                    let last_day = first_day + Duration::days(6);
+                    format!("{} – {}", first_day.format("%Y-%m-%d"), last_day.format("%m-%d"))
+                }
+            };
+            ui.label(RichText::new(title).strong());
         });
+        ui.separator();
         
-        ui.add_space(16.0);
-        
-        ui.heading("其他设置");
-        
-        // 添加关于按钮
-        if ui.button("关于 Rodo").clicked() {
-            self.view = View::About;
+        // 按日期收集任务：未完成的任务显示在截止日，已完成的任务显示在完成日
+        let mut by_day: std::collections::HashMap<NaiveDate, Vec<Todo>> = std::collections::HashMap::new();
+        for todo in self.todo_list.filtered_todos() {
+            if let Some(date) = todo.calendar_date() {
+                by_day.entry(date).or_default().push(todo.clone());
+            }
+        }
+        for todos in by_day.values_mut() {
+            todos.sort_by_key(|t| (t.completed, t.due_date));
         }
         
-        ui.add_space(16.0);
-        
+        // 星期标题
+        let spacing = 4.0;
+        let cell_width = ((ui.available_width() - spacing * 6.0) / 7.0).max(60.0);
         ui.horizontal(|ui| {
-            if ui.button("返回").clicked() {
-                self.view = View::List;
+            ui.spacing_mut().item_spacing.x = spacing;
+            for weekday in [Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat, Weekday::Sun] {
+                ui.add_sized([cell_width, 20.0], egui::Label::new(RichText::new(format!("周{}", weekday_name(&weekday))).strong()));
             }
-            
-            ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
-                // 恢复初始状态按钮 - 使用警告色以表示危险操作
-                let reset_button = Button::new(RichText::new("恢复初始状态").color(self.theme.error));
-                if ui.add(reset_button).clicked() {
-                    self.show_confirm(
-                        "确定要恢复初始状态吗？这将清空所有数据并重置所有设置，此操作不可撤销！",
-                        ConfirmationAction::ResetApp,
-                    );
+        });
+        
+        let cell_height = match self.calendar_mode {
+            CalendarMode::Month => ((ui.available_height() - spacing * weeks as f32) / weeks as f32).max(80.0),
+            CalendarMode::Week => ui.available_height().max(200.0),
+        };
+        // 每格最多显示的任务数
+        let max_items = ((cell_height - 28.0) / 18.0).max(1.0) as usize;
+        
+        let mut cell_rects: Vec<(NaiveDate, egui::Rect)> = Vec::new();
+        let mut open_todo = None;
+        let mut add_on = None;
+        
+        ScrollArea::vertical()
+            .id_source("calendar_scroll_area")
+            .show(ui, |ui| {
+                ui.spacing_mut().item_spacing = Vec2::splat(spacing);
+                for week in 0..weeks {
+                    ui.horizontal(|ui| {
+                        for day in 0..7 {
+                            let date = first_day + Duration::days((week * 7 + day) as i64);
+                            let in_month = self.calendar_mode == CalendarMode::Week || date.month() == anchor.month();
+                            
+                            // 先分配整个格子，点击空白处添加任务；任务标签在其上层，优先响应
+                            let (rect, background) = ui.allocate_exact_size(Vec2::new(cell_width, cell_height), egui::Sense::click());
+                            if background.on_hover_text("点击添加任务").clicked() {
+                                add_on = Some(date);
+                            }
+                            
+                            let fill = if date == today {
+                                self.theme.accent.linear_multiply(0.15)
+                            } else if in_month {
+                                ui.visuals().panel_fill
+                            } else {
+                                ui.visuals().faint_bg_color
+                            };
+                            let hovered_drop = self.dragging_todo_id.is_some()
+                                && ui.ctx().pointer_hover_pos().is_some_and(|pos| rect.contains(pos));
+                            let stroke = if hovered_drop {
+                                egui::Stroke::new(2.0, self.theme.accent)
+                            } else {
+                                ui.visuals().widgets.noninteractive.bg_stroke
+                            };
+                            ui.painter().rect(rect, egui::Rounding::same(4.0), fill, stroke);
+                            cell_rects.push((date, rect));
+                            
+                            let mut cell_ui = ui.child_ui(rect.shrink(4.0), Layout::top_down(egui::Align::Min));
+                            cell_ui.spacing_mut().item_spacing = Vec2::new(2.0, 2.0);
+                            
+                            let day_text = RichText::new(date.day().to_string()).small();
+                            let day_text = if date == today {
+                                day_text.strong().color(self.theme.accent)
+                            } else if in_month {
+                                day_text
+                            } else {
+                                day_text.color(self.theme.text_secondary)
+                            };
+                            cell_ui.label(day_text);
+                            
+                            let todos = by_day.get(&date).map(|v| v.as_slice()).unwrap_or(&[]);
+                            for (i, todo) in todos.iter().enumerate() {
+                                if i >= max_items {
+                                    cell_ui.label(RichText::new(format!("+{} 更多", todos.len() - i)).small().color(self.theme.text_secondary));
+                                    break;
+                                }
+                                
+                                cell_ui.horizontal(|ui| {
+                                    ui.label(RichText::new("●").small().color(priority_color(&todo.priority)));
+                                    
+                                    let mut text = if todo.completed {
+                                        RichText::new(&todo.title).small().strikethrough()
+                                    } else if todo.is_overdue() {
+                                        RichText::new(&todo.title).small().color(self.theme.error)
+                                    } else {
+                                        RichText::new(&todo.title).small()
+                                    };
+                                    if self.dragging_todo_id.as_ref() == Some(&todo.id) {
+                                        text = text.weak();
+                                    }
+                                    
+                                    // 已完成的任务按完成日显示，不能拖动改期
+                                    let sense = if todo.completed { egui::Sense::click() } else { egui::Sense::click_and_drag() };
+                                    let response = ui.add(egui::Label::new(text).truncate(true).sense(sense));
+                                    let response = match todo.due_date {
+                                        Some(due) if !todo.completed => response.on_hover_text(format!("{}\n截止: {}", todo.title, due.format("%H:%M"))),
+                                        _ => response.on_hover_text(&todo.title),
+                                    };
+                                    
+                                    if response.drag_started() {
+                                        self.dragging_todo_id = Some(todo.id.clone());
+                                    } else if response.clicked() {
+                                        open_todo = Some(todo.id.clone());
+                                    }
+                                });
+                            }
+                        }
+                    });
+                }
+            });
+        
+        // 拖放：松开鼠标时把任务改期到指针所在的日期
+        if let Some(dragging_id) = self.dragging_todo_id.clone() {
+            if let Some(pos) = ui.ctx().pointer_hover_pos() {
+                if let Some(todo) = self.todo_list.todos.get(&dragging_id) {
+                    egui::Area::new("calendar_drag_preview")
+                        .fixed_pos(pos + Vec2::new(12.0, 12.0))
+                        .order(egui::Order::Tooltip)
+                        .show(ui.ctx(), |ui| {
+                            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                                ui.label(&todo.title);
+                            });
+                        });
                 }
                 
-                ui.add_space(8.0);
-                
-                if ui.button("重置默认主题").clicked() {
-                    self.show_confirm(
-                        "确定要重置为默认主题吗？",
-                        ConfirmationAction::ResetSettings,
-                    );
+                if ui.input(|i| i.pointer.any_released()) {
+                    if let Some((date, _)) = cell_rects.iter().find(|(_, rect)| rect.contains(pos)) {
+                        let current = self.todo_list.todos.get(&dragging_id).and_then(|t| t.calendar_date());
+                        if current != Some(*date) {
+                            self.reschedule_todo(&dragging_id, *date);
+                        }
+                    }
                 }
-            });
-        });
+            }
+            
+            if !ui.input(|i| i.pointer.any_down()) {
+                self.dragging_todo_id = None;
+            }
+        } else if let Some(id) = open_todo {
+            self.editing_todo_id = Some(id);
+            self.view = View::EditTodo;
+        } else if let Some(date) = add_on {
+            self.add_todo_on(date);
+        }
     }
     
-    /// 渲染统计页面
-    fn render_stats(&mut self, ui: &mut Ui) {
-        ui.heading("统计");
+    /// 渲染回收站页面
+    fn render_trash(&mut self, ui: &mut Ui) {
+        ui.heading("回收站");
         ui.separator();
         
         ui.add_space(16.0);
         
-        ui.label("统计功能尚未实现。");
+        // 自动清除设置
+        ui.horizontal(|ui| {
+            ui.label("自动清除:");
+            let mut days = self.todo_list.trash_retention_days;
+            if ui.add(egui::DragValue::new(&mut days).clamp_range(0..=365).suffix("天后")).changed() {
+                self.todo_list.trash_retention_days = days;
+                self.modified = true;
+                let purged = self.purge_expired_trash();
+                if purged > 0 {
+                    self.toasts.push(Toast::new(format!("已永久删除 {} 个超过保留天数的任务", purged), None));
+                }
+            }
+            if days == 0 {
+                ui.label(RichText::new("（永不清除）").small().color(self.theme.text_secondary));
+            }
+        });
         
-        ui.add_space(16.0);
+        ui.add_space(12.0);
         
-        if ui.button("返回").clicked() {
-            self.view = View::List;
+        if self.todo_list.trash.is_empty() {
+            ui.vertical_centered(|ui| {
+                ui.add_space(20.0);
+                ui.label("回收站是空的");
+                ui.add_space(20.0);
+            });
+        } else {
+            // 最近删除的在前
+            let items: Vec<(TrashedTodo, Option<DateTime<Local>>)> = self.todo_list.trash
+                .iter()
+                .rev()
+                .map(|t| (t.clone(), self.todo_list.trash_purge_time(t)))
+                .collect();
+            
+            let mut restore = None;
+            let mut purge = None;
+            
+            ScrollArea::vertical()
+                .id_source("trash_scroll_area")
+                .max_height(ui.available_height() - 60.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("trash_grid").striped(true).num_columns(4).show(ui, |ui| {
+                        let date_color = self.theme.text_secondary;
+                        for (trashed, purge_at) in &items {
+                            ui.label(truncate_string(&trashed.todo.title, 40));
+                            ui.label(RichText::new(format!("删除于: {}", Todo::format_date_time(&trashed.deleted_at))).color(date_color).small());
+                            
+                            let purge_text = match purge_at {
+                                Some(at) => format!("{} 自动清除", at.format("%Y-%m-%d")),
+                                None => String::new(),
+                            };
+                            ui.label(RichText::new(purge_text).color(date_color).small());
+                            
+                            ui.horizontal(|ui| {
+                                if ui.button("恢复").clicked() {
+                                    restore = Some(trashed.todo.id.clone());
+                                }
+                                if ui.button(RichText::new("永久删除").color(self.theme.error)).clicked() {
+                                    purge = Some((trashed.todo.id.clone(), trashed.todo.title.clone()));
+                                }
+                            });
+                            ui.end_row();
+                        }
+                    });
+                });
+            
+            if let Some(id) = restore {
+                self.restore_todo(&id);
+            }
+            
+            if let Some((id, title)) = purge {
+                self.show_confirm(
+                    &format!("确定要永久删除任务\"{}\"吗？", title),
+                    ConfirmationAction::PurgeTrashedTodo(id),
+                );
+            }
         }
+        
+        ui.add_space(16.0);
+        
+        ui.horizontal(|ui| {
+            if ui.button("返回").clicked() {
+                self.view = View::List;
+            }
+            
+            if !self.todo_list.trash.is_empty() {
+                ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button(RichText::new("清空回收站").color(self.theme.error)).clicked() {
+                        self.show_confirm(
+                            &format!("确定要永久删除回收站中的 {} 个任务吗？", self.todo_list.trash.len()),
+                            ConfirmationAction::EmptyTrash,
+                        );
+                    }
+                });
+            }
+        });
     }
     
     /// 渲染标签管理页面
@@ -1713,6 +4435,7 @@ impl RodoApp {
         
         // 缓存活跃标签以避免UI闪烁
         let active_tags = self.todo_list.active_tags.clone();
+        let excluded_tags = self.todo_list.excluded_tags.clone();
         
         // 获取所有标签和使用次数
         let mut all_tags = std::collections::HashMap::new();
@@ -1749,6 +4472,7 @@ impl RodoApp {
             // 使用状态变量避免借用冲突
             let mut tag_to_delete = None;
             let mut tags_to_toggle = Vec::new();
+            let mut tags_to_toggle_excluded = Vec::new();
             
             // 显示标签列表
             ScrollArea::vertical()
@@ -1784,6 +4508,17 @@ impl RodoApp {
                                 }).inner {
                                     tags_to_toggle.push(tag.clone());
                                 }
+                                
+                                // 标签排除按钮
+                                let is_excluded = excluded_tags.contains(tag);
+                                let text = if is_excluded { "取消排除" } else { "排除" };
+                                
+                                let exclude_btn_id = ui.id().with(format!("{}_exclude", tag_id));
+                                if ui.push_id(exclude_btn_id, |ui| {
+                                    ui.selectable_label(is_excluded, text).clicked()
+                                }).inner {
+                                    tags_to_toggle_excluded.push(tag.clone());
+                                }
                             });
                         });
                         
@@ -1804,11 +4539,20 @@ impl RodoApp {
                 for tag in tags_to_toggle {
                     let is_active = active_tags.contains(&tag);
                     if is_active {
-                        if let Some(pos) = self.todo_list.active_tags.iter().position(|t| t == &tag) {
-                            self.todo_list.active_tags.remove(pos);
-                        }
+                        self.todo_list.clear_tag_filter(&tag);
+                    } else {
+                        self.todo_list.include_tag(&tag);
+                    }
+                }
+                self.modified = true;
+            }
+            
+            if !tags_to_toggle_excluded.is_empty() {
+                for tag in tags_to_toggle_excluded {
+                    if excluded_tags.contains(&tag) {
+                        self.todo_list.clear_tag_filter(&tag);
                     } else {
-                        self.todo_list.active_tags.push(tag);
+                        self.todo_list.exclude_tag(&tag);
                     }
                 }
                 self.modified = true;
@@ -1850,6 +4594,7 @@ impl RodoApp {
                 ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
                     if ui.button("清除所有筛选").clicked() {
                         self.todo_list.active_tags.clear();
+                        self.todo_list.excluded_tags.clear();
                         self.modified = true;
                     }
                 });
@@ -2132,6 +4877,53 @@ impl RodoApp {
         }
     }
     
+    /// 渲染任务文件无法读取时的恢复对话框
+    fn render_load_failure_dialog(&mut self, ctx: &egui::Context) {
+        let (file_name, error, backups) = match &self.load_failure {
+            Some(failure) => (failure.file_name.clone(), failure.error.clone(), failure.backups.clone()),
+            None => return,
+        };
+        let mut result = None;
+        
+        egui::Window::new("无法读取任务文件")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(format!("工作区 \"{}\" 的任务文件 {} 无法读取：", self.workspaces.active().name, file_name));
+                ui.label(RichText::new(&error).small().color(self.theme.error));
+                ui.add_space(8.0);
+                ui.label("处理之前不会保存任何修改。可以从以下备份恢复：");
+                ui.add_space(4.0);
+                
+                if backups.is_empty() {
+                    ui.label(RichText::new("没有可用的备份").italics().color(self.theme.text_secondary));
+                }
+                ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                    for backup in &backups {
+                        ui.horizontal(|ui| {
+                            ui.label(Todo::format_date_time(&backup.created_at));
+                            if ui.button("恢复").clicked() {
+                                result = Some(self.restore_from_backup(&backup.path));
+                            }
+                        });
+                    }
+                });
+                
+                ui.separator();
+                if ui.button("使用空列表").on_hover_text("原文件会改名保留在数据目录中").clicked() {
+                    result = Some(self.discard_unreadable_todos());
+                }
+            });
+        
+        if let Some(Err(err)) = result {
+            eprintln!("恢复任务失败: {}", err);
+            if let Some(failure) = &mut self.load_failure {
+                failure.error = format!("恢复失败: {}", err);
+            }
+        }
+    }
+    
     /// 渲染确认对话框
     fn render_confirmation_dialog(&mut self, ctx: &egui::Context) {
         // 保存导入路径，以避免借用冲突
@@ -2155,7 +4947,9 @@ impl RodoApp {
             String::new()
         };
         
-        egui::Window::new("确认")
+        let is_notice = matches!(self.confirmation_action, Some(ConfirmationAction::Notice));
+        
+        egui::Window::new(if is_notice { "提示" } else { "确认" })
             .collapsible(false)
             .resizable(false)
             .fixed_size(Vec2::new(300.0, 150.0))
@@ -2167,12 +4961,14 @@ impl RodoApp {
                     ui.add_space(20.0);
                     
                     ui.horizontal(|ui| {
-                        if ui.button("取消").clicked() {
+                        if !is_notice && ui.button("取消").clicked() {
                             self.show_confirmation = false;
                             self.confirmation_action = None;
                         }
                         
                         if ui.button("确定").clicked() {
+                            // 先关闭对话框，动作失败时显示的提示不会被随即关闭
+                            self.show_confirmation = false;
                             match self.confirmation_action.take() {
                                 Some(ConfirmationAction::DeleteTodo(id)) => {
                                     self.delete_todo(&id);
@@ -2228,16 +5024,41 @@ impl RodoApp {
                                         self.temp_input.clear();
                                     }
                                 },
+                                Some(ConfirmationAction::CompleteBlocked(id, status_id)) => {
+                                    // 用户确认提前完成被阻塞的任务
+                                    self.history.label_next("完成任务", false);
+                                    self.todo_list.set_todo_status(&id, &status_id);
+                                    self.modified = true;
+                                },
+                                Some(ConfirmationAction::DeleteWorkspace(id)) => {
+                                    if let Err(err) = self.delete_workspace(&id) {
+                                        eprintln!("删除工作区失败: {}", err);
+                                        self.show_notice(&format!("删除工作区失败: {}", err));
+                                    }
+                                },
+                                Some(ConfirmationAction::PurgeTrashedTodo(id)) => {
+                                    self.purge_trashed_todo(&id);
+                                },
+                                Some(ConfirmationAction::EmptyTrash) => {
+                                    self.empty_trash();
+                                },
+                                Some(ConfirmationAction::DeleteTodos(ids)) => {
+                                    self.bulk_delete(&ids);
+                                },
+                                Some(ConfirmationAction::DemoteTodo(id, target_id)) => {
+                                    if let Err(err) = self.demote_todo(&id, &target_id) {
+                                        eprintln!("转为子任务失败: {}", err);
+                                        self.show_notice(&format!("转为子任务失败: {}", err));
+                                    }
+                                },
                                 Some(ConfirmationAction::DeleteTag(_tag)) => {
                                     // 使用事先保存的标签名
                                     if !tag_to_delete.is_empty() {
                                         self.delete_tag(&tag_to_delete);
                                     }
                                 },
-                                None => {},
+                                Some(ConfirmationAction::Notice) | None => {},
                             }
-                            
-                            self.show_confirmation = false;
                         }
                     });
                 });
@@ -0,0 +1,352 @@
+use crate::schema;
+use crate::storage;
+use crate::todo::TodoList;
+use serde::{Deserialize, Serialize};
+use chrono::Local;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// 默认工作区ID，对应原有的 todos.json 文件
+const DEFAULT_WORKSPACE_ID: &str = "default";
+
+/// 工作区，每个工作区使用独立的任务文件
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Workspace {
+    pub id: String,
+    pub name: String,
+}
+
+impl Workspace {
+    /// 获取工作区对应的任务文件名
+    pub fn file_name(&self) -> String {
+        if self.id == DEFAULT_WORKSPACE_ID {
+            "todos.json".to_string()
+        } else {
+            format!("todos-{}.json", self.id)
+        }
+    }
+    
+    /// 获取工作区对应的智能列表文件名
+    pub fn smart_lists_file_name(&self) -> String {
+        if self.id == DEFAULT_WORKSPACE_ID {
+            "smart_lists.json".to_string()
+        } else {
+            format!("smart_lists-{}.json", self.id)
+        }
+    }
+}
+
+/// 工作区集合
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Workspaces {
+    pub workspaces: Vec<Workspace>,
+    /// 当前使用的工作区ID
+    pub active: String,
+}
+
+impl Default for Workspaces {
+    fn default() -> Self {
+        Self {
+            workspaces: vec![Workspace {
+                id: DEFAULT_WORKSPACE_ID.to_string(),
+                name: "默认".to_string(),
+            }],
+            active: DEFAULT_WORKSPACE_ID.to_string(),
+        }
+    }
+}
+
+impl Workspaces {
+    /// 获取当前工作区
+    pub fn active(&self) -> &Workspace {
+        self.get(&self.active).unwrap_or(&self.workspaces[0])
+    }
+    
+    /// 获取指定ID的工作区
+    pub fn get(&self, id: &str) -> Option<&Workspace> {
+        self.workspaces.iter().find(|w| w.id == id)
+    }
+    
+    /// 检查名称是否可用
+    fn validate_name(&self, name: &str, except_id: Option<&str>) -> Result<(), String> {
+        if name.trim().is_empty() {
+            return Err("工作区名称不能为空".to_string());
+        }
+        if self.workspaces.iter().any(|w| w.name == name.trim() && Some(w.id.as_str()) != except_id) {
+            return Err(format!("工作区 '{}' 已存在", name.trim()));
+        }
+        Ok(())
+    }
+    
+    /// 创建新的工作区，返回新工作区ID
+    pub fn create(&mut self, name: &str) -> Result<String, String> {
+        self.validate_name(name, None)?;
+        
+        let id = Uuid::new_v4().to_string();
+        self.workspaces.push(Workspace {
+            id: id.clone(),
+            name: name.trim().to_string(),
+        });
+        self.save()?;
+        
+        Ok(id)
+    }
+    
+    /// 重命名工作区
+    pub fn rename(&mut self, id: &str, name: &str) -> Result<(), String> {
+        self.validate_name(name, Some(id))?;
+        
+        let workspace = self.workspaces.iter_mut()
+            .find(|w| w.id == id)
+            .ok_or_else(|| "工作区不存在".to_string())?;
+        workspace.name = name.trim().to_string();
+        
+        self.save()
+    }
+    
+    /// 复制工作区及其任务文件，返回新工作区ID
+    pub fn duplicate(&mut self, id: &str, name: &str) -> Result<String, String> {
+        let source = self.get(id).cloned().ok_or_else(|| "工作区不存在".to_string())?;
+        self.validate_name(name, None)?;
+        
+        let new_id = Uuid::new_v4().to_string();
+        let copy = Workspace {
+            id: new_id.clone(),
+            name: name.trim().to_string(),
+        };
+        
+        let source_path = TodoList::data_file_path(&source.file_name())?;
+        if source_path.exists() {
+            let target_path = TodoList::data_file_path(&copy.file_name())?;
+            std::fs::copy(source_path, target_path).map_err(|e| format!("复制任务文件失败: {}", e))?;
+        }
+        
+        let source_path = TodoList::data_file_path(&source.smart_lists_file_name())?;
+        if source_path.exists() {
+            let target_path = TodoList::data_file_path(&copy.smart_lists_file_name())?;
+            std::fs::copy(source_path, target_path).map_err(|e| format!("复制智能列表文件失败: {}", e))?;
+        }
+        
+        self.workspaces.push(copy);
+        self.save()?;
+        
+        Ok(new_id)
+    }
+    
+    /// 删除工作区及其任务文件
+    pub fn delete(&mut self, id: &str) -> Result<(), String> {
+        let path = Workspaces::get_workspaces_file_path()?;
+        self.delete_at(&path, id)
+    }
+    
+    /// 删除工作区，`path` 为工作区文件路径，任务文件位于同一目录
+    ///
+    /// 先删除文件并保存新的工作区集合，全部成功后才修改内存中的状态，
+    /// 避免中途失败时当前工作区指向已移除的工作区，之后把它的任务保存到其他工作区的文件中。
+    fn delete_at(&mut self, path: &Path, id: &str) -> Result<(), String> {
+        if self.workspaces.len() <= 1 {
+            return Err("至少需要保留一个工作区".to_string());
+        }
+        
+        let workspace = self.get(id).cloned().ok_or_else(|| "工作区不存在".to_string())?;
+        let data_dir = path.parent().ok_or_else(|| format!("无效的文件路径: {}", path.display()))?;
+        
+        let smart_lists_path = data_dir.join(workspace.smart_lists_file_name());
+        if smart_lists_path.exists() {
+            std::fs::remove_file(smart_lists_path).map_err(|e| format!("删除智能列表文件失败: {}", e))?;
+        }
+        
+        let todos_path = data_dir.join(workspace.file_name());
+        if todos_path.exists() {
+            std::fs::remove_file(&todos_path).map_err(|e| format!("删除任务文件失败: {}", e))?;
+        }
+        
+        let mut remaining = self.clone();
+        remaining.workspaces.retain(|w| w.id != id);
+        // 删除当前工作区时切换到第一个工作区
+        if remaining.active == id {
+            remaining.active = remaining.workspaces[0].id.clone();
+        }
+        remaining.write(path)?;
+        *self = remaining;
+        
+        // 任务文件已删除，它的备份也不再需要；清理失败不影响删除结果
+        for backup in storage::list_backups(&todos_path) {
+            if let Err(err) = std::fs::remove_file(&backup.path) {
+                eprintln!("删除备份失败: {}", err);
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// 保存工作区集合到文件
+    pub fn save(&self) -> Result<(), String> {
+        self.write(&Workspaces::get_workspaces_file_path()?)
+    }
+    
+    /// 将工作区集合写入指定文件
+    fn write(&self, path: &Path) -> Result<(), String> {
+        let serialized = schema::WORKSPACES.encode(self).map_err(|e| format!("序列化工作区失败: {}", e))?;
+        storage::write_atomic(path, serialized.as_bytes()).map_err(|e| format!("写入工作区文件失败: {}", e))?;
+        Ok(())
+    }
+    
+    /// 从文件加载工作区集合，同时返回需要提示用户的问题
+    ///
+    /// 文件无法读取时改名保留原文件，并根据数据目录中的任务文件重建工作区，
+    /// 避免之后保存时覆盖原文件、使其他工作区的任务文件无处可寻。
+    pub fn load() -> (Self, Option<String>) {
+        let path = match Workspaces::get_workspaces_file_path() {
+            Ok(path) => path,
+            Err(err) => return (Self::default(), Some(err)),
+        };
+        
+        match Workspaces::read(&path) {
+            Ok(workspaces) if !workspaces.workspaces.is_empty() => (workspaces, None),
+            Ok(_) => (Self::default(), None),
+            Err(err) => {
+                let notice = storage::set_aside_unreadable(&path, "工作区文件", &err, Local::now());
+                let recovered = Workspaces::recover(path.parent().unwrap_or(&path));
+                let notice = format!("{}\n已根据数据目录中的 {} 个任务文件重建工作区", notice, recovered.workspaces.len());
+                (recovered, Some(notice))
+            }
+        }
+    }
+    
+    /// 读取工作区文件，文件不存在时返回默认工作区
+    fn read(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        
+        let data = std::fs::read_to_string(path)
+            .map_err(|e| format!("读取工作区文件失败: {}", e))?;
+            
+        schema::WORKSPACES.decode(&data)
+            .map_err(|e| format!("解析工作区JSON失败: {}", e))
+    }
+    
+    /// 根据数据目录中的任务文件重建工作区集合，工作区名称无法恢复，按顺序编号
+    fn recover(data_dir: &Path) -> Self {
+        let mut ids: Vec<String> = std::fs::read_dir(data_dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+                    .filter_map(|name| Some(name.strip_prefix("todos-")?.strip_suffix(".json")?.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        ids.sort();
+        
+        let mut recovered = Self::default();
+        for (n, id) in ids.into_iter().enumerate() {
+            recovered.workspaces.push(Workspace {
+                id,
+                name: format!("恢复的工作区 {}", n + 1),
+            });
+        }
+        recovered
+    }
+    
+    /// 获取工作区文件路径，与任务文件位于同一目录
+    fn get_workspaces_file_path() -> Result<PathBuf, String> {
+        TodoList::data_file_path("workspaces.json")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    
+    /// 测试用的临时数据目录，离开作用域时删除
+    struct TestDir(PathBuf);
+    
+    impl TestDir {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!("rodo-workspace-{}", Uuid::new_v4()));
+            fs::create_dir_all(&dir).unwrap();
+            TestDir(dir)
+        }
+        
+        fn file(&self, name: &str) -> PathBuf {
+            self.0.join(name)
+        }
+    }
+    
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+    
+    /// 包含默认工作区和另一个工作区的集合，当前工作区为后者
+    fn two_workspaces() -> Workspaces {
+        let mut workspaces = Workspaces::default();
+        workspaces.workspaces.push(Workspace {
+            id: "work".to_string(),
+            name: "工作".to_string(),
+        });
+        workspaces.active = "work".to_string();
+        workspaces
+    }
+    
+    #[test]
+    fn delete_removes_files_and_switches_active() {
+        let dir = TestDir::new();
+        let path = dir.file("workspaces.json");
+        let mut workspaces = two_workspaces();
+        fs::write(dir.file("todos.json"), b"default").unwrap();
+        fs::write(dir.file("todos-work.json"), b"work").unwrap();
+        fs::write(dir.file("smart_lists-work.json"), b"lists").unwrap();
+        storage::backup_if_due(&dir.file("todos-work.json"), Local::now()).unwrap();
+        storage::backup_if_due(&dir.file("todos.json"), Local::now()).unwrap();
+        
+        workspaces.delete_at(&path, "work").unwrap();
+        
+        assert_eq!(workspaces.workspaces.len(), 1);
+        assert_eq!(workspaces.active, DEFAULT_WORKSPACE_ID);
+        assert!(!dir.file("todos-work.json").exists());
+        assert!(!dir.file("smart_lists-work.json").exists());
+        assert!(storage::list_backups(&dir.file("todos-work.json")).is_empty());
+        // 其他工作区的文件和备份保留
+        assert_eq!(fs::read(dir.file("todos.json")).unwrap(), b"default");
+        assert_eq!(storage::list_backups(&dir.file("todos.json")).len(), 1);
+        
+        let saved = Workspaces::read(&path).unwrap();
+        assert_eq!(saved.workspaces, workspaces.workspaces);
+        assert_eq!(saved.active, DEFAULT_WORKSPACE_ID);
+    }
+    
+    #[test]
+    fn failed_delete_keeps_workspace() {
+        let dir = TestDir::new();
+        let path = dir.file("workspaces.json");
+        let mut workspaces = two_workspaces();
+        // 任务文件位置是非空目录时删除会失败
+        fs::create_dir(dir.file("todos-work.json")).unwrap();
+        fs::write(dir.file("todos-work.json").join("keep"), b"work").unwrap();
+        
+        assert!(workspaces.delete_at(&path, "work").is_err());
+        
+        assert_eq!(workspaces.workspaces.len(), 2);
+        assert_eq!(workspaces.active, "work");
+        assert_eq!(workspaces.active().file_name(), "todos-work.json");
+        assert!(!path.exists());
+    }
+    
+    #[test]
+    fn delete_keeps_last_workspace() {
+        let dir = TestDir::new();
+        let path = dir.file("workspaces.json");
+        let mut workspaces = Workspaces::default();
+        assert!(workspaces.delete_at(&path, DEFAULT_WORKSPACE_ID).is_err());
+        assert_eq!(workspaces.workspaces.len(), 1);
+        
+        let mut workspaces = two_workspaces();
+        assert!(workspaces.delete_at(&path, "missing").is_err());
+        assert_eq!(workspaces.workspaces.len(), 2);
+        assert!(!path.exists());
+    }
+}